@@ -0,0 +1,19 @@
+-- Admin roles: owner, manager, expert, read_only
+-- Existing accounts had full access, so they are promoted to owner.
+ALTER TABLE admin ADD COLUMN IF NOT EXISTS role VARCHAR(50) NOT NULL DEFAULT 'owner';
+ALTER TABLE admin ALTER COLUMN role SET DEFAULT 'read_only';
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_constraint
+        WHERE conname = 'check_admin_role_valid'
+    ) THEN
+        ALTER TABLE admin
+            ADD CONSTRAINT check_admin_role_valid
+            CHECK (role IN ('owner', 'manager', 'expert', 'read_only'));
+    END IF;
+END;
+$$;
+
+CREATE INDEX IF NOT EXISTS idx_admin_role ON admin(role);
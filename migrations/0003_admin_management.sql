@@ -0,0 +1,5 @@
+-- Admin account lifecycle: activation flag and last successful login
+ALTER TABLE admin ADD COLUMN IF NOT EXISTS is_active BOOLEAN NOT NULL DEFAULT TRUE;
+ALTER TABLE admin ADD COLUMN IF NOT EXISTS last_login_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_admin_is_active ON admin(is_active);
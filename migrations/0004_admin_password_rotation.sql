@@ -0,0 +1,3 @@
+-- Password rotation state for admin accounts
+ALTER TABLE admin ADD COLUMN IF NOT EXISTS password_changed_at TIMESTAMPTZ;
+ALTER TABLE admin ADD COLUMN IF NOT EXISTS must_change_password BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,17 @@
+-- TOTP two-factor authentication (RFC 6238)
+ALTER TABLE admin ADD COLUMN IF NOT EXISTS totp_secret VARCHAR(64);
+ALTER TABLE admin ADD COLUMN IF NOT EXISTS totp_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+-- Last accepted time step, prevents replay of a code within its window
+ALTER TABLE admin ADD COLUMN IF NOT EXISTS totp_last_step BIGINT;
+
+-- One-time recovery codes, stored as SHA-256 hashes
+CREATE TABLE IF NOT EXISTS admin_recovery_codes (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    admin_id UUID NOT NULL REFERENCES admin(id) ON DELETE CASCADE,
+    code_hash VARCHAR(64) NOT NULL,
+    used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_recovery_codes_admin_id ON admin_recovery_codes(admin_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_admin_recovery_codes_hash ON admin_recovery_codes(admin_id, code_hash);
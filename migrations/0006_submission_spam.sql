@@ -0,0 +1,8 @@
+-- Anti-spam: suspected spam is kept with a dedicated status instead of being dropped
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS spam_score INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS spam_reasons TEXT[] NOT NULL DEFAULT '{}';
+
+ALTER TABLE submissions DROP CONSTRAINT IF EXISTS check_status_valid;
+ALTER TABLE submissions
+    ADD CONSTRAINT check_status_valid
+    CHECK (status IN ('new', 'viewed', 'in_progress', 'completed', 'rejected', 'spam'));
@@ -0,0 +1,5 @@
+-- Assignment of submissions to experts
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS assigned_at TIMESTAMPTZ;
+
+-- Expert workload is counted over open submissions
+CREATE INDEX IF NOT EXISTS idx_submissions_admin_status ON submissions(admin_id, status) WHERE admin_id IS NOT NULL;
@@ -0,0 +1,29 @@
+-- Quotes: price is stored in minor units (kopecks, cents) together with its currency
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS currency VARCHAR(3) NOT NULL DEFAULT 'RUB';
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS quoted_at TIMESTAMPTZ;
+
+ALTER TABLE submissions DROP CONSTRAINT IF EXISTS check_submissions_currency_valid;
+ALTER TABLE submissions
+    ADD CONSTRAINT check_submissions_currency_valid
+    CHECK (currency IN ('RUB', 'USD', 'EUR'));
+
+-- Every quote change is kept with the admin who made it
+CREATE TABLE IF NOT EXISTS submission_quotes (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    submission_id UUID NOT NULL REFERENCES submissions(submission_id) ON DELETE CASCADE,
+    admin_id UUID REFERENCES admin(id) ON DELETE SET NULL,
+    price BIGINT NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    previous_price BIGINT,
+    previous_currency VARCHAR(3),
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE submission_quotes DROP CONSTRAINT IF EXISTS check_submission_quotes_price_nonnegative;
+ALTER TABLE submission_quotes
+    ADD CONSTRAINT check_submission_quotes_price_nonnegative
+    CHECK (price >= 0);
+
+CREATE INDEX IF NOT EXISTS idx_submission_quotes_submission_created ON submission_quotes(submission_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_submissions_quoted_at ON submissions(quoted_at) WHERE quoted_at IS NOT NULL;
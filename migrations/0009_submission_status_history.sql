@@ -0,0 +1,14 @@
+-- Audit trail of submission status changes
+CREATE TABLE IF NOT EXISTS submission_status_history (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    submission_id UUID NOT NULL REFERENCES submissions(submission_id) ON DELETE CASCADE,
+    from_status VARCHAR(50) NOT NULL,
+    to_status VARCHAR(50) NOT NULL,
+    -- NULL when the admin account was deleted
+    admin_id UUID REFERENCES admin(id) ON DELETE SET NULL,
+    reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_submission_status_history_submission_created
+    ON submission_status_history(submission_id, created_at DESC);
@@ -0,0 +1,42 @@
+-- Reply threads, editing and soft deletion of admin comments
+ALTER TABLE admin_comments
+    ADD COLUMN IF NOT EXISTS parent_id UUID REFERENCES admin_comments(id) ON DELETE CASCADE,
+    ADD COLUMN IF NOT EXISTS edited_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+-- Top-level comments of a submission are paginated, replies are loaded per thread
+CREATE INDEX IF NOT EXISTS idx_admin_comments_submission_threads
+    ON admin_comments(submission_id, created_at DESC) WHERE parent_id IS NULL;
+CREATE INDEX IF NOT EXISTS idx_admin_comments_parent_id
+    ON admin_comments(parent_id, created_at) WHERE parent_id IS NOT NULL;
+
+-- Previous versions of edited comments
+CREATE TABLE IF NOT EXISTS admin_comment_edits (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    comment_id UUID NOT NULL REFERENCES admin_comments(id) ON DELETE CASCADE,
+    previous_comment TEXT NOT NULL,
+    edited_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_comment_edits_comment_edited
+    ON admin_comment_edits(comment_id, edited_at DESC);
+
+-- Per-admin notifications, e.g. @username mentions in comments
+CREATE TABLE IF NOT EXISTS notifications (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    admin_id UUID NOT NULL REFERENCES admin(id) ON DELETE CASCADE,
+    kind VARCHAR(50) NOT NULL,
+    submission_id UUID REFERENCES submissions(submission_id) ON DELETE CASCADE,
+    comment_id UUID REFERENCES admin_comments(id) ON DELETE CASCADE,
+    -- Admin whose action produced the notification; NULL once the account is deleted
+    actor_id UUID REFERENCES admin(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    read_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_admin_created
+    ON notifications(admin_id, created_at DESC);
+
+-- Editing a comment must not notify the same admin about the same mention twice
+CREATE UNIQUE INDEX IF NOT EXISTS idx_notifications_comment_mention
+    ON notifications(admin_id, comment_id) WHERE kind = 'mention';
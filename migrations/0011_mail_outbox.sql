@@ -0,0 +1,38 @@
+-- Email address and on-duty flag for new submission notifications
+ALTER TABLE admin
+    ADD COLUMN IF NOT EXISTS email VARCHAR(255),
+    ADD COLUMN IF NOT EXISTS on_duty BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Outgoing email, written in the same transaction as the event that produced it
+-- and delivered by a background worker
+CREATE TABLE IF NOT EXISTS outbox (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    recipient VARCHAR(255) NOT NULL,
+    subject TEXT NOT NULL,
+    body TEXT NOT NULL,
+    submission_id UUID REFERENCES submissions(submission_id) ON DELETE SET NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Set while a worker is sending the message, so other instances skip it
+    locked_until TIMESTAMPTZ,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    sent_at TIMESTAMPTZ
+);
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_constraint
+        WHERE conname = 'check_outbox_status_valid'
+    ) THEN
+        ALTER TABLE outbox
+            ADD CONSTRAINT check_outbox_status_valid
+            CHECK (status IN ('pending', 'sent', 'failed'));
+    END IF;
+END;
+$$;
+
+CREATE INDEX IF NOT EXISTS idx_outbox_pending
+    ON outbox(next_attempt_at) WHERE status = 'pending';
@@ -0,0 +1,26 @@
+-- Telegram account linked to an admin, used to authorize bot button presses
+ALTER TABLE admin
+    ADD COLUMN IF NOT EXISTS telegram_user_id BIGINT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_admin_telegram_user_id
+    ON admin(telegram_user_id) WHERE telegram_user_id IS NOT NULL;
+
+-- The outbox also carries Telegram posts: recipient is the chat id, no subject
+ALTER TABLE outbox
+    ADD COLUMN IF NOT EXISTS channel VARCHAR(20) NOT NULL DEFAULT 'email';
+
+ALTER TABLE outbox
+    ALTER COLUMN subject DROP NOT NULL;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_constraint
+        WHERE conname = 'check_outbox_channel_valid'
+    ) THEN
+        ALTER TABLE outbox
+            ADD CONSTRAINT check_outbox_channel_valid
+            CHECK (channel IN ('email', 'telegram'));
+    END IF;
+END;
+$$;
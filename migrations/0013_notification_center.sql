@@ -0,0 +1,39 @@
+-- Due date of a submission; the assignee is notified when it approaches
+ALTER TABLE submissions
+    ADD COLUMN IF NOT EXISTS due_at TIMESTAMPTZ,
+    -- Set once the deadline notification is created, reset when the due date or assignee changes
+    ADD COLUMN IF NOT EXISTS deadline_notified_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_submissions_due_at_pending
+    ON submissions(due_at) WHERE due_at IS NOT NULL AND deadline_notified_at IS NULL;
+
+-- Every notification is recorded; `inbox` is false when the admin turned the inbox off
+-- for this kind, so the row only keeps mentions from being delivered twice
+ALTER TABLE notifications
+    ADD COLUMN IF NOT EXISTS inbox BOOLEAN NOT NULL DEFAULT TRUE,
+    ADD COLUMN IF NOT EXISTS message TEXT;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_constraint
+        WHERE conname = 'check_notification_kind_valid'
+    ) THEN
+        ALTER TABLE notifications
+            ADD CONSTRAINT check_notification_kind_valid
+            CHECK (kind IN ('assignment', 'mention', 'deadline', 'status_change'));
+    END IF;
+END;
+$$;
+
+CREATE INDEX IF NOT EXISTS idx_notifications_admin_unread
+    ON notifications(admin_id) WHERE inbox AND read_at IS NULL;
+
+-- Per-admin delivery settings; a missing row means inbox only
+CREATE TABLE IF NOT EXISTS notification_preferences (
+    admin_id UUID NOT NULL REFERENCES admin(id) ON DELETE CASCADE,
+    kind VARCHAR(50) NOT NULL,
+    inbox BOOLEAN NOT NULL DEFAULT TRUE,
+    email BOOLEAN NOT NULL DEFAULT FALSE,
+    PRIMARY KEY (admin_id, kind)
+);
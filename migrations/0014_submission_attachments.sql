@@ -0,0 +1,38 @@
+-- Attachment contents, stored once per SHA-256 digest under `storage_key`
+CREATE TABLE IF NOT EXISTS stored_files (
+    sha256 CHAR(64) PRIMARY KEY,
+    size_bytes BIGINT NOT NULL,
+    -- Detected from the file contents, not taken from the upload
+    content_type VARCHAR(100) NOT NULL,
+    storage_key TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Files attached to a submission by the client with the contact form or by an admin
+CREATE TABLE IF NOT EXISTS submission_attachments (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    submission_id UUID NOT NULL REFERENCES submissions(submission_id) ON DELETE CASCADE,
+    sha256 CHAR(64) NOT NULL REFERENCES stored_files(sha256),
+    file_name VARCHAR(255) NOT NULL,
+    source VARCHAR(10) NOT NULL,
+    -- NULL for client uploads and once the admin account is deleted
+    uploaded_by UUID REFERENCES admin(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_constraint
+        WHERE conname = 'check_attachment_source_valid'
+    ) THEN
+        ALTER TABLE submission_attachments
+            ADD CONSTRAINT check_attachment_source_valid
+            CHECK (source IN ('client', 'admin'));
+    END IF;
+END;
+$$;
+
+-- The same file attached to a submission twice is kept once
+CREATE UNIQUE INDEX IF NOT EXISTS idx_submission_attachments_file
+    ON submission_attachments(submission_id, sha256);
@@ -0,0 +1,7 @@
+-- Last time an upload reused this content. The cleanup worker removes content
+-- that no attachment references only after a grace period since this moment
+ALTER TABLE stored_files
+    ADD COLUMN IF NOT EXISTS last_used_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+CREATE INDEX IF NOT EXISTS idx_stored_files_last_used_at
+    ON stored_files(last_used_at);
@@ -0,0 +1,12 @@
+-- Cursor pagination compares the whole sort key, including the submission_id
+-- tie-breaker, so the indexes cover it in the same order as ORDER BY.
+-- A backward scan serves the ascending direction
+CREATE INDEX IF NOT EXISTS idx_submissions_created_at_id
+    ON submissions(created_at DESC, submission_id DESC);
+
+CREATE INDEX IF NOT EXISTS idx_submissions_status_created_at_id
+    ON submissions(status DESC, created_at DESC, submission_id DESC);
+
+-- Superseded by the indexes above, which share their leading columns
+DROP INDEX IF EXISTS idx_submissions_created_at;
+DROP INDEX IF EXISTS idx_submissions_status_created_at;
@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+use crate::routers::admin::auth::ClientInfo;
+use crate::state::AppState;
+
+/// Действие администратора, записываемое в `admin_panel_log.action`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    LoginSuccess,
+    LoginFailure,
+    Logout,
+    PasswordChange,
+    TwoFactorEnable,
+    TwoFactorDisable,
+    SessionRevoke,
+    StatusChange,
+    Comment,
+    CommentEdit,
+    CommentDelete,
+    AttachmentUpload,
+    PriceChange,
+    AssignmentChange,
+    DueDateChange,
+    AdminCreate,
+    AdminActiveChange,
+    AdminRoleChange,
+    AdminPasswordReset,
+    AdminMailChange,
+    AdminTelegramChange,
+    AdminDelete,
+}
+
+impl AuditAction {
+    pub const ALL: [AuditAction; 22] = [
+        AuditAction::LoginSuccess,
+        AuditAction::LoginFailure,
+        AuditAction::Logout,
+        AuditAction::PasswordChange,
+        AuditAction::TwoFactorEnable,
+        AuditAction::TwoFactorDisable,
+        AuditAction::SessionRevoke,
+        AuditAction::StatusChange,
+        AuditAction::Comment,
+        AuditAction::CommentEdit,
+        AuditAction::CommentDelete,
+        AuditAction::AttachmentUpload,
+        AuditAction::PriceChange,
+        AuditAction::AssignmentChange,
+        AuditAction::DueDateChange,
+        AuditAction::AdminCreate,
+        AuditAction::AdminActiveChange,
+        AuditAction::AdminRoleChange,
+        AuditAction::AdminPasswordReset,
+        AuditAction::AdminMailChange,
+        AuditAction::AdminTelegramChange,
+        AuditAction::AdminDelete,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::LoginSuccess => "login_success",
+            AuditAction::LoginFailure => "login_failure",
+            AuditAction::Logout => "logout",
+            AuditAction::PasswordChange => "password_change",
+            AuditAction::TwoFactorEnable => "two_factor_enable",
+            AuditAction::TwoFactorDisable => "two_factor_disable",
+            AuditAction::SessionRevoke => "session_revoke",
+            AuditAction::StatusChange => "status_change",
+            AuditAction::Comment => "comment",
+            AuditAction::CommentEdit => "comment_edit",
+            AuditAction::CommentDelete => "comment_delete",
+            AuditAction::AttachmentUpload => "attachment_upload",
+            AuditAction::PriceChange => "price_change",
+            AuditAction::AssignmentChange => "assignment_change",
+            AuditAction::DueDateChange => "due_date_change",
+            AuditAction::AdminCreate => "admin_create",
+            AuditAction::AdminActiveChange => "admin_active_change",
+            AuditAction::AdminRoleChange => "admin_role_change",
+            AuditAction::AdminPasswordReset => "admin_password_reset",
+            AuditAction::AdminMailChange => "admin_mail_change",
+            AuditAction::AdminTelegramChange => "admin_telegram_change",
+            AuditAction::AdminDelete => "admin_delete",
+        }
+    }
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for AuditAction {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        AuditAction::ALL
+            .into_iter()
+            .find(|action| action.as_str() == value)
+            .ok_or_else(|| format!("Unknown audit action: {}", value))
+    }
+}
+
+/// Запись действия в журнал `admin_panel_log`. Сбой записи не отменяет
+/// само действие и попадает только в лог приложения
+pub async fn record(
+    state: &AppState,
+    client: &ClientInfo,
+    username: &str,
+    action: AuditAction,
+    metadata: Value,
+) {
+    let result = state
+        .db_postgres
+        .insert_audit_log(
+            username,
+            client.ip.parse().ok(),
+            client.user_agent.as_deref(),
+            action.as_str(),
+            &metadata.to_string(),
+        )
+        .await;
+
+    if let Err(e) = result {
+        tracing::warn!(action = %action, username, error = %e, "Failed to write audit log entry");
+    }
+}
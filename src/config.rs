@@ -1,5 +1,7 @@
 use dotenvy::dotenv;
+use ipnet::IpNet;
 use std::env;
+use std::net::IpAddr;
 
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -8,7 +10,258 @@ pub struct Config {
     pub server_address: String,
     pub jwt_secret: String,
     pub admin_login: String,
-    pub admin_password: String
+    pub admin_password: String,
+    /// Прокси, которым разрешено передавать адрес клиента в X-Forwarded-For
+    pub trusted_proxies: Vec<IpNet>,
+    pub auto_assign: AssignmentStrategy,
+    /// `None`, если SMTP_HOST не задан: почтовые уведомления отключены
+    pub mail: Option<MailConfig>,
+    /// `None`, если TELEGRAM_BOT_TOKEN не задан: бот отключен
+    pub telegram: Option<TelegramConfig>,
+    /// Где хранятся файлы вложений
+    pub storage: StorageConfig,
+    pub upload_limits: UploadLimits,
+}
+
+/// Автоматическое назначение новых заявок экспертам
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssignmentStrategy {
+    Off,
+    /// По очереди: эксперт, которому дольше всех ничего не назначали
+    RoundRobin,
+    /// Эксперт с наименьшим числом открытых заявок
+    LeastLoaded,
+}
+
+impl std::str::FromStr for AssignmentStrategy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "off" | "none" | "" => Ok(Self::Off),
+            "round_robin" => Ok(Self::RoundRobin),
+            "least_loaded" => Ok(Self::LeastLoaded),
+            other => Err(format!("Invalid AUTO_ASSIGN value: {}", other)),
+        }
+    }
+}
+
+/// Настройки SMTP и шаблонов писем
+#[derive(Debug, Clone)]
+pub struct MailConfig {
+    pub host: String,
+    pub port: u16,
+    pub tls: SmtpTls,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Адрес отправителя: `Экспертиза <noreply@example.com>`
+    pub from: String,
+    pub templates_dir: String,
+}
+
+/// Режим шифрования соединения с SMTP-сервером
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpTls {
+    /// Без шифрования, например для локального SMTP-приемника
+    None,
+    /// STARTTLS после подключения, обычно порт 587
+    StartTls,
+    /// TLS с самого начала соединения, обычно порт 465
+    Tls,
+}
+
+impl SmtpTls {
+    fn default_port(self) -> u16 {
+        match self {
+            SmtpTls::None => 25,
+            SmtpTls::StartTls => 587,
+            SmtpTls::Tls => 465,
+        }
+    }
+}
+
+impl std::str::FromStr for SmtpTls {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "none" | "off" => Ok(Self::None),
+            "starttls" | "" => Ok(Self::StartTls),
+            "tls" | "ssl" => Ok(Self::Tls),
+            other => Err(format!("Invalid SMTP_TLS value: {}", other)),
+        }
+    }
+}
+
+impl MailConfig {
+    fn from_env() -> Result<Option<Self>, Box<dyn std::error::Error>> {
+        let Some(host) = env::var("SMTP_HOST").ok().filter(|host| !host.trim().is_empty()) else {
+            return Ok(None);
+        };
+
+        let tls: SmtpTls = env::var("SMTP_TLS")
+            .map(|value| value.parse())
+            .unwrap_or(Ok(SmtpTls::StartTls))?;
+
+        let port = match env::var("SMTP_PORT").ok().filter(|port| !port.trim().is_empty()) {
+            Some(port) => port.trim().parse().map_err(|_| format!("Invalid SMTP_PORT value: {}", port))?,
+            None => tls.default_port(),
+        };
+
+        let from = env::var("SMTP_FROM")
+            .map_err(|_| "SMTP_FROM must be set when SMTP_HOST is set")?;
+
+        Ok(Some(MailConfig {
+            host,
+            port,
+            tls,
+            username: env::var("SMTP_USERNAME").ok().filter(|v| !v.is_empty()),
+            password: env::var("SMTP_PASSWORD").ok().filter(|v| !v.is_empty()),
+            from,
+            templates_dir: env::var("MAIL_TEMPLATES_DIR")
+                .unwrap_or_else(|_| "templates/mail".into()),
+        }))
+    }
+}
+
+/// Настройки бота Telegram
+#[derive(Debug, Clone)]
+pub struct TelegramConfig {
+    pub bot_token: String,
+    /// Чат, в который публикуются новые заявки
+    pub chat_id: i64,
+    /// Секрет из заголовка `X-Telegram-Bot-Api-Secret-Token` вебхука
+    pub webhook_secret: String,
+    /// Адрес Bot API, для тестов подменяется локальной заглушкой
+    pub api_url: String,
+    /// Если задан, вебхук регистрируется при запуске
+    pub webhook_url: Option<String>,
+}
+
+impl TelegramConfig {
+    fn from_env() -> Result<Option<Self>, Box<dyn std::error::Error>> {
+        let Some(bot_token) = env::var("TELEGRAM_BOT_TOKEN").ok().filter(|token| !token.trim().is_empty()) else {
+            return Ok(None);
+        };
+
+        let chat_id = env::var("TELEGRAM_CHAT_ID")
+            .map_err(|_| "TELEGRAM_CHAT_ID must be set when TELEGRAM_BOT_TOKEN is set")?;
+        let chat_id = chat_id
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid TELEGRAM_CHAT_ID value: {}", chat_id))?;
+
+        let webhook_secret = env::var("TELEGRAM_WEBHOOK_SECRET")
+            .ok()
+            .filter(|secret| !secret.is_empty())
+            .ok_or("TELEGRAM_WEBHOOK_SECRET must be set when TELEGRAM_BOT_TOKEN is set")?;
+        // Ограничение Bot API для secret_token
+        if webhook_secret.len() > 256
+            || !webhook_secret.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err("TELEGRAM_WEBHOOK_SECRET must be 1-256 characters of A-Z, a-z, 0-9, _ and -".into());
+        }
+
+        Ok(Some(TelegramConfig {
+            bot_token: bot_token.trim().to_string(),
+            chat_id,
+            webhook_secret,
+            api_url: env::var("TELEGRAM_API_URL")
+                .ok()
+                .filter(|url| !url.trim().is_empty())
+                .unwrap_or_else(|| "https://api.telegram.org".into()),
+            webhook_url: env::var("TELEGRAM_WEBHOOK_URL").ok().filter(|url| !url.trim().is_empty()),
+        }))
+    }
+}
+
+/// Хранилище вложений: каталог на диске или S3-совместимый бакет
+#[derive(Debug, Clone)]
+pub enum StorageConfig {
+    Local { dir: String },
+    S3(S3Config),
+}
+
+/// Настройки S3-совместимого хранилища (AWS S3, MinIO и т. п.).
+/// Бакет адресуется в пути: `{endpoint}/{bucket}/{key}`
+#[derive(Debug, Clone)]
+pub struct S3Config {
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+}
+
+impl StorageConfig {
+    fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
+        let backend = env::var("STORAGE_BACKEND").unwrap_or_default();
+        match backend.trim().to_lowercase().as_str() {
+            "local" | "" => Ok(StorageConfig::Local {
+                dir: env::var("STORAGE_DIR")
+                    .ok()
+                    .filter(|dir| !dir.trim().is_empty())
+                    .unwrap_or_else(|| "uploads".into()),
+            }),
+            "s3" => {
+                let required = |name: &str| {
+                    env::var(name)
+                        .ok()
+                        .filter(|value| !value.trim().is_empty())
+                        .ok_or_else(|| format!("{} must be set when STORAGE_BACKEND=s3", name))
+                };
+                Ok(StorageConfig::S3(S3Config {
+                    endpoint: required("S3_ENDPOINT")?.trim_end_matches('/').to_string(),
+                    bucket: required("S3_BUCKET")?,
+                    region: env::var("S3_REGION")
+                        .ok()
+                        .filter(|region| !region.trim().is_empty())
+                        .unwrap_or_else(|| "us-east-1".into()),
+                    access_key_id: required("S3_ACCESS_KEY_ID")?,
+                    secret_access_key: required("S3_SECRET_ACCESS_KEY")?,
+                }))
+            }
+            other => Err(format!("Invalid STORAGE_BACKEND value: {}", other).into()),
+        }
+    }
+}
+
+/// Ограничения на файлы, загружаемые с заявкой
+#[derive(Debug, Clone, Copy)]
+pub struct UploadLimits {
+    pub max_file_bytes: usize,
+    pub max_files: usize,
+}
+
+impl UploadLimits {
+    /// Запас на текстовые поля формы и заголовки multipart
+    const FORM_OVERHEAD_BYTES: usize = 1024 * 1024;
+
+    /// Предельный размер тела запроса с файлами
+    pub fn max_request_bytes(&self) -> usize {
+        self.max_file_bytes
+            .saturating_mul(self.max_files)
+            .saturating_add(Self::FORM_OVERHEAD_BYTES)
+    }
+
+    fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
+        let number = |name: &str, default: usize| -> Result<usize, String> {
+            match env::var(name).ok().filter(|value| !value.trim().is_empty()) {
+                Some(value) => value
+                    .trim()
+                    .parse()
+                    .ok()
+                    .filter(|value| *value > 0)
+                    .ok_or_else(|| format!("Invalid {} value: {}", name, value)),
+                None => Ok(default),
+            }
+        };
+
+        Ok(UploadLimits {
+            max_file_bytes: number("UPLOAD_MAX_FILE_SIZE_MB", 20)?.saturating_mul(1024 * 1024),
+            max_files: number("UPLOAD_MAX_FILES", 4)?,
+        })
+    }
 }
 
 impl Config {
@@ -28,6 +281,19 @@ impl Config {
 
         let admin_password = env::var("ADMIN_PASSWORD")?;
         let admin_login = env::var("ADMIN_LOGIN")?;
+
+        let trusted_proxies = env::var("TRUSTED_PROXIES")
+            .map(|value| parse_trusted_proxies(&value))
+            .unwrap_or_else(|_| Ok(Vec::new()))?;
+
+        let auto_assign = env::var("AUTO_ASSIGN")
+            .map(|value| value.parse())
+            .unwrap_or(Ok(AssignmentStrategy::Off))?;
+
+        let mail = MailConfig::from_env()?;
+        let telegram = TelegramConfig::from_env()?;
+        let storage = StorageConfig::from_env()?;
+        let upload_limits = UploadLimits::from_env()?;
         
         Ok(Config {
             database_url,
@@ -35,7 +301,28 @@ impl Config {
             server_address,
             jwt_secret,
             admin_login,
-            admin_password
+            admin_password,
+            trusted_proxies,
+            auto_assign,
+            mail,
+            telegram,
+            storage,
+            upload_limits,
         })
     }
+}
+
+/// Список адресов и подсетей через запятую: `10.0.0.0/8, 127.0.0.1`
+fn parse_trusted_proxies(value: &str) -> Result<Vec<IpNet>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            entry
+                .parse::<IpNet>()
+                .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from))
+                .map_err(|_| format!("Invalid TRUSTED_PROXIES entry: {}", entry))
+        })
+        .collect()
 }
\ No newline at end of file
@@ -1,11 +1,24 @@
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use axum_extra::extract::cookie::CookieJar;
+use chrono::Utc;
 use rand::rng;
 use redis::AsyncCommands;
 use rand::{distr::Alphanumeric, Rng};
 use serde::Serialize;
 
 use crate::error::AppError;
+use crate::state::AppState;
 
 const CSRF_TOKEN_PREFIX: &str = "csrf";
+const CSRF_HEADER_NAME: &str = "X-CSRF-Token";
+const CSRF_SESSION_COOKIE_NAME: &str = "session_id";
+/// Время жизни токена в секундах
+pub const CSRF_TOKEN_TTL: usize = 900;
 
 #[derive(Serialize)]
 pub struct CsrfTokenResponse {
@@ -31,25 +44,78 @@ impl CsrfService {
             .map(char::from)
             .collect();
 
+        // Время выдачи нужно антиспам-проверке формы
         let key = format!("{}:{}:{}", CSRF_TOKEN_PREFIX, session_id, token);
-        let _: () = self.redis_con.set_ex(&key, b"1", self.ttl_seconds as u64).await?;
+        let _: () = self.redis_con.set_ex(&key, Utc::now().timestamp(), self.ttl_seconds as u64).await?;
 
         Ok(token.as_str().to_string())
     }
 
-    /// Проверка и немедленное удаление CSRF-токена
-    pub async fn validate_csrf_token(&mut self, session_id: &str, token: &str) -> Result<(), AppError> {
+    /// Проверка и немедленное удаление CSRF-токена: каждый токен действует
+    /// на один запрос. Возвращает время выдачи (unix time)
+    pub async fn validate_csrf_token(&mut self, session_id: &str, token: &str) -> Result<i64, AppError> {
         let key = format!("{}:{}:{}", CSRF_TOKEN_PREFIX, session_id, token);
-        let exists: bool = self.redis_con.exists(&key).await
+        let issued_at: Option<i64> = self.redis_con.get_del(&key).await
             .map_err(AppError::from)?;
 
-        if !exists {
-            return Err(AppError::unauthorized("Invalid or expired CSRF token"));
-        }
+        issued_at.ok_or_else(|| AppError::csrf("CSRF_TOKEN_INVALID", "Invalid or expired CSRF token"))
+    }
+}
 
-        let _: () = self.redis_con.del(&key).await
-            .map_err(AppError::from)?;
+/// Время выдачи проверенного CSRF-токена, доступно обработчику
+/// через расширения запроса
+#[derive(Debug, Clone, Copy)]
+pub struct CsrfIssuedAt(pub i64);
+
+/// Проверка CSRF для изменяющих запросов: токен из заголовка `X-CSRF-Token`
+/// должен быть выдан для cookie `session_id`
+pub async fn csrf_middleware(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    if matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return Ok(next.run(request).await);
+    }
+
+    let token = request
+        .headers()
+        .get(CSRF_HEADER_NAME)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| AppError::csrf("CSRF_TOKEN_MISSING", "Missing CSRF token"))?;
+
+    let session_id = jar
+        .get(CSRF_SESSION_COOKIE_NAME)
+        .map(|cookie| cookie.value())
+        .ok_or_else(|| AppError::csrf("CSRF_TOKEN_MISSING", "Missing session_id cookie"))?;
+
+    let conn = state.db_redis.get_connection().await?;
+    let issued_at = CsrfService::new(conn, CSRF_TOKEN_TTL)
+        .validate_csrf_token(session_id, token)
+        .await?;
+
+    request.extensions_mut().insert(CsrfIssuedAt(issued_at));
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::redis::redis_interface::tests::test_redis;
+
+    #[tokio::test]
+    async fn token_is_accepted_once() {
+        let Some(redis) = test_redis().await else { return };
+        let mut csrf = CsrfService::new(redis.get_connection().await.unwrap(), CSRF_TOKEN_TTL);
+        let session_id = uuid::Uuid::new_v4().to_string();
+
+        let token = csrf.create_token(session_id.clone()).await.unwrap();
+        let issued_at = csrf.validate_csrf_token(&session_id, &token).await.unwrap();
+        assert!(issued_at <= Utc::now().timestamp());
 
-        Ok(())
+        let reused = csrf.validate_csrf_token(&session_id, &token).await;
+        assert!(matches!(reused, Err(AppError::Csrf { .. })));
     }
 }
@@ -1,15 +1,54 @@
+use sqlx::error::ErrorKind;
 use thiserror;
 
 #[derive(Debug, thiserror::Error)]
 pub enum DatabaseError {
     #[error("Database error: {0}")]
-    Database(#[from] sqlx::Error),
-    
+    Database(sqlx::Error),
+
+    #[error("Unique constraint violation: {}", .constraint.as_deref().unwrap_or("unknown"))]
+    UniqueViolation { constraint: Option<String> },
+
+    #[error("Check constraint violation: {}", .constraint.as_deref().unwrap_or("unknown"))]
+    CheckViolation { constraint: Option<String> },
+
+    #[error("Foreign key violation: {}", .constraint.as_deref().unwrap_or("unknown"))]
+    ForeignKeyViolation { constraint: Option<String> },
+
+    #[error("Record not found")]
+    NotFound,
+
+    #[error("Timed out waiting for a database connection")]
+    PoolTimeout,
+
     #[error("Migration error: {0}")]
     Migration(#[from] sqlx::migrate::MigrateError),
-    
+
+    #[error("Password hashing error: {0}")]
+    Hashing(#[from] bcrypt::BcryptError),
+
     #[error("Invalid UUID format: {0}")]
     InvalidUuid(#[from] uuid::Error),
 }
 
-pub type Result<T> = std::result::Result<T, DatabaseError>;
\ No newline at end of file
+/// Ошибки sqlx раскладываются по видам, чтобы обработчики не разбирали текст ошибки
+impl From<sqlx::Error> for DatabaseError {
+    fn from(error: sqlx::Error) -> Self {
+        match &error {
+            sqlx::Error::RowNotFound => Self::NotFound,
+            sqlx::Error::PoolTimedOut => Self::PoolTimeout,
+            sqlx::Error::Database(db_error) => {
+                let constraint = db_error.constraint().map(str::to_string);
+                match db_error.kind() {
+                    ErrorKind::UniqueViolation => Self::UniqueViolation { constraint },
+                    ErrorKind::CheckViolation => Self::CheckViolation { constraint },
+                    ErrorKind::ForeignKeyViolation => Self::ForeignKeyViolation { constraint },
+                    _ => Self::Database(error),
+                }
+            }
+            _ => Self::Database(error),
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, DatabaseError>;
@@ -2,6 +2,17 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use data_encoding::BASE64URL_NOPAD;
+use crate::roles::AdminRole;
+use crate::workflow::SubmissionStatus;
+use crate::notifications::NotificationKind;
+
+/// Статусы, по которым считается текущая нагрузка эксперта
+pub const OPEN_SUBMISSION_STATUSES: &[&str] = &["new", "viewed", "in_progress"];
+/// Валюты оценки, совпадают с ограничением `check_submissions_currency_valid`
+pub const QUOTE_CURRENCIES: &[&str] = &["RUB", "USD", "EUR"];
+/// Максимальный размер страницы списка заявок
+pub const MAX_PAGE_SIZE: i64 = 100;
 
 #[derive(Debug, Serialize, Deserialize, Clone, FromRow)]
 pub struct Submission {
@@ -11,12 +22,27 @@ pub struct Submission {
     pub phone: Option<String>,
     pub message: String,
     pub created_at: chrono::DateTime<chrono::Utc>,
-    pub status: String,
+    #[sqlx(try_from = "String")]
+    pub status: SubmissionStatus,
+    pub spam_score: i32,
+    pub spam_reasons: Vec<String>,
+    /// Стоимость в минимальных единицах валюты (копейки, центы)
+    pub price: i64,
+    pub currency: String,
+    /// Время последней оценки, `None` — заявка еще не оценена
+    pub quoted_at: Option<DateTime<Utc>>,
+    pub admin_id: Option<Uuid>,
+    pub assigned_at: Option<DateTime<Utc>>,
+    /// Срок выполнения, за сутки до него исполнитель получает уведомление
+    pub due_at: Option<DateTime<Utc>>,
+    /// Логин назначенного администратора, заполняется запросами списка
+    #[sqlx(default)]
+    pub assignee_name: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
-pub struct PaginationResult {
-    pub data: Vec<Submission>,
+pub struct PaginationResult<T = Submission> {
+    pub data: Vec<T>,
     pub total_count: i64,
     pub page: i64,
     pub per_page: i64,
@@ -25,8 +51,8 @@ pub struct PaginationResult {
     pub has_prev: bool,
 }
 
-impl PaginationResult {
-    pub fn new(data: Vec<Submission>, total_count: i64, page: i64, per_page: i64) -> Self {
+impl<T> PaginationResult<T> {
+    pub fn new(data: Vec<T>, total_count: i64, page: i64, per_page: i64) -> Self {
         let total_pages = (total_count + per_page - 1) / per_page;
         Self {
             data,
@@ -41,12 +67,165 @@ impl PaginationResult {
 }
 
 
+/// Фильтры списка заявок. Незаданные поля выборку не ограничивают
+#[derive(Debug, Default)]
+pub struct SubmissionFilter {
+    /// Полнотекстовый запрос по имени и сообщению
+    pub query: Option<String>,
+    pub email: Option<String>,
+    /// Телефон в формате E.164
+    pub phone: Option<String>,
+    pub statuses: Option<Vec<String>>,
+    pub created_from: Option<DateTime<Utc>>,
+    /// Граница не включается
+    pub created_before: Option<DateTime<Utc>>,
+    pub assigned_to: Option<Uuid>,
+    pub price_min: Option<i64>,
+    pub price_max: Option<i64>,
+    /// Валюта цены: границы цены сравниваются только с оценками в ней
+    pub currency: Option<String>,
+    pub include_spam: bool,
+}
+
+impl SubmissionFilter {
+    /// Явно запрошенный статус spam показывается без отдельного флага
+    pub fn shows_spam(&self) -> bool {
+        self.include_spam
+            || self.statuses.as_ref().is_some_and(|s| s.iter().any(|status| status == "spam"))
+    }
+}
+
+/// Ключ сортировки, для которого поддерживается выборка по курсору
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeysetSort {
+    CreatedAt,
+    Status,
+}
+
+/// Как считать общее число заявок в режиме курсора
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CountMode {
+    #[default]
+    None,
+    Exact,
+    /// Оценка по плану запроса, не зависит от размера архива
+    Estimate,
+}
+
+/// Позиция в списке: ключ сортировки последней строки страницы.
+/// Клиенту отдается как непрозрачная base64url-строка
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubmissionCursor {
+    pub sort: KeysetSort,
+    pub descending: bool,
+    pub created_at: DateTime<Utc>,
+    pub submission_id: Uuid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+impl SubmissionCursor {
+    pub fn after(submission: &Submission, sort: KeysetSort, descending: bool) -> Self {
+        Self {
+            sort,
+            descending,
+            created_at: submission.created_at,
+            submission_id: submission.submission_id,
+            status: (sort == KeysetSort::Status).then(|| submission.status.to_string()),
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        BASE64URL_NOPAD.encode(&json)
+    }
+
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let json = BASE64URL_NOPAD.decode(cursor.as_bytes()).ok()?;
+        let cursor: Self = serde_json::from_slice(&json).ok()?;
+        // Курсор по статусу без статуса построить нельзя
+        (cursor.sort != KeysetSort::Status || cursor.status.is_some()).then_some(cursor)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CursorPage {
+    pub data: Vec<Submission>,
+    /// Курсор следующей страницы, `None` на последней
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_count: Option<i64>,
+    pub total_is_estimate: bool,
+}
+
 #[derive(Debug, Serialize)]
 pub struct DatabaseStats {
     pub total_submissions: i64,
     pub today_count: i64,
     pub this_week_count: i64,
     pub this_month_count: i64,
+    /// Суммы оценок по валютам
+    pub quotes: Vec<QuoteTotals>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct QuoteTotals {
+    pub currency: String,
+    pub quoted_count: i64,
+    pub total_price: i64,
+    pub average_price: i64,
+}
+
+/// Фильтры журнала действий администраторов
+#[derive(Debug, Default)]
+pub struct AuditLogFilter {
+    pub username: Option<String>,
+    pub action: Option<String>,
+    pub ip: Option<String>,
+    pub created_from: Option<DateTime<Utc>>,
+    /// Верхняя граница, не включается
+    pub created_before: Option<DateTime<Utc>>,
+}
+
+/// Запись журнала `admin_panel_log`
+#[derive(Debug, Serialize)]
+pub struct AuditLogEntry {
+    pub id: Uuid,
+    pub username: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub action: Option<String>,
+    pub metadata: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Запись истории смены статуса заявки
+#[derive(Debug, Serialize, FromRow)]
+pub struct StatusHistoryEntry {
+    pub id: Uuid,
+    pub from_status: String,
+    pub to_status: String,
+    /// `None`, если учетная запись администратора удалена
+    pub admin_name: Option<String>,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Запись истории оценок заявки
+#[derive(Debug, Serialize, FromRow)]
+pub struct QuoteHistoryEntry {
+    pub id: Uuid,
+    pub price: i64,
+    pub currency: String,
+    pub previous_price: Option<i64>,
+    pub previous_currency: Option<String>,
+    pub note: Option<String>,
+    /// `None`, если учетная запись администратора удалена
+    pub admin_name: Option<String>,
+    pub created_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -56,6 +235,16 @@ pub struct CreateSubmissionRequest {
     pub email: String,
     pub phone: Option<String>,
     pub message: String,
+    #[serde(default)]
+    pub spam: SpamAssessment,
+}
+
+/// Результат антиспам-проверки заявки
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct SpamAssessment {
+    pub score: i32,
+    pub reasons: Vec<String>,
+    pub is_spam: bool,
 }
 
 impl CreateSubmissionRequest {
@@ -68,20 +257,219 @@ impl CreateSubmissionRequest {
             phone: self.phone,
             message: self.message,
             created_at: now,
-            status: "new".to_string()
+            // Подозрительные заявки сохраняются, но скрыты из списка по умолчанию
+            status: if self.spam.is_spam { SubmissionStatus::Spam } else { SubmissionStatus::New },
+            spam_score: self.spam.score,
+            spam_reasons: self.spam.reasons,
+            price: 0,
+            currency: "RUB".to_string(),
+            quoted_at: None,
+            admin_id: None,
+            assigned_at: None,
+            due_at: None,
+            assignee_name: None,
         }
     }
 }
 
-#[derive(Debug, Serialize)]
+/// Получатель сообщения из outbox
+#[derive(Debug, Clone)]
+pub enum OutboxRecipient {
+    /// Активные дежурные администраторы с указанной почтой
+    OnDutyAdmins,
+    Email(String),
+    /// Чат Telegram, в который бот публикует заявки
+    TelegramChat(i64),
+}
+
+impl OutboxRecipient {
+    /// Значение колонки `outbox.channel`
+    pub fn channel(&self) -> &'static str {
+        match self {
+            OutboxRecipient::OnDutyAdmins | OutboxRecipient::Email(_) => OUTBOX_CHANNEL_EMAIL,
+            OutboxRecipient::TelegramChat(_) => OUTBOX_CHANNEL_TELEGRAM,
+        }
+    }
+}
+
+pub const OUTBOX_CHANNEL_EMAIL: &str = "email";
+pub const OUTBOX_CHANNEL_TELEGRAM: &str = "telegram";
+
+/// Сообщение, которое записывается в outbox вместе с заявкой
+#[derive(Debug, Clone)]
+pub struct OutboxEntry {
+    pub recipient: OutboxRecipient,
+    /// Тема письма, у сообщений Telegram отсутствует
+    pub subject: Option<String>,
+    pub body: String,
+}
+
+/// Сообщение, взятое из outbox на отправку
+#[derive(Debug, FromRow)]
+pub struct OutboxMessage {
+    pub id: Uuid,
+    pub channel: String,
+    pub recipient: String,
+    pub subject: Option<String>,
+    pub body: String,
+    pub submission_id: Option<Uuid>,
+    pub attempts: i32,
+}
+
+/// Максимальная длина комментария администратора
+pub const MAX_COMMENT_CHARS: usize = 5000;
+
+#[derive(Debug, Serialize, FromRow)]
 pub struct SubmissionComment {
     pub comment_id: Uuid,
-    pub comment: String,
-    pub created_at: DateTime<Utc>,
+    pub parent_id: Option<Uuid>,
+    /// `None` для удаленного комментария
+    pub comment: Option<String>,
     pub admin_name: String,
+    /// Комментарий оставлен текущим администратором и доступен ему для правки
+    pub is_own: bool,
+    pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
+/// Комментарий верхнего уровня с ответами на него
 #[derive(Debug, Serialize)]
-pub struct SubmissionCommentsRequest {
-    pub data: Vec<SubmissionComment>,
-}
\ No newline at end of file
+pub struct CommentThread {
+    #[serde(flatten)]
+    pub comment: SubmissionComment,
+    pub replies: Vec<SubmissionComment>,
+}
+
+/// Поля комментария, нужные для проверки прав перед изменением
+#[derive(Debug, FromRow)]
+pub struct CommentRecord {
+    pub id: Uuid,
+    pub submission_id: Uuid,
+    pub admin_id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// Предыдущая версия отредактированного комментария
+#[derive(Debug, Serialize, FromRow)]
+pub struct CommentEdit {
+    pub id: Uuid,
+    pub previous_comment: String,
+    pub edited_at: DateTime<Utc>,
+}
+
+/// Уведомление во входящих администратора
+#[derive(Debug, Serialize, FromRow)]
+pub struct Notification {
+    pub id: Uuid,
+    #[sqlx(try_from = "String")]
+    pub kind: NotificationKind,
+    pub submission_id: Option<Uuid>,
+    /// Имя клиента из заявки
+    pub submission_name: Option<String>,
+    pub comment_id: Option<Uuid>,
+    pub actor_name: Option<String>,
+    pub message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+/// Куда доставлять уведомления одного вида. Без записи — только во входящие
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct NotificationPreference {
+    #[sqlx(try_from = "String")]
+    pub kind: NotificationKind,
+    pub inbox: bool,
+    pub email: bool,
+}
+
+/// Письмо с уведомлением, уходит через outbox на `admin.email`
+#[derive(Debug, Clone)]
+pub struct NotificationEmail {
+    pub subject: String,
+    pub body: String,
+}
+
+/// Новое уведомление. Письмо есть, только если почта настроена
+#[derive(Debug, Clone)]
+pub struct NewNotification {
+    pub kind: NotificationKind,
+    pub submission_id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub message: String,
+    pub email: Option<NotificationEmail>,
+}
+
+/// Файл, уже записанный в хранилище и готовый к привязке к заявке
+#[derive(Debug, Clone)]
+pub struct NewAttachment {
+    pub sha256: String,
+    pub size_bytes: i64,
+    pub content_type: &'static str,
+    pub storage_key: String,
+    pub file_name: String,
+}
+
+/// Вложение заявки
+#[derive(Debug, Serialize, FromRow)]
+pub struct Attachment {
+    pub id: Uuid,
+    pub submission_id: Uuid,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+    /// `client` — приложено к форме на сайте, `admin` — загружено в панели
+    pub source: String,
+    pub uploaded_by_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip)]
+    pub storage_key: String,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AdminCredentials {
+    pub id: Uuid,
+    pub password: String,
+    #[sqlx(try_from = "String")]
+    pub role: AdminRole,
+    pub is_active: bool,
+    pub must_change_password: bool,
+    pub totp_enabled: bool,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AdminTotp {
+    pub totp_secret: Option<String>,
+    pub totp_enabled: bool,
+    pub totp_last_step: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Clone, FromRow)]
+pub struct AdminAccount {
+    pub id: Uuid,
+    pub username: String,
+    #[sqlx(try_from = "String")]
+    pub role: AdminRole,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_login_at: Option<DateTime<Utc>>,
+    pub password_changed_at: Option<DateTime<Utc>>,
+    pub must_change_password: bool,
+    pub totp_enabled: bool,
+    /// Адрес для почтовых уведомлений
+    pub email: Option<String>,
+    /// Дежурный получает письма о новых заявках
+    pub on_duty: bool,
+}
+
+/// Итог изменения доступа администратора: снятие роли владельца,
+/// деактивация или удаление
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessChange {
+    Applied,
+    NotFound,
+    /// Изменение оставило бы систему без активного владельца
+    LastOwner,
+}
@@ -1,8 +1,16 @@
 use super::error::{Result, DatabaseError};
 use super::models::*;
-use sqlx::{PgPool, Row};
+use sqlx::{postgres::PgArguments, query::QueryAs, PgConnection, PgPool, Postgres, Row};
+use chrono::{DateTime, Utc};
+use std::future::Future;
+use std::net::IpAddr;
 use uuid::Uuid;
-use tracing::info;
+use tracing::{info, warn};
+use crate::password::is_bcrypt_hash;
+use crate::roles::AdminRole;
+use crate::config::AssignmentStrategy;
+use crate::workflow::SubmissionStatus;
+use crate::notifications::NotificationKind;
 
 pub struct PostgresDatabase {
     pool: PgPool,
@@ -23,29 +31,79 @@ impl PostgresDatabase {
         sqlx::migrate!("./migrations")
             .run(&self.pool)
             .await
-            .map_err(|e| DatabaseError::Migration(e))?;
+            .map_err(DatabaseError::Migration)?;
+
+        // Пароль из окружения в открытом виде в таблицу не попадает
+        let (password_hash, must_change_password) = if is_bcrypt_hash(admin_password) {
+            (admin_password.clone(), false)
+        } else {
+            (bcrypt::hash(admin_password, bcrypt::DEFAULT_COST)?, true)
+        };
 
         sqlx::query(
         "
-            INSERT INTO public.admin(username, password)
-            VALUES ($1, $2)
+            INSERT INTO public.admin(username, password, role, must_change_password)
+            VALUES ($1, $2, 'owner', $3)
             ON CONFLICT (username) DO NOTHING;
             "
         )
         .bind(admin_login)
-        .bind(admin_password)
+        .bind(password_hash)
+        .bind(must_change_password)
         .execute(&self.pool)
         .await?;
+
+        self.hash_plaintext_passwords().await?;
+        Ok(())
+    }
+
+    /// Хэширует пароли, сохраненные в открытом виде до появления хэширования
+    /// при создании администратора, и требует их смены при следующем входе
+    async fn hash_plaintext_passwords(&self) -> Result<()> {
+        let rows = sqlx::query("SELECT id, username, password FROM admin")
+            .fetch_all(&self.pool)
+            .await?;
+
+        for row in rows {
+            let password: String = row.get("password");
+            if is_bcrypt_hash(&password) {
+                continue;
+            }
+
+            let id: Uuid = row.get("id");
+            let password_hash = bcrypt::hash(&password, bcrypt::DEFAULT_COST)?;
+            sqlx::query(
+                r#"
+                UPDATE admin
+                SET password = $1, must_change_password = TRUE
+                WHERE id = $2
+                "#
+            )
+            .bind(password_hash)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+            warn!("Plaintext password hashed for admin {}, password change required", row.get::<String, _>("username"));
+        }
         Ok(())
     }
     
-    pub async fn save_submission(&self, request: CreateSubmissionRequest) -> Result<()> {
+    /// Заявка и уведомления о ней сохраняются в одной транзакции: уведомление
+    /// уходит только о сохраненной заявке и не теряется при сбое доставки
+    pub async fn save_submission(
+        &self,
+        request: CreateSubmissionRequest,
+        outbox: &[OutboxEntry],
+        attachments: &[NewAttachment],
+    ) -> Result<()> {
         let submission = request.into_submission();
+        let mut tx = self.pool.begin().await?;
 
         sqlx::query(
             "
-            INSERT INTO submissions (submission_id, name, email, phone, message, created_at, status)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO submissions (submission_id, name, email, phone, message, created_at, status, spam_score, spam_reasons)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             "
         )
         .bind(submission.submission_id)
@@ -54,35 +112,93 @@ impl PostgresDatabase {
         .bind(submission.phone)
         .bind(submission.message)
         .bind(submission.created_at)
-        .bind(submission.status)
-        .execute(&self.pool)
+        .bind(submission.status.as_str())
+        .bind(submission.spam_score)
+        .bind(submission.spam_reasons)
+        .execute(&mut *tx)
         .await?;
 
+        for entry in outbox {
+            let recipient = match &entry.recipient {
+                OutboxRecipient::OnDutyAdmins => None,
+                OutboxRecipient::Email(address) => Some(address.clone()),
+                OutboxRecipient::TelegramChat(chat_id) => Some(chat_id.to_string()),
+            };
+
+            let query = match recipient {
+                Some(recipient) => sqlx::query(
+                    r#"
+                    INSERT INTO outbox (channel, subject, body, submission_id, recipient)
+                    VALUES ($1, $2, $3, $4, $5)
+                    "#
+                )
+                .bind(entry.recipient.channel())
+                .bind(&entry.subject)
+                .bind(&entry.body)
+                .bind(submission.submission_id)
+                .bind(recipient),
+                // Сообщение получает каждый дежурный
+                None => sqlx::query(
+                    r#"
+                    INSERT INTO outbox (channel, subject, body, submission_id, recipient)
+                    SELECT $1, $2, $3, $4, email FROM admin
+                    WHERE on_duty AND is_active AND email IS NOT NULL
+                    "#
+                )
+                .bind(entry.recipient.channel())
+                .bind(&entry.subject)
+                .bind(&entry.body)
+                .bind(submission.submission_id),
+            };
+            query.execute(&mut *tx).await?;
+        }
+
+        insert_attachments(&mut tx, submission.submission_id, None, attachments).await?;
+
+        tx.commit().await?;
         info!("Submission saved: {}", submission.submission_id);
         Ok(())
     }
 
+    /// Недавние заявки с тем же email: (с тем же текстом за сутки, любые за час)
+    pub async fn count_recent_submissions(&self, email: &str, message: &str) -> Result<(i64, i64)> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE message = $2) AS duplicates,
+                COUNT(*) FILTER (WHERE created_at >= NOW() - INTERVAL '1 hour') AS recent
+            FROM submissions
+            WHERE email = $1 AND created_at >= NOW() - INTERVAL '24 hours'
+            "#
+        )
+        .bind(email)
+        .bind(message)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok((row.get("duplicates"), row.get("recent")))
+    }
+
     pub async fn get_submissions_paginated(
         &self,
         page: i64,
         per_page: i64,
         sort_by: Option<&str>,
         sort_order: Option<&str>,
+        filter: &SubmissionFilter,
     ) -> Result<PaginationResult> {
         let page = page.max(1);
-        let per_page = per_page.clamp(1, 10);
-        let offset = (page - 1) * per_page;
-
-        let total_count_query = "SELECT COUNT(*) as count FROM submissions";
-        let total_count: i64 = sqlx::query(total_count_query)
-            .fetch_one(&self.pool)
-            .await?
-            .get("count");
+        let per_page = per_page.clamp(1, MAX_PAGE_SIZE);
+        let offset = (page - 1).saturating_mul(per_page);
 
+        let total_count = self.count_submissions(filter).await?;
 
         let sort_col = sort_by
             .map(|s| s.trim().to_lowercase())
-            .unwrap_or_else(|| "created_at".to_string());
+            .unwrap_or_else(|| {
+                // С поисковым запросом по умолчанию сортируем по релевантности
+                if filter.query.is_some() { "relevance" } else { "created_at" }.to_string()
+            });
 
         let sort_column = match sort_col.as_str() {
             "created_at" | "created" | "date" => "created_at",
@@ -90,6 +206,7 @@ impl PostgresDatabase {
             "email" => "email",
             "status" => "status",
             "submission_id" | "id" => "submission_id",
+            "relevance" | "rank" if filter.query.is_some() => "rank",
             _ => "created_at", // default
         };
 
@@ -104,13 +221,19 @@ impl PostgresDatabase {
         };
 
         let data_query = format!(
-            "SELECT * FROM submissions
-            ORDER BY {} {}
-            LIMIT $1 OFFSET $2",
-            sort_column, order_dir
+            "SELECT *, {},
+                COALESCE(ts_rank(
+                    to_tsvector('russian', name) || to_tsvector('russian', message),
+                    websearch_to_tsquery('russian', $3)
+                ), 0) AS rank
+            FROM submissions
+            WHERE {}
+            ORDER BY {} {}, created_at DESC, submission_id DESC
+            LIMIT $12 OFFSET $13",
+            ASSIGNEE_NAME, SUBMISSION_FILTER, sort_column, order_dir
         );
 
-        let submissions = sqlx::query_as::<_, Submission>(&data_query)
+        let submissions = bind_submission_filter(sqlx::query_as::<_, Submission>(&data_query), filter)
             .bind(per_page)
             .bind(offset)
             .fetch_all(&self.pool)
@@ -119,7 +242,180 @@ impl PostgresDatabase {
         Ok(PaginationResult::new(submissions, total_count, page, per_page))
     }
 
+    /// Выборка по курсору: вместо OFFSET условие на ключ сортировки последней
+    /// строки предыдущей страницы, поэтому глубокие страницы не медленнее первых.
+    /// Порядок стабилен за счет `submission_id` в конце ключа
+    pub async fn get_submissions_keyset(
+        &self,
+        limit: i64,
+        sort: KeysetSort,
+        descending: bool,
+        after: Option<&SubmissionCursor>,
+        count: CountMode,
+        filter: &SubmissionFilter,
+    ) -> Result<CursorPage> {
+        let limit = limit.clamp(1, MAX_PAGE_SIZE);
+        let (cmp, dir) = if descending { ("<", "DESC") } else { (">", "ASC") };
+
+        // Ключи совпадают с индексами (created_at, submission_id) и
+        // (status, created_at, submission_id) из миграции 0016
+        let (key, order_by) = match sort {
+            KeysetSort::CreatedAt => (
+                "(created_at, submission_id)",
+                format!("created_at {dir}, submission_id {dir}"),
+            ),
+            KeysetSort::Status => (
+                "(status, created_at, submission_id)",
+                format!("status {dir}, created_at {dir}, submission_id {dir}"),
+            ),
+        };
+        let cursor_values = match sort {
+            KeysetSort::CreatedAt => "($13, $14)",
+            KeysetSort::Status => "($15, $13, $14)",
+        };
+
+        // Первая страница и страница после курсора — отдельные запросы: условие
+        // вида `$13 IS NULL OR ...` планировщик не сводит к поиску по индексу
+        let cursor_condition = match after {
+            Some(_) => format!("AND {key} {cmp} {cursor_values}"),
+            None => String::new(),
+        };
+        let data_query = format!(
+            "SELECT *, {ASSIGNEE_NAME} FROM submissions
+            WHERE {SUBMISSION_FILTER}
+            {cursor_condition}
+            ORDER BY {order_by}
+            LIMIT $12"
+        );
+
+        // Лишняя строка показывает, есть ли следующая страница
+        let mut query = bind_submission_filter(sqlx::query_as::<_, Submission>(&data_query), filter)
+            .bind(limit + 1);
+        if let Some(cursor) = after {
+            query = query.bind(cursor.created_at).bind(cursor.submission_id);
+            if sort == KeysetSort::Status {
+                query = query.bind(cursor.status.as_deref());
+            }
+        }
+        let mut submissions = query.fetch_all(&self.pool).await?;
+
+        let has_more = submissions.len() as i64 > limit;
+        submissions.truncate(limit as usize);
+
+        let next_cursor = match submissions.last() {
+            Some(last) if has_more => Some(SubmissionCursor::after(last, sort, descending).encode()),
+            _ => None,
+        };
+
+        let total_count = match count {
+            CountMode::None => None,
+            CountMode::Exact => Some(self.count_submissions(filter).await?),
+            CountMode::Estimate => Some(self.estimate_submissions(filter).await?),
+        };
+
+        Ok(CursorPage {
+            data: submissions,
+            next_cursor,
+            has_more,
+            total_count,
+            total_is_estimate: count == CountMode::Estimate,
+        })
+    }
+
+    pub async fn count_submissions(&self, filter: &SubmissionFilter) -> Result<i64> {
+        let query = format!("SELECT COUNT(*) FROM submissions WHERE {}", SUBMISSION_FILTER);
+        let (count,) = bind_submission_filter(sqlx::query_as::<_, (i64,)>(&query), filter)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count)
+    }
+
+    /// Оценка числа строк по плану запроса: без полного прохода по таблице
+    pub async fn estimate_submissions(&self, filter: &SubmissionFilter) -> Result<i64> {
+        let query = format!("EXPLAIN SELECT 1 FROM submissions WHERE {}", SUBMISSION_FILTER);
+        let plan: Vec<(String,)> = bind_submission_filter(sqlx::query_as(&query), filter)
+            .fetch_all(&self.pool)
+            .await?;
+
+        // Первая строка плана: "Seq Scan on submissions  (cost=0.00..1.10 rows=42 width=4)"
+        let rows = plan
+            .first()
+            .and_then(|(line,)| line.split("rows=").nth(1))
+            .and_then(|rest| rest.split_whitespace().next())
+            .and_then(|rows| rows.parse().ok())
+            .unwrap_or(0);
+        Ok(rows)
+    }
+
+    /// Назначение заявки администратору, `None` снимает назначение.
+    /// Возвращает `false`, если заявка не найдена
+    pub async fn assign_submission(&self, submission_id: Uuid, admin_id: Option<Uuid>) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE submissions
+            SET admin_id = $2,
+                assigned_at = CASE WHEN $2::uuid IS NULL THEN NULL ELSE NOW() END,
+                deadline_notified_at = NULL
+            WHERE submission_id = $1
+            "#
+        )
+        .bind(submission_id)
+        .bind(admin_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Автоматическое назначение заявки активному эксперту. Выбор идет под
+    /// advisory-блокировкой, чтобы одновременные заявки не ушли одному эксперту
+    pub async fn auto_assign_submission(
+        &self,
+        submission_id: Uuid,
+        strategy: AssignmentStrategy,
+    ) -> Result<Option<Uuid>> {
+        let order_by = match strategy {
+            AssignmentStrategy::Off => return Ok(None),
+            AssignmentStrategy::RoundRobin => "last_assigned_at NULLS FIRST, a.id",
+            AssignmentStrategy::LeastLoaded => "open_count, last_assigned_at NULLS FIRST, a.id",
+        };
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('submission_auto_assign'))")
+            .execute(&mut *tx)
+            .await?;
+
+        let query = format!(
+            "SELECT a.id,
+                (SELECT COUNT(*) FROM submissions s
+                 WHERE s.admin_id = a.id AND s.status = ANY($1)) AS open_count,
+                (SELECT MAX(s.assigned_at) FROM submissions s WHERE s.admin_id = a.id) AS last_assigned_at
+            FROM admin a
+            WHERE a.role = 'expert' AND a.is_active
+            ORDER BY {}
+            LIMIT 1",
+            order_by
+        );
+        let expert: Option<Uuid> = sqlx::query(&query)
+            .bind(OPEN_SUBMISSION_STATUSES)
+            .fetch_optional(&mut *tx)
+            .await?
+            .map(|row| row.get("id"));
+
+        if let Some(expert_id) = expert {
+            sqlx::query(
+                "UPDATE submissions SET admin_id = $2, assigned_at = NOW(), deadline_notified_at = NULL
+                 WHERE submission_id = $1 AND admin_id IS NULL"
+            )
+            .bind(submission_id)
+            .bind(expert_id)
+            .execute(&mut *tx)
+            .await?;
+        }
 
+        tx.commit().await?;
+        Ok(expert)
+    }
 
     pub async fn get_statistics(&self) -> Result<DatabaseStats> {
         let stats = sqlx::query(
@@ -130,97 +426,1556 @@ impl PostgresDatabase {
                 COUNT(*) FILTER (WHERE created_at >= CURRENT_DATE - INTERVAL '7 days') as this_week_count,
                 COUNT(*) FILTER (WHERE created_at >= CURRENT_DATE - INTERVAL '30 days') as this_month_count
             FROM submissions
+            WHERE status <> 'spam'
             "#
         )
         .fetch_one(&self.pool)
         .await?;
 
+        // Суммы в разных валютах не складываются, поэтому считаются по отдельности
+        let quotes = sqlx::query_as::<_, QuoteTotals>(
+            r#"
+            SELECT
+                currency,
+                COUNT(*) AS quoted_count,
+                SUM(price)::bigint AS total_price,
+                ROUND(AVG(price))::bigint AS average_price
+            FROM submissions
+            WHERE quoted_at IS NOT NULL AND status <> 'spam'
+            GROUP BY currency
+            ORDER BY currency
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
         Ok(DatabaseStats {
             total_submissions: stats.get::<i64, _>("total_submissions"),
             today_count: stats.get::<i64, _>("today_count"),
             this_week_count: stats.get::<i64, _>("this_week_count"),
             this_month_count: stats.get::<i64, _>("this_month_count"),
+            quotes,
         })
     }
 
-    pub async fn update_submissions_status(
+    pub async fn get_submission(&self, submission_id: Uuid) -> Result<Option<Submission>> {
+        let query = format!("SELECT *, {ASSIGNEE_NAME} FROM submissions WHERE submission_id = $1");
+        let submission = sqlx::query_as::<_, Submission>(&query)
+            .bind(submission_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(submission)
+    }
+
+    /// Новая оценка заявки с записью в историю. Возвращает `false`, если заявка не найдена
+    pub async fn set_submission_quote(
         &self,
         submission_id: Uuid,
-        status: String,
-    ) -> Result<()> {  
-        let updated_submission = sqlx::query(
+        admin_id: Uuid,
+        price: i64,
+        currency: &str,
+        note: Option<&str>,
+    ) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        // Предыдущая оценка есть только у уже оцененной заявки, цена 0 по умолчанию ею не считается
+        let previous: Option<(Option<i64>, Option<String>)> = sqlx::query_as(
             r#"
-            UPDATE submissions
-            SET status = $1
-            WHERE submission_id = $2
-            RETURNING submission_id
-            "#,
+            SELECT
+                CASE WHEN quoted_at IS NULL THEN NULL ELSE price END,
+                CASE WHEN quoted_at IS NULL THEN NULL ELSE currency END
+            FROM submissions
+            WHERE submission_id = $1
+            FOR UPDATE
+            "#
         )
-        .bind(status)
         .bind(submission_id)
-        .fetch_one(&self.pool)
+        .fetch_optional(&mut *tx)
         .await?;
 
-        info!("Submission updated: {}", updated_submission.get::<Uuid, _>("submission_id"));
-        Ok(())
+        let Some((previous_price, previous_currency)) = previous else {
+            return Ok(false);
+        };
+
+        sqlx::query(
+            "UPDATE submissions SET price = $2, currency = $3, quoted_at = NOW() WHERE submission_id = $1"
+        )
+        .bind(submission_id)
+        .bind(price)
+        .bind(currency)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO submission_quotes
+                (submission_id, admin_id, price, currency, previous_price, previous_currency, note)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#
+        )
+        .bind(submission_id)
+        .bind(admin_id)
+        .bind(price)
+        .bind(currency)
+        .bind(previous_price)
+        .bind(previous_currency)
+        .bind(note)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(true)
     }
 
-    pub async fn get_admin_password(&self, admin_name: &str) -> Result<Option<(Uuid, String)>> {
-        let result = sqlx::query(
+    pub async fn get_quote_history(&self, submission_id: Uuid) -> Result<Vec<QuoteHistoryEntry>> {
+        let history = sqlx::query_as::<_, QuoteHistoryEntry>(
             r#"
-            SELECT id, password FROM admin WHERE username = $1
+            SELECT q.id, q.price, q.currency, q.previous_price, q.previous_currency, q.note,
+                   a.username AS admin_name, q.created_at
+            FROM submission_quotes q
+            LEFT JOIN admin a ON a.id = q.admin_id
+            WHERE q.submission_id = $1
+            ORDER BY q.created_at DESC
             "#
         )
-        .bind(admin_name)
-        .fetch_optional(&self.pool)
+        .bind(submission_id)
+        .fetch_all(&self.pool)
         .await?;
-    
-        Ok(result.map(|row| (row.get("id"), row.get("password"))))
+
+        Ok(history)
     }
 
-    pub async fn create_admin_comments(&self, admin_id: Uuid, submissions_id: Uuid, text: String) -> Result<()> {
-        sqlx::query(    
+    /// Смена статуса с записью в историю. Обновление происходит, только если
+    /// статус все еще равен `from`: возвращает `false`, если его успели изменить
+    pub async fn change_submission_status(
+        &self,
+        submission_id: Uuid,
+        from: SubmissionStatus,
+        to: SubmissionStatus,
+        admin_id: Uuid,
+        reason: Option<&str>,
+    ) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let updated = sqlx::query(
+            "UPDATE submissions SET status = $3 WHERE submission_id = $1 AND status = $2"
+        )
+        .bind(submission_id)
+        .bind(from.as_str())
+        .bind(to.as_str())
+        .execute(&mut *tx)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query(
             r#"
-            INSERT INTO admin_comments (admin_id, submission_id, comment)
-            VALUES ($1, $2, $3)
+            INSERT INTO submission_status_history (submission_id, from_status, to_status, admin_id, reason)
+            VALUES ($1, $2, $3, $4, $5)
             "#
         )
+        .bind(submission_id)
+        .bind(from.as_str())
+        .bind(to.as_str())
         .bind(admin_id)
-        .bind(submissions_id)
-        .bind(text)
-        .execute(&self.pool)
+        .bind(reason)
+        .execute(&mut *tx)
         .await?;
-        Ok(())
+
+        tx.commit().await?;
+        Ok(true)
     }
 
-    pub async fn get_admin_comments(&self, submissions_id: Uuid) -> Result<SubmissionCommentsRequest> {
-        let rows = sqlx::query(
+    pub async fn get_status_history(&self, submission_id: Uuid) -> Result<Vec<StatusHistoryEntry>> {
+        let history = sqlx::query_as::<_, StatusHistoryEntry>(
             r#"
-            SELECT 
-                ac.id as comment_id,
-                ac.comment,
-                ac.created_at,
-                a.username as admin_name
-            FROM admin_comments ac
-            JOIN admin a ON ac.admin_id = a.id
-            WHERE ac.submission_id = $1
-            ORDER BY ac.created_at ASC
+            SELECT h.id, h.from_status, h.to_status, a.username AS admin_name, h.reason, h.created_at
+            FROM submission_status_history h
+            LEFT JOIN admin a ON a.id = h.admin_id
+            WHERE h.submission_id = $1
+            ORDER BY h.created_at DESC
             "#
         )
-        .bind(submissions_id)
+        .bind(submission_id)
         .fetch_all(&self.pool)
         .await?;
 
-        let comments = rows
+        Ok(history)
+    }
+
+    /// `metadata` передается готовой JSON-строкой: у sqlx нет поддержки JSONB без feature `json`
+    pub async fn insert_audit_log(
+        &self,
+        username: &str,
+        ip: Option<IpAddr>,
+        user_agent: Option<&str>,
+        action: &str,
+        metadata: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO admin_panel_log (username, ip, user_agent, action, metadata)
+            VALUES ($1, $2::inet, $3, $4, $5::jsonb)
+            "#
+        )
+        .bind(username)
+        .bind(ip.map(|ip| ip.to_string()))
+        .bind(user_agent)
+        .bind(action)
+        .bind(metadata)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_audit_log(
+        &self,
+        page: i64,
+        per_page: i64,
+        filter: &AuditLogFilter,
+    ) -> Result<PaginationResult<AuditLogEntry>> {
+        let page = page.max(1);
+        let per_page = per_page.clamp(1, MAX_PAGE_SIZE);
+        let offset = (page - 1).saturating_mul(per_page);
+
+        let count_query = format!("SELECT COUNT(*) FROM admin_panel_log WHERE {}", AUDIT_FILTER);
+        let (total_count,): (i64,) = sqlx::query_as(&count_query)
+            .bind(filter.username.as_deref())
+            .bind(filter.action.as_deref())
+            .bind(filter.ip.as_deref())
+            .bind(filter.created_from)
+            .bind(filter.created_before)
+            .fetch_one(&self.pool)
+            .await?;
+
+        let data_query = format!(
+            "SELECT admin_log_id, username, host(ip) AS ip, user_agent, action,
+                    metadata::text AS metadata, created_at
+            FROM admin_panel_log
+            WHERE {}
+            ORDER BY created_at DESC, admin_log_id DESC
+            LIMIT $6 OFFSET $7",
+            AUDIT_FILTER
+        );
+        let rows = sqlx::query(&data_query)
+            .bind(filter.username.as_deref())
+            .bind(filter.action.as_deref())
+            .bind(filter.ip.as_deref())
+            .bind(filter.created_from)
+            .bind(filter.created_before)
+            .bind(per_page)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let entries = rows
             .into_iter()
-            .map(|row| SubmissionComment {
-                comment_id: row.get("comment_id"),
-                comment: row.get("comment"),
+            .map(|row| AuditLogEntry {
+                id: row.get("admin_log_id"),
+                username: row.get("username"),
+                ip: row.get("ip"),
+                user_agent: row.get("user_agent"),
+                action: row.get("action"),
+                metadata: row
+                    .get::<Option<String>, _>("metadata")
+                    .and_then(|json| serde_json::from_str(&json).ok()),
                 created_at: row.get("created_at"),
-                admin_name: row.get("admin_name"),
             })
             .collect();
 
-        Ok(SubmissionCommentsRequest { data: comments })
+        Ok(PaginationResult::new(entries, total_count, page, per_page))
+    }
+
+    pub async fn get_admin_credentials(&self, admin_name: &str) -> Result<Option<AdminCredentials>> {
+        let result = sqlx::query_as::<_, AdminCredentials>(
+            r#"
+            SELECT id, password, role, is_active, must_change_password, totp_enabled
+            FROM admin
+            WHERE username = $1
+            "#
+        )
+        .bind(admin_name)
+        .fetch_optional(&self.pool)
+        .await?;
+    
+        Ok(result)
+    }
+
+    pub async fn record_admin_login(&self, admin_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE admin SET last_login_at = NOW() WHERE id = $1")
+            .bind(admin_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn list_admins(&self) -> Result<Vec<AdminAccount>> {
+        let admins = sqlx::query_as::<_, AdminAccount>(
+            r#"
+            SELECT id, username, role, is_active, created_at, last_login_at,
+                   password_changed_at, must_change_password, totp_enabled, email, on_duty, telegram_user_id
+            FROM admin
+            ORDER BY created_at ASC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(admins)
+    }
+
+    pub async fn get_admin_account(&self, admin_id: Uuid) -> Result<Option<AdminAccount>> {
+        let admin = sqlx::query_as::<_, AdminAccount>(
+            r#"
+            SELECT id, username, role, is_active, created_at, last_login_at,
+                   password_changed_at, must_change_password, totp_enabled, email, on_duty, telegram_user_id
+            FROM admin
+            WHERE id = $1
+            "#
+        )
+        .bind(admin_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(admin)
+    }
+
+    /// Возвращает `None`, если логин уже занят. Пароль, заданный владельцем,
+    /// считается временным и должен быть сменен при первом входе
+    pub async fn create_admin(
+        &self,
+        username: &str,
+        password_hash: &str,
+        role: AdminRole,
+    ) -> Result<Option<AdminAccount>> {
+        let admin = sqlx::query_as::<_, AdminAccount>(
+            r#"
+            INSERT INTO admin (username, password, role, must_change_password)
+            VALUES ($1, $2, $3, TRUE)
+            ON CONFLICT (username) DO NOTHING
+            RETURNING id, username, role, is_active, created_at, last_login_at,
+                   password_changed_at, must_change_password, totp_enabled, email, on_duty, telegram_user_id
+            "#
+        )
+        .bind(username)
+        .bind(password_hash)
+        .bind(role.as_str())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if let Some(admin) = &admin {
+            info!("Admin created: {} ({})", admin.username, admin.id);
+        }
+        Ok(admin)
+    }
+
+    /// Деактивация не применяется к последнему активному владельцу
+    pub async fn set_admin_active(&self, admin_id: Uuid, is_active: bool) -> Result<AccessChange> {
+        let mut tx = self.pool.begin().await?;
+
+        if !is_active {
+            let check = lock_owner_change(&mut tx, admin_id).await?;
+            if check != AccessChange::Applied {
+                return Ok(check);
+            }
+        }
+
+        let result = sqlx::query("UPDATE admin SET is_active = $1 WHERE id = $2")
+            .bind(is_active)
+            .bind(admin_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(access_change(result.rows_affected()))
+    }
+
+    /// Роль последнего активного владельца не понижается
+    pub async fn set_admin_role(&self, admin_id: Uuid, role: AdminRole) -> Result<AccessChange> {
+        let mut tx = self.pool.begin().await?;
+
+        if role != AdminRole::Owner {
+            let check = lock_owner_change(&mut tx, admin_id).await?;
+            if check != AccessChange::Applied {
+                return Ok(check);
+            }
+        }
+
+        let result = sqlx::query("UPDATE admin SET role = $1 WHERE id = $2")
+            .bind(role.as_str())
+            .bind(admin_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(access_change(result.rows_affected()))
+    }
+
+    /// `must_change_password` выставляется при сбросе пароля владельцем:
+    /// временный пароль нужно сменить при первом входе
+    pub async fn update_admin_password(
+        &self,
+        admin_id: Uuid,
+        password_hash: &str,
+        must_change_password: bool,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE admin
+            SET password = $1, password_changed_at = NOW(), must_change_password = $2
+            WHERE id = $3
+            "#
+        )
+            .bind(password_hash)
+            .bind(must_change_password)
+            .bind(admin_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Последнего активного владельца удалить нельзя
+    pub async fn delete_admin(&self, admin_id: Uuid) -> Result<AccessChange> {
+        let mut tx = self.pool.begin().await?;
+
+        let check = lock_owner_change(&mut tx, admin_id).await?;
+        if check != AccessChange::Applied {
+            return Ok(check);
+        }
+
+        let result = sqlx::query("DELETE FROM admin WHERE id = $1")
+            .bind(admin_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(access_change(result.rows_affected()))
+    }
+
+    pub async fn get_admin_password_hash(&self, admin_id: Uuid) -> Result<Option<String>> {
+        let password: Option<String> = sqlx::query_scalar("SELECT password FROM admin WHERE id = $1")
+            .bind(admin_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(password)
+    }
+
+    pub async fn get_admin_totp(&self, admin_id: Uuid) -> Result<Option<AdminTotp>> {
+        let totp = sqlx::query_as::<_, AdminTotp>(
+            "SELECT totp_secret, totp_enabled, totp_last_step FROM admin WHERE id = $1"
+        )
+        .bind(admin_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(totp)
+    }
+
+    /// Включение 2FA и замена кодов восстановления одной транзакцией
+    pub async fn enable_admin_totp(
+        &self,
+        admin_id: Uuid,
+        secret: &str,
+        recovery_code_hashes: &[String],
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE admin
+            SET totp_secret = $1, totp_enabled = TRUE, totp_last_step = NULL
+            WHERE id = $2
+            "#
+        )
+        .bind(secret)
+        .bind(admin_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM admin_recovery_codes WHERE admin_id = $1")
+            .bind(admin_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO admin_recovery_codes (admin_id, code_hash)
+            SELECT $1, UNNEST($2::varchar[])
+            "#
+        )
+        .bind(admin_id)
+        .bind(recovery_code_hashes)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        info!("TOTP enabled for admin {}", admin_id);
+        Ok(())
+    }
+
+    pub async fn disable_admin_totp(&self, admin_id: Uuid) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE admin
+            SET totp_secret = NULL, totp_enabled = FALSE, totp_last_step = NULL
+            WHERE id = $1
+            "#
+        )
+        .bind(admin_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM admin_recovery_codes WHERE admin_id = $1")
+            .bind(admin_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        info!("TOTP disabled for admin {}", admin_id);
+        Ok(())
+    }
+
+    /// Фиксирует использованный шаг TOTP. Возвращает `false`, если код
+    /// этого или более позднего шага уже принимался (повтор)
+    pub async fn consume_totp_step(&self, admin_id: Uuid, step: i64) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE admin
+            SET totp_last_step = $2
+            WHERE id = $1 AND (totp_last_step IS NULL OR totp_last_step < $2)
+            "#
+        )
+        .bind(admin_id)
+        .bind(step)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Погашение кода восстановления. Каждый код действует один раз
+    pub async fn consume_recovery_code(&self, admin_id: Uuid, code_hash: &str) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE admin_recovery_codes
+            SET used_at = NOW()
+            WHERE admin_id = $1 AND code_hash = $2 AND used_at IS NULL
+            "#
+        )
+        .bind(admin_id)
+        .bind(code_hash)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn admin_has_comments(&self, admin_id: Uuid) -> Result<bool> {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM admin_comments WHERE admin_id = $1)"
+        )
+        .bind(admin_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(exists)
+    }
+
+    pub async fn is_submission_assigned_to(&self, submission_id: Uuid, admin_id: Uuid) -> Result<bool> {
+        let assigned: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM submissions WHERE submission_id = $1 AND admin_id = $2
+            )
+            "#
+        )
+        .bind(submission_id)
+        .bind(admin_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(assigned)
+    }
+
+    pub async fn set_admin_mail(&self, admin_id: Uuid, email: Option<&str>, on_duty: bool) -> Result<bool> {
+        let result = sqlx::query("UPDATE admin SET email = $1, on_duty = $2 WHERE id = $3")
+            .bind(email)
+            .bind(on_duty)
+            .bind(admin_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Привязка аккаунта Telegram. Один аккаунт Telegram — один администратор
+    pub async fn set_admin_telegram(&self, admin_id: Uuid, telegram_user_id: Option<i64>) -> Result<bool> {
+        let result = sqlx::query("UPDATE admin SET telegram_user_id = $1 WHERE id = $2")
+            .bind(telegram_user_id)
+            .bind(admin_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get_admin_by_telegram_id(&self, telegram_user_id: i64) -> Result<Option<AdminAccount>> {
+        let admin = sqlx::query_as::<_, AdminAccount>(
+            r#"
+            SELECT id, username, role, is_active, created_at, last_login_at,
+                   password_changed_at, must_change_password, totp_enabled, email, on_duty, telegram_user_id
+            FROM admin
+            WHERE telegram_user_id = $1
+            "#
+        )
+        .bind(telegram_user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(admin)
+    }
+
+    /// Забирает следующее готовое к отправке сообщение указанных каналов.
+    /// Сообщение блокируется на `lock_secs`, поэтому несколько экземпляров приложения
+    /// не отправят его дважды; после падения воркера блокировка истекает сама
+    pub async fn claim_outbox_message(&self, channels: &[&str], lock_secs: i64) -> Result<Option<OutboxMessage>> {
+        let message = sqlx::query_as::<_, OutboxMessage>(
+            r#"
+            UPDATE outbox SET locked_until = NOW() + $1 * INTERVAL '1 second'
+            WHERE id = (
+                SELECT id FROM outbox
+                WHERE status = 'pending'
+                  AND channel = ANY($2)
+                  AND next_attempt_at <= NOW()
+                  AND (locked_until IS NULL OR locked_until < NOW())
+                ORDER BY next_attempt_at
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, channel, recipient, subject, body, submission_id, attempts
+            "#
+        )
+        .bind(lock_secs)
+        .bind(channels)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(message)
+    }
+
+    pub async fn mark_outbox_sent(&self, id: Uuid) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE outbox
+            SET status = 'sent', sent_at = NOW(), attempts = attempts + 1,
+                locked_until = NULL, last_error = NULL
+            WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Неудачная попытка. Без `retry_at` сообщение больше не отправляется
+    pub async fn mark_outbox_failed(
+        &self,
+        id: Uuid,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE outbox
+            SET attempts = attempts + 1,
+                last_error = $2,
+                locked_until = NULL,
+                status = CASE WHEN $3::timestamptz IS NULL THEN 'failed' ELSE 'pending' END,
+                next_attempt_at = COALESCE($3, next_attempt_at)
+            WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .bind(error)
+        .bind(retry_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Срок выполнения заявки. Новый срок снова включает напоминание.
+    /// Возвращает `false`, если заявка не найдена
+    pub async fn set_submission_due_at(&self, submission_id: Uuid, due_at: Option<DateTime<Utc>>) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE submissions SET due_at = $2, deadline_notified_at = NULL WHERE submission_id = $1"
+        )
+        .bind(submission_id)
+        .bind(due_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Уведомление одному администратору. `false`, если оно не создано:
+    /// администратор неактивен или отключил этот вид уведомлений
+    pub async fn create_notification(&self, notification: &NewNotification, recipient: Uuid) -> Result<bool> {
+        let mut conn = self.pool.acquire().await?;
+        let notified = insert_notifications(&mut conn, notification, None, &[recipient]).await?;
+        Ok(!notified.is_empty())
+    }
+
+    /// Напоминания исполнителям открытых заявок, срок которых наступит в
+    /// ближайшие `warning_secs` секунд. Заявки отмечаются в той же транзакции,
+    /// `SKIP LOCKED` не дает нескольким экземплярам взять одни и те же.
+    /// Возвращает число обработанных заявок
+    pub async fn create_deadline_notifications(
+        &self,
+        warning_secs: i64,
+        limit: i64,
+        compose: impl Fn(Uuid, DateTime<Utc>) -> NewNotification,
+    ) -> Result<usize> {
+        let mut tx = self.pool.begin().await?;
+
+        let due: Vec<(Uuid, Uuid, DateTime<Utc>)> = sqlx::query_as(
+            r#"
+            SELECT submission_id, admin_id, due_at
+            FROM submissions
+            WHERE status = ANY($1)
+              AND admin_id IS NOT NULL
+              AND deadline_notified_at IS NULL
+              AND due_at <= NOW() + $2 * INTERVAL '1 second'
+            ORDER BY due_at
+            LIMIT $3
+            FOR UPDATE SKIP LOCKED
+            "#
+        )
+        .bind(OPEN_SUBMISSION_STATUSES)
+        .bind(warning_secs)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        if due.is_empty() {
+            return Ok(0);
+        }
+
+        let ids: Vec<Uuid> = due.iter().map(|(submission_id, _, _)| *submission_id).collect();
+        sqlx::query("UPDATE submissions SET deadline_notified_at = NOW() WHERE submission_id = ANY($1)")
+            .bind(&ids)
+            .execute(&mut *tx)
+            .await?;
+
+        for (submission_id, admin_id, due_at) in &due {
+            insert_notifications(&mut tx, &compose(*submission_id, *due_at), None, &[*admin_id]).await?;
+        }
+
+        tx.commit().await?;
+        Ok(due.len())
+    }
+
+    /// Входящие администратора, новые первыми
+    pub async fn list_notifications(
+        &self,
+        admin_id: Uuid,
+        unread_only: bool,
+        page: i64,
+        per_page: i64,
+    ) -> Result<PaginationResult<Notification>> {
+        let page = page.max(1);
+        let per_page = per_page.clamp(1, MAX_PAGE_SIZE);
+        let offset = (page - 1).saturating_mul(per_page);
+
+        let total_count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM notifications
+            WHERE admin_id = $1 AND inbox AND (NOT $2 OR read_at IS NULL)"
+        )
+        .bind(admin_id)
+        .bind(unread_only)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let notifications = sqlx::query_as::<_, Notification>(
+            r#"
+            SELECT n.id, n.kind, n.submission_id, s.name AS submission_name, n.comment_id,
+                a.username AS actor_name, n.message, n.created_at, n.read_at
+            FROM notifications n
+            LEFT JOIN submissions s ON s.submission_id = n.submission_id
+            LEFT JOIN admin a ON a.id = n.actor_id
+            WHERE n.admin_id = $1 AND n.inbox AND (NOT $2 OR n.read_at IS NULL)
+            ORDER BY n.created_at DESC, n.id DESC
+            LIMIT $3 OFFSET $4
+            "#
+        )
+        .bind(admin_id)
+        .bind(unread_only)
+        .bind(per_page)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(PaginationResult::new(notifications, total_count, page, per_page))
+    }
+
+    pub async fn count_unread_notifications(&self, admin_id: Uuid) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM notifications WHERE admin_id = $1 AND inbox AND read_at IS NULL"
+        )
+        .bind(admin_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Отметка уведомления прочитанным или непрочитанным.
+    /// `false`, если у администратора нет такого уведомления
+    pub async fn set_notification_read(&self, notification_id: Uuid, admin_id: Uuid, read: bool) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE notifications
+            SET read_at = CASE WHEN $3 THEN COALESCE(read_at, NOW()) END
+            WHERE id = $1 AND admin_id = $2 AND inbox
+            "#
+        )
+        .bind(notification_id)
+        .bind(admin_id)
+        .bind(read)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn mark_all_notifications_read(&self, admin_id: Uuid) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE notifications SET read_at = NOW() WHERE admin_id = $1 AND inbox AND read_at IS NULL"
+        )
+        .bind(admin_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Настройки доставки по всем видам уведомлений, с умолчаниями для незаданных
+    pub async fn get_notification_preferences(&self, admin_id: Uuid) -> Result<Vec<NotificationPreference>> {
+        let saved = sqlx::query_as::<_, NotificationPreference>(
+            "SELECT kind, inbox, email FROM notification_preferences WHERE admin_id = $1"
+        )
+        .bind(admin_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(NotificationKind::ALL
+            .into_iter()
+            .map(|kind| {
+                saved
+                    .iter()
+                    .find(|preference| preference.kind == kind)
+                    .map(|preference| NotificationPreference { kind, inbox: preference.inbox, email: preference.email })
+                    .unwrap_or(NotificationPreference { kind, inbox: true, email: false })
+            })
+            .collect())
+    }
+
+    pub async fn set_notification_preferences(
+        &self,
+        admin_id: Uuid,
+        preferences: &[NotificationPreference],
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for preference in preferences {
+            sqlx::query(
+                r#"
+                INSERT INTO notification_preferences (admin_id, kind, inbox, email)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (admin_id, kind) DO UPDATE SET inbox = EXCLUDED.inbox, email = EXCLUDED.email
+                "#
+            )
+            .bind(admin_id)
+            .bind(preference.kind.as_str())
+            .bind(preference.inbox)
+            .bind(preference.email)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Отмечает использование уже сохраненного содержимого. `false`, если его
+    /// нет и файл нужно загрузить. Блокируется, пока очистка удаляет эту запись
+    pub async fn touch_stored_file(&self, sha256: &str) -> Result<bool> {
+        let result = sqlx::query("UPDATE stored_files SET last_used_at = NOW() WHERE sha256 = $1")
+            .bind(sha256)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Удаление содержимого, на которое не ссылается ни одно вложение и
+    /// которое не использовалось `grace_secs` секунд. Записи блокируются на
+    /// время удаления из хранилища (`delete`), поэтому загрузка того же файла
+    /// дожидается конца очистки и загружает его заново. Запись, содержимое
+    /// которой удалить не удалось, остается до следующего запуска.
+    /// Возвращает число удаленных файлов
+    pub async fn delete_unreferenced_files<F, Fut>(&self, grace_secs: i64, limit: i64, mut delete: F) -> Result<usize>
+    where
+        F: FnMut(String) -> Fut,
+        Fut: Future<Output = bool>,
+    {
+        let mut tx = self.pool.begin().await?;
+
+        let candidates: Vec<(String, String)> = sqlx::query_as(
+            r#"
+            SELECT f.sha256, f.storage_key
+            FROM stored_files f
+            WHERE f.last_used_at < NOW() - $1 * INTERVAL '1 second'
+              AND NOT EXISTS (SELECT 1 FROM submission_attachments a WHERE a.sha256 = f.sha256)
+            ORDER BY f.last_used_at
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+            "#
+        )
+        .bind(grace_secs)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut deleted = Vec::with_capacity(candidates.len());
+        for (sha256, storage_key) in candidates {
+            if delete(storage_key).await {
+                deleted.push(sha256);
+            }
+        }
+
+        sqlx::query("DELETE FROM stored_files WHERE sha256 = ANY($1)")
+            .bind(&deleted)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(deleted.len())
+    }
+
+    /// Вложения, загруженные администратором. `false`, если заявки нет
+    pub async fn add_submission_attachments(
+        &self,
+        submission_id: Uuid,
+        admin_id: Uuid,
+        attachments: &[NewAttachment],
+    ) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM submissions WHERE submission_id = $1)")
+            .bind(submission_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        if !exists {
+            return Ok(false);
+        }
+
+        insert_attachments(&mut tx, submission_id, Some(admin_id), attachments).await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    pub async fn get_submission_attachments(&self, submission_id: Uuid) -> Result<Vec<Attachment>> {
+        let attachments = sqlx::query_as::<_, Attachment>(&format!(
+            "{} WHERE sa.submission_id = $1 ORDER BY sa.created_at, sa.file_name",
+            ATTACHMENT_SELECT
+        ))
+        .bind(submission_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(attachments)
+    }
+
+    pub async fn get_attachment(&self, attachment_id: Uuid) -> Result<Option<Attachment>> {
+        let attachment = sqlx::query_as::<_, Attachment>(&format!("{} WHERE sa.id = $1", ATTACHMENT_SELECT))
+            .bind(attachment_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(attachment)
+    }
+
+    pub async fn get_comment(&self, comment_id: Uuid) -> Result<Option<CommentRecord>> {
+        let comment = sqlx::query_as::<_, CommentRecord>(
+            "SELECT id, submission_id, admin_id, parent_id, deleted_at FROM admin_comments WHERE id = $1"
+        )
+        .bind(comment_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(comment)
+    }
+
+    /// Сохраняет комментарий и уведомления упомянутым администраторам.
+    /// Возвращает id комментария и логины тех, кто получил уведомление
+    pub async fn create_admin_comment(
+        &self,
+        admin_id: Uuid,
+        submission_id: Uuid,
+        parent_id: Option<Uuid>,
+        text: &str,
+        mentions: &[String],
+        notification: &NewNotification,
+    ) -> Result<(Uuid, Vec<String>)> {
+        let mut tx = self.pool.begin().await?;
+
+        let (comment_id,): (Uuid,) = sqlx::query_as(
+            r#"
+            INSERT INTO admin_comments (admin_id, submission_id, parent_id, comment)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id
+            "#
+        )
+        .bind(admin_id)
+        .bind(submission_id)
+        .bind(parent_id)
+        .bind(text)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let notified = insert_mention_notifications(&mut tx, comment_id, admin_id, mentions, notification).await?;
+
+        tx.commit().await?;
+        Ok((comment_id, notified))
+    }
+
+    /// Правка комментария с сохранением предыдущей версии. Новые упоминания
+    /// получают уведомление, уже уведомленные повторно не получают.
+    /// `None`, если комментарий удален
+    pub async fn update_admin_comment(
+        &self,
+        comment_id: Uuid,
+        admin_id: Uuid,
+        text: &str,
+        mentions: &[String],
+        notification: &NewNotification,
+    ) -> Result<Option<Vec<String>>> {
+        let mut tx = self.pool.begin().await?;
+
+        let previous: Option<(String,)> = sqlx::query_as(
+            "SELECT comment FROM admin_comments WHERE id = $1 AND deleted_at IS NULL FOR UPDATE"
+        )
+        .bind(comment_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some((previous,)) = previous else {
+            return Ok(None);
+        };
+        if previous == text {
+            return Ok(Some(Vec::new()));
+        }
+
+        sqlx::query("INSERT INTO admin_comment_edits (comment_id, previous_comment) VALUES ($1, $2)")
+            .bind(comment_id)
+            .bind(&previous)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE admin_comments SET comment = $2, edited_at = NOW() WHERE id = $1")
+            .bind(comment_id)
+            .bind(text)
+            .execute(&mut *tx)
+            .await?;
+
+        let notified = insert_mention_notifications(&mut tx, comment_id, admin_id, mentions, notification).await?;
+
+        tx.commit().await?;
+        Ok(Some(notified))
+    }
+
+    /// Мягкое удаление: ветка ответов сохраняется, текст скрывается
+    pub async fn soft_delete_admin_comment(&self, comment_id: Uuid) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE admin_comments SET deleted_at = NOW() WHERE id = $1 AND deleted_at IS NULL"
+        )
+        .bind(comment_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get_comment_edits(&self, comment_id: Uuid) -> Result<Vec<CommentEdit>> {
+        let edits = sqlx::query_as::<_, CommentEdit>(
+            r#"
+            SELECT id, previous_comment, edited_at
+            FROM admin_comment_edits
+            WHERE comment_id = $1
+            ORDER BY edited_at DESC
+            "#
+        )
+        .bind(comment_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(edits)
+    }
+
+    /// Страница веток обсуждения заявки: новые ветки первыми,
+    /// ответы внутри ветки в порядке написания
+    pub async fn get_comment_threads(
+        &self,
+        submission_id: Uuid,
+        viewer_id: Uuid,
+        page: i64,
+        per_page: i64,
+    ) -> Result<PaginationResult<CommentThread>> {
+        let page = page.max(1);
+        let per_page = per_page.clamp(1, MAX_PAGE_SIZE);
+        let offset = (page - 1).saturating_mul(per_page);
+
+        let total_count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM admin_comments WHERE submission_id = $1 AND parent_id IS NULL"
+        )
+        .bind(submission_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let roots_query = format!(
+            "SELECT {} FROM admin_comments ac JOIN admin a ON a.id = ac.admin_id
+            WHERE ac.submission_id = $2 AND ac.parent_id IS NULL
+            ORDER BY ac.created_at DESC, ac.id DESC
+            LIMIT $3 OFFSET $4",
+            COMMENT_COLUMNS
+        );
+        let roots = sqlx::query_as::<_, SubmissionComment>(&roots_query)
+            .bind(viewer_id)
+            .bind(submission_id)
+            .bind(per_page)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let root_ids: Vec<Uuid> = roots.iter().map(|c| c.comment_id).collect();
+        let replies_query = format!(
+            "SELECT {} FROM admin_comments ac JOIN admin a ON a.id = ac.admin_id
+            WHERE ac.parent_id = ANY($2)
+            ORDER BY ac.created_at ASC, ac.id ASC",
+            COMMENT_COLUMNS
+        );
+        let mut replies = sqlx::query_as::<_, SubmissionComment>(&replies_query)
+            .bind(viewer_id)
+            .bind(&root_ids)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let threads = roots
+            .into_iter()
+            .map(|comment| {
+                let (own, rest) = replies
+                    .drain(..)
+                    .partition(|reply| reply.parent_id == Some(comment.comment_id));
+                replies = rest;
+                CommentThread { comment, replies: own }
+            })
+            .collect();
+
+        Ok(PaginationResult::new(threads, total_count, page, per_page))
+    }
+}
+
+/// Колонки `SubmissionComment`; $1 — id администратора, запросившего список.
+/// Текст удаленного комментария не отдается
+const COMMENT_COLUMNS: &str = "ac.id AS comment_id, ac.parent_id,
+    CASE WHEN ac.deleted_at IS NULL THEN ac.comment END AS comment,
+    a.username AS admin_name, ac.admin_id = $1 AS is_own,
+    ac.created_at, ac.edited_at, ac.deleted_at";
+
+/// Уведомления об упоминании в комментарии. Получают их только активные
+/// администраторы, которым видна заявка (эксперт — только назначенную),
+/// автор комментария себя не уведомляет
+async fn insert_mention_notifications(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    comment_id: Uuid,
+    actor_id: Uuid,
+    usernames: &[String],
+    notification: &NewNotification,
+) -> Result<Vec<String>> {
+    if usernames.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let recipients: Vec<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT a.id
+        FROM admin a
+        JOIN admin_comments c ON c.id = $1
+        JOIN submissions s ON s.submission_id = c.submission_id
+        WHERE a.username = ANY($3)
+          AND a.id <> $2
+          AND (a.role <> 'expert' OR s.admin_id = a.id)
+        "#
+    )
+    .bind(comment_id)
+    .bind(actor_id)
+    .bind(usernames)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    insert_notifications(tx, notification, Some(comment_id), &recipients).await
+}
+
+/// Блокирует строки активных владельцев и самого администратора до конца
+/// транзакции, чтобы параллельные изменения не сняли последнего владельца.
+/// `LastOwner`, если администратор — единственный активный владелец
+async fn lock_owner_change(conn: &mut PgConnection, admin_id: Uuid) -> Result<AccessChange> {
+    let rows: Vec<(Uuid, bool)> = sqlx::query_as(
+        r#"
+        SELECT id, role = 'owner' AND is_active
+        FROM admin
+        WHERE id = $1 OR (role = 'owner' AND is_active)
+        ORDER BY id
+        FOR UPDATE
+        "#
+    )
+    .bind(admin_id)
+    .fetch_all(conn)
+    .await?;
+
+    let Some(&(_, is_owner)) = rows.iter().find(|(id, _)| *id == admin_id) else {
+        return Ok(AccessChange::NotFound);
+    };
+    let owners = rows.iter().filter(|(_, owner)| *owner).count();
+    Ok(if is_owner && owners == 1 { AccessChange::LastOwner } else { AccessChange::Applied })
+}
+
+fn access_change(rows_affected: u64) -> AccessChange {
+    if rows_affected > 0 { AccessChange::Applied } else { AccessChange::NotFound }
+}
+
+/// Уведомления активным получателям по их настройкам: во входящие и/или
+/// письмом через outbox. Запись создается и без входящих, чтобы повторное
+/// упоминание не дублировало письмо. Возвращает логины уведомленных
+async fn insert_notifications(
+    conn: &mut PgConnection,
+    notification: &NewNotification,
+    comment_id: Option<Uuid>,
+    recipients: &[Uuid],
+) -> Result<Vec<String>> {
+    if recipients.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let email = notification.email.as_ref();
+    let notified: Vec<String> = sqlx::query_scalar(
+        r#"
+        WITH recipients AS (
+            SELECT a.id, a.username, a.email,
+                COALESCE(p.inbox, TRUE) AS inbox,
+                COALESCE(p.email, FALSE) AS by_email
+            FROM admin a
+            LEFT JOIN notification_preferences p ON p.admin_id = a.id AND p.kind = $1
+            WHERE a.id = ANY($2) AND a.is_active
+        ),
+        inserted AS (
+            INSERT INTO notifications (admin_id, kind, submission_id, comment_id, actor_id, message, inbox)
+            SELECT id, $1, $3, $4, $5, $6, inbox
+            FROM recipients
+            WHERE inbox OR by_email
+            ON CONFLICT DO NOTHING
+            RETURNING admin_id
+        ),
+        emailed AS (
+            INSERT INTO outbox (channel, recipient, subject, body, submission_id)
+            SELECT 'email', r.email, $7, $8, $3
+            FROM recipients r
+            JOIN inserted i ON i.admin_id = r.id
+            WHERE $8::text IS NOT NULL AND r.by_email AND r.email IS NOT NULL
+        )
+        SELECT r.username FROM recipients r JOIN inserted i ON i.admin_id = r.id
+        "#
+    )
+    .bind(notification.kind.as_str())
+    .bind(recipients)
+    .bind(notification.submission_id)
+    .bind(comment_id)
+    .bind(notification.actor_id)
+    .bind(&notification.message)
+    .bind(email.map(|email| email.subject.as_str()))
+    .bind(email.map(|email| email.body.as_str()))
+    .fetch_all(conn)
+    .await?;
+
+    Ok(notified)
+}
+
+/// Привязка вложений к заявке. `uploaded_by` — `None` для файлов из формы
+/// на сайте. Метаданные содержимого записываются один раз на SHA-256,
+/// повторная привязка того же файла к заявке пропускается
+async fn insert_attachments(
+    conn: &mut PgConnection,
+    submission_id: Uuid,
+    uploaded_by: Option<Uuid>,
+    attachments: &[NewAttachment],
+) -> Result<()> {
+    if attachments.is_empty() {
+        return Ok(());
+    }
+
+    let hashes: Vec<&str> = attachments.iter().map(|a| a.sha256.as_str()).collect();
+    let sizes: Vec<i64> = attachments.iter().map(|a| a.size_bytes).collect();
+    let content_types: Vec<&str> = attachments.iter().map(|a| a.content_type).collect();
+    let keys: Vec<&str> = attachments.iter().map(|a| a.storage_key.as_str()).collect();
+    let file_names: Vec<&str> = attachments.iter().map(|a| a.file_name.as_str()).collect();
+
+    sqlx::query(
+        r#"
+        INSERT INTO stored_files (sha256, size_bytes, content_type, storage_key)
+        SELECT * FROM UNNEST($1::text[], $2::bigint[], $3::text[], $4::text[])
+        ON CONFLICT (sha256) DO NOTHING
+        "#
+    )
+    .bind(&hashes)
+    .bind(&sizes)
+    .bind(&content_types)
+    .bind(&keys)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO submission_attachments (submission_id, sha256, file_name, source, uploaded_by)
+        SELECT $1, f.sha256, f.file_name, $2, $3
+        FROM UNNEST($4::text[], $5::text[]) AS f(sha256, file_name)
+        ON CONFLICT (submission_id, sha256) DO NOTHING
+        "#
+    )
+    .bind(submission_id)
+    .bind(if uploaded_by.is_some() { "admin" } else { "client" })
+    .bind(uploaded_by)
+    .bind(&hashes)
+    .bind(&file_names)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+const ATTACHMENT_SELECT: &str = "SELECT sa.id, sa.submission_id, sa.file_name, f.content_type, f.size_bytes,
+    f.sha256, sa.source, a.username AS uploaded_by_name, sa.created_at, f.storage_key
+    FROM submission_attachments sa
+    JOIN stored_files f ON f.sha256 = sa.sha256
+    LEFT JOIN admin a ON a.id = sa.uploaded_by";
+
+/// Логин назначенного администратора. Подзапрос вместо JOIN, чтобы
+/// колонки `admin` не пересекались с условиями `SUBMISSION_FILTER`
+const ASSIGNEE_NAME: &str =
+    "(SELECT username FROM admin WHERE admin.id = submissions.admin_id) AS assignee_name";
+
+/// Условия фильтра `SubmissionFilter`, общие для выборки и подсчета ($1..$11).
+/// Полнотекстовый поиск использует GIN-индексы по to_tsvector('russian', ...).
+/// Спам скрыт, пока не запрошен явно
+const SUBMISSION_FILTER: &str = "($1::uuid IS NULL OR admin_id = $1)
+    AND ($2 OR status <> 'spam')
+    AND ($3::text IS NULL
+        OR to_tsvector('russian', name) @@ websearch_to_tsquery('russian', $3)
+        OR to_tsvector('russian', message) @@ websearch_to_tsquery('russian', $3))
+    AND ($4::text IS NULL OR email = $4)
+    AND ($5::text IS NULL OR phone = $5)
+    AND ($6::text[] IS NULL OR status = ANY($6))
+    AND ($7::timestamptz IS NULL OR created_at >= $7)
+    AND ($8::timestamptz IS NULL OR created_at < $8)
+    AND ($9::bigint IS NULL OR price >= $9)
+    AND ($10::bigint IS NULL OR price <= $10)
+    AND ($11::text IS NULL OR currency = $11)";
+
+/// Фильтр журнала действий администраторов ($1..$5)
+const AUDIT_FILTER: &str = "($1::text IS NULL OR username = $1)
+    AND ($2::text IS NULL OR action = $2)
+    AND ($3::inet IS NULL OR ip = $3::inet)
+    AND ($4::timestamptz IS NULL OR created_at >= $4)
+    AND ($5::timestamptz IS NULL OR created_at < $5)";
+
+fn bind_submission_filter<'q, O>(
+    query: QueryAs<'q, Postgres, O, PgArguments>,
+    filter: &'q SubmissionFilter,
+) -> QueryAs<'q, Postgres, O, PgArguments> {
+    query
+        .bind(filter.assigned_to)
+        .bind(filter.shows_spam())
+        .bind(filter.query.as_deref())
+        .bind(filter.email.as_deref())
+        .bind(filter.phone.as_deref())
+        .bind(filter.statuses.as_deref())
+        .bind(filter.created_from)
+        .bind(filter.created_before)
+        .bind(filter.price_min)
+        .bind(filter.price_max)
+        .bind(filter.currency.as_deref())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// База для проверки запросов из `TEST_DATABASE_URL`, миграции применяются
+    /// перед тестом. Без переменной тесты с базой пропускаются
+    pub(crate) async fn test_database() -> Option<PostgresDatabase> {
+        let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+            eprintln!("TEST_DATABASE_URL is not set, skipping database test");
+            return None;
+        };
+        let db = PostgresDatabase::new(&url).await.expect("test database is reachable");
+        sqlx::migrate!("./migrations").run(&db.pool).await.expect("migrations apply");
+        Some(db)
+    }
+
+    /// Администратор с уникальным логином, удаляется в конце теста
+    pub(crate) async fn create_test_admin(db: &PostgresDatabase, password: &str, role: AdminRole) -> (Uuid, String) {
+        let username = format!("test-{}", Uuid::new_v4().simple());
+        let id: Uuid = sqlx::query_scalar("INSERT INTO admin (username, password, role) VALUES ($1, $2, $3) RETURNING id")
+            .bind(&username)
+            .bind(password)
+            .bind(role.as_str())
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+        (id, username)
+    }
+
+    pub(crate) async fn delete_test_admin(db: &PostgresDatabase, id: Uuid) {
+        sqlx::query("DELETE FROM admin WHERE id = $1").bind(id).execute(&db.pool).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn decodes_admin_credentials() {
+        let Some(db) = test_database().await else { return };
+        let (id, username) = create_test_admin(&db, "$2b$04$hash", AdminRole::Expert).await;
+
+        let credentials = db.get_admin_credentials(&username).await;
+        delete_test_admin(&db, id).await;
+
+        let credentials = credentials.unwrap().expect("admin exists");
+        assert_eq!(credentials.id, id);
+        assert_eq!(credentials.password, "$2b$04$hash");
+        assert_eq!(credentials.role, AdminRole::Expert);
+        assert!(credentials.is_active);
+        assert!(!credentials.totp_enabled);
+    }
+
+    #[tokio::test]
+    async fn seeds_admin_with_hashed_password() {
+        let Some(db) = test_database().await else { return };
+        let username = format!("test-{}", Uuid::new_v4().simple());
+
+        db.migrate(&username, &"Seed-Passw0rd!".to_string()).await.unwrap();
+        let row = sqlx::query("SELECT id, password, must_change_password FROM admin WHERE username = $1")
+            .bind(&username)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+        delete_test_admin(&db, row.get("id")).await;
+
+        let password: String = row.get("password");
+        assert!(is_bcrypt_hash(&password));
+        assert!(bcrypt::verify("Seed-Passw0rd!", &password).unwrap());
+        assert!(row.get::<bool, _>("must_change_password"));
+    }
+
+    #[tokio::test]
+    async fn concurrent_deactivation_keeps_an_owner() {
+        let Some(db) = test_database().await else { return };
+        // Остаются только два владельца теста, остальные на время отключаются
+        let others: Vec<Uuid> = sqlx::query_scalar(
+            "UPDATE admin SET is_active = FALSE WHERE role = 'owner' AND is_active RETURNING id"
+        )
+        .fetch_all(&db.pool)
+        .await
+        .unwrap();
+        let (first, _) = create_test_admin(&db, "$2b$04$hash", AdminRole::Owner).await;
+        let (second, _) = create_test_admin(&db, "$2b$04$hash", AdminRole::Owner).await;
+
+        // Первая деактивация держит транзакцию открытой, пока идет вторая
+        let mut tx = db.pool.begin().await.unwrap();
+        assert_eq!(lock_owner_change(&mut tx, first).await.unwrap(), AccessChange::Applied);
+        sqlx::query("UPDATE admin SET is_active = FALSE WHERE id = $1")
+            .bind(first)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        let (second_result, _) = tokio::join!(db.set_admin_active(second, false), async {
+            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+            tx.commit().await.unwrap();
+        });
+        let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM admin WHERE role = 'owner' AND is_active")
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+
+        delete_test_admin(&db, first).await;
+        delete_test_admin(&db, second).await;
+        sqlx::query("UPDATE admin SET is_active = TRUE WHERE id = ANY($1)")
+            .bind(&others)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+
+        second_result.unwrap();
+        assert!(remaining >= 1, "both owners were deactivated");
+    }
+
+    #[tokio::test]
+    async fn keyset_pages_follow_the_status_order() {
+        let Some(db) = test_database().await else { return };
+        let email = format!("{}@example.com", Uuid::new_v4().simple());
+        let rows = [("new", 1), ("viewed", 2), ("new", 3), ("viewed", 3)];
+        for (status, minutes) in rows {
+            sqlx::query(
+                r#"
+                INSERT INTO submissions (name, email, message, status, created_at)
+                VALUES ('Test', $1, 'Keyset test', $2, TIMESTAMPTZ '2026-01-01' + make_interval(mins => $3))
+                "#
+            )
+            .bind(&email)
+            .bind(status)
+            .bind(minutes)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        }
+
+        let filter = SubmissionFilter { email: Some(email.clone()), ..Default::default() };
+        let mut seen = Vec::new();
+        let mut after = None;
+        let pages = loop {
+            let page = db
+                .get_submissions_keyset(1, KeysetSort::Status, true, after.as_ref(), CountMode::None, &filter)
+                .await;
+            let Ok(page) = page else { break page.map(|_| ()) };
+            seen.extend(page.data.iter().map(|s| (s.status.to_string(), s.submission_id, s.created_at)));
+            match page.next_cursor {
+                Some(cursor) => after = SubmissionCursor::decode(&cursor),
+                None => break Ok(()),
+            }
+        };
+        sqlx::query("DELETE FROM submissions WHERE email = $1").bind(&email).execute(&db.pool).await.unwrap();
+        pages.unwrap();
+
+        let mut sorted = seen.clone();
+        sorted.sort_by(|a, b| (&b.0, b.2, b.1).cmp(&(&a.0, a.2, a.1)));
+        assert_eq!(seen.len(), rows.len());
+        assert_eq!(seen, sorted);
     }
 }
@@ -28,6 +28,7 @@ impl From<serde_json::Error> for DatabaseError {
 }
 
 pub struct RedisDatabase {
+    client: redis::Client,
     connection_pool: redis::aio::ConnectionManager,
 }
 
@@ -38,6 +39,7 @@ impl RedisDatabase {
         let connection_pool = client.get_connection_manager().await?;
         
         Ok(Self {
+            client,
             connection_pool,
         })
     }
@@ -46,4 +48,32 @@ impl RedisDatabase {
     pub async fn get_connection(&self) -> Result<redis::aio::ConnectionManager, DatabaseError> {
         Ok(self.connection_pool.clone())
     }
+
+    pub async fn publish(&self, channel: &str, payload: &str) -> Result<(), DatabaseError> {
+        let mut conn = self.connection_pool.clone();
+        let _: i64 = redis::cmd("PUBLISH").arg(channel).arg(payload).query_async(&mut conn).await?;
+        Ok(())
+    }
+
+    /// Отдельное соединение для подписки: в режиме pub/sub оно не принимает
+    /// обычные команды, поэтому пул для этого не подходит
+    pub async fn subscribe(&self, channels: &[String]) -> Result<redis::aio::PubSub, DatabaseError> {
+        let mut pubsub = self.client.get_async_pubsub().await?;
+        pubsub.subscribe(channels).await?;
+        Ok(pubsub)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::RedisDatabase;
+
+    /// Redis для тестов из `TEST_REDIS_URL`. Без переменной тесты пропускаются
+    pub(crate) async fn test_redis() -> Option<RedisDatabase> {
+        let Ok(url) = std::env::var("TEST_REDIS_URL") else {
+            eprintln!("TEST_REDIS_URL is not set, skipping Redis test");
+            return None;
+        };
+        Some(RedisDatabase::new(&url).await.expect("test Redis is reachable"))
+    }
 }
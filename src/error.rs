@@ -2,10 +2,15 @@ use axum::{
     http::StatusCode,
     response::{Json, IntoResponse, Response},
 };
-use serde_json::json;
+use serde::Serialize;
+use serde_json::{json, Value};
 use thiserror::Error;
 use crate::database::postgres::error::DatabaseError;
 use crate::database::redis::redis_interface::DatabaseError as RedisDbError;
+use crate::middleware::current_request_id;
+use crate::storage::StorageError;
+use crate::validation::FieldError;
+use crate::workflow::SubmissionStatus;
 
 #[derive(Error, Debug)]
 pub enum AppError {
@@ -24,8 +29,32 @@ pub enum AppError {
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
     
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+    
+    #[error("Not found: {0}")]
+    NotFound(String),
+    
+    #[error("Conflict: {0}")]
+    Conflict(String),
+    
     #[error("Too many requests: {0}")]
     TooManyRequests(String),
+
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+
+    #[error("Unsupported media type: {0}")]
+    UnsupportedMediaType(String),
+
+    #[error("Validation failed: {} field(s)", .0.len())]
+    Validation(Vec<FieldError>),
+
+    #[error("Status transition {from} -> {to} is not allowed")]
+    InvalidStatusTransition { from: SubmissionStatus, to: SubmissionStatus },
+
+    #[error("CSRF error: {message}")]
+    Csrf { code: &'static str, message: String },
     
     #[error("JWT error: {0}")]
     JwtError(#[from] jsonwebtoken::errors::Error),
@@ -38,36 +67,108 @@ pub enum AppError {
     
     #[error("Environment variable error: {0}")]
     EnvError(#[from] std::env::VarError),
+
+    #[error("Storage error: {0}")]
+    Storage(#[from] StorageError),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Единый формат ошибки API:
+/// `{"code": "...", "message": "...", "details": ..., "request_id": "..."}`.
+/// `details` присутствует только у ошибок с дополнительными данными (например, список полей)
+#[derive(Debug, Serialize)]
+pub struct ErrorEnvelope {
+    pub code: &'static str,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+    pub request_id: Option<String>,
+}
+
+/// Ответ об ошибке в едином формате. `request_id` берется из текущего запроса
+pub fn error_response(
+    status: StatusCode,
+    code: &'static str,
+    message: impl Into<String>,
+    details: Option<Value>,
+) -> Response {
+    let envelope = ErrorEnvelope {
+        code,
+        message: message.into(),
+        details,
+        request_id: current_request_id(),
+    };
+    (status, Json(envelope)).into_response()
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        let (status, message) = match self {
-            // Существующие ошибки базы данных
-            AppError::DatabaseError(e) => (
+        let description = self.to_string();
+
+        // Текст внутренних ошибок наружу не отдается, только в лог
+        let (status, code, message, details) = match self {
+            AppError::DatabaseError(e) => {
+                let (status, code, message) = database_error_response(&e);
+                (status, code, message.to_string(), None)
+            }
+
+            AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "BAD_REQUEST", msg, None),
+            AppError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, "UNAUTHORIZED", msg, None),
+            AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, "FORBIDDEN", msg, None),
+            AppError::NotFound(msg) => (StatusCode::NOT_FOUND, "NOT_FOUND", msg, None),
+            AppError::Conflict(msg) => (StatusCode::CONFLICT, "CONFLICT", msg, None),
+            AppError::TooManyRequests(msg) => (StatusCode::TOO_MANY_REQUESTS, "RATE_LIMITED", msg, None),
+            AppError::PayloadTooLarge(msg) => (StatusCode::PAYLOAD_TOO_LARGE, "PAYLOAD_TOO_LARGE", msg, None),
+            AppError::UnsupportedMediaType(msg) => {
+                (StatusCode::UNSUPPORTED_MEDIA_TYPE, "UNSUPPORTED_MEDIA_TYPE", msg, None)
+            }
+            AppError::Csrf { code, message } => (StatusCode::FORBIDDEN, code, message, None),
+            AppError::InvalidStatusTransition { from, to } => (
+                StatusCode::CONFLICT,
+                "INVALID_STATUS_TRANSITION",
+                format!("Cannot change status from {} to {}", from, to),
+                Some(json!({ "from": from, "to": to, "allowed": from.next_statuses() })),
+            ),
+            AppError::Validation(errors) => (
+                StatusCode::BAD_REQUEST,
+                "VALIDATION_ERROR",
+                "Validation failed".to_string(),
+                Some(json!(errors)),
+            ),
+
+            AppError::JwtError(_) => (
+                StatusCode::UNAUTHORIZED,
+                "INVALID_TOKEN",
+                "Invalid or expired token".to_string(),
+                None,
+            ),
+
+            // Тело запроса разбирает экстрактор `Json`, его отказы оформляет
+            // error_envelope_middleware. Здесь остаются только внутренние данные
+            // (сессии и тикеты в Redis), их повреждение — ошибка сервера
+            AppError::JsonError(_)
+            | AppError::RedisError(_)
+            | AppError::RawRedis(_)
+            | AppError::BcryptError(_)
+            | AppError::EnvError(_)
+            | AppError::Storage(_)
+            | AppError::Io(_) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Database error: {}", e),
+                "INTERNAL_ERROR",
+                "Internal server error".to_string(),
+                None,
             ),
-            
-            AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
-            AppError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg),
-            AppError::TooManyRequests(msg) => (StatusCode::TOO_MANY_REQUESTS, msg),
-            
-            AppError::RedisError(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Redis error: {}", e)),
-            AppError::RawRedis(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Redis error: {}", e)),
-            
-            AppError::JwtError(e) => (StatusCode::UNAUTHORIZED, format!("Authentication error: {}", e)),
-            
-            AppError::BcryptError(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Password hashing error: {}", e)),
-            
-            AppError::JsonError(e) => (StatusCode::BAD_REQUEST, format!("JSON parsing error: {}", e)),
-        
-            AppError::EnvError(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Configuration error: {}", e)),
         };
 
-        tracing::error!("Application error: {} - Status: {}", message, status);
+        if status.is_server_error() {
+            tracing::error!(code, status = %status, "Application error: {}", description);
+        } else {
+            tracing::warn!(code, status = %status, "Request rejected: {}", description);
+        }
 
-        (status, Json(json!({ "error": message }))).into_response()
+        error_response(status, code, message, details)
     }
 }
 
@@ -80,8 +181,62 @@ impl AppError {
         Self::Unauthorized(msg.into())
     }
     
+    pub fn forbidden(msg: impl Into<String>) -> Self {
+        Self::Forbidden(msg.into())
+    }
+    
+    pub fn not_found(msg: impl Into<String>) -> Self {
+        Self::NotFound(msg.into())
+    }
+    
+    pub fn conflict(msg: impl Into<String>) -> Self {
+        Self::Conflict(msg.into())
+    }
+    
     pub fn too_many_requests(msg: impl Into<String>) -> Self {
         Self::TooManyRequests(msg.into())
     }
+
+    pub fn payload_too_large(msg: impl Into<String>) -> Self {
+        Self::PayloadTooLarge(msg.into())
+    }
+
+    pub fn unsupported_media_type(msg: impl Into<String>) -> Self {
+        Self::UnsupportedMediaType(msg.into())
+    }
+
+    pub fn csrf(code: &'static str, msg: impl Into<String>) -> Self {
+        Self::Csrf { code, message: msg.into() }
+    }
     
-}
\ No newline at end of file
+}
+
+/// Статус, стабильный код и безопасное сообщение для ошибки базы данных
+fn database_error_response(error: &DatabaseError) -> (StatusCode, &'static str, &'static str) {
+    match error {
+        DatabaseError::UniqueViolation { .. } => (StatusCode::CONFLICT, "ALREADY_EXISTS", "Record already exists"),
+        DatabaseError::CheckViolation { constraint } => {
+            let code = match constraint.as_deref() {
+                Some("check_email_format") => "INVALID_EMAIL",
+                Some("check_name_length") => "INVALID_NAME",
+                Some("check_message_length") => "INVALID_MESSAGE",
+                Some("check_status_valid") => "INVALID_STATUS",
+                Some("check_submissions_price_nonnegative")
+                | Some("check_submission_quotes_price_nonnegative") => "INVALID_PRICE",
+                Some("check_submissions_currency_valid") => "INVALID_CURRENCY",
+                Some("check_admin_role_valid") => "INVALID_ROLE",
+                Some("check_admin_comment_length") => "INVALID_COMMENT",
+                _ => "CONSTRAINT_VIOLATION",
+            };
+            (StatusCode::BAD_REQUEST, code, "Value violates a data constraint")
+        }
+        DatabaseError::ForeignKeyViolation { .. } => {
+            (StatusCode::BAD_REQUEST, "INVALID_REFERENCE", "Referenced record does not exist")
+        }
+        DatabaseError::NotFound => (StatusCode::NOT_FOUND, "NOT_FOUND", "Record not found"),
+        DatabaseError::PoolTimeout => {
+            (StatusCode::SERVICE_UNAVAILABLE, "DATABASE_UNAVAILABLE", "Database is temporarily unavailable")
+        }
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, "DATABASE_ERROR", "Internal database error"),
+    }
+}
@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio_stream::StreamExt;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::database::postgres::models::Submission;
+use crate::database::redis::redis_interface::RedisDatabase;
+use crate::state::AppState;
+use crate::workflow::SubmissionStatus;
+
+const CHANNEL_PREFIX: &str = "expertiza:events:";
+const EVENT_KINDS: [&str; 6] = [
+    "submission_created",
+    "status_changed",
+    "comment_added",
+    "assignment_changed",
+    "comment_updated",
+    "comment_deleted",
+];
+/// Событий в буфере на случай медленных подписчиков
+const HUB_CAPACITY: usize = 256;
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// Событие панели. Публикуется в Redis, чтобы его получили потоки
+/// `/admin/api/v1/events` всех экземпляров приложения
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PanelEvent {
+    SubmissionCreated {
+        submission: Box<Submission>,
+    },
+    StatusChanged {
+        submission_id: Uuid,
+        from: SubmissionStatus,
+        to: SubmissionStatus,
+    },
+    CommentAdded {
+        submission_id: Uuid,
+        comment_id: Uuid,
+        parent_id: Option<Uuid>,
+    },
+    AssignmentChanged {
+        submission_id: Uuid,
+        assignee_id: Option<Uuid>,
+        assignee_name: Option<String>,
+    },
+    CommentUpdated {
+        submission_id: Uuid,
+        comment_id: Uuid,
+    },
+    CommentDeleted {
+        submission_id: Uuid,
+        comment_id: Uuid,
+    },
+}
+
+impl PanelEvent {
+    /// Имя события в SSE и суффикс канала Redis
+    pub fn kind(&self) -> &'static str {
+        match self {
+            PanelEvent::SubmissionCreated { .. } => EVENT_KINDS[0],
+            PanelEvent::StatusChanged { .. } => EVENT_KINDS[1],
+            PanelEvent::CommentAdded { .. } => EVENT_KINDS[2],
+            PanelEvent::AssignmentChanged { .. } => EVENT_KINDS[3],
+            PanelEvent::CommentUpdated { .. } => EVENT_KINDS[4],
+            PanelEvent::CommentDeleted { .. } => EVENT_KINDS[5],
+        }
+    }
+
+    pub fn submission_id(&self) -> Uuid {
+        match self {
+            PanelEvent::SubmissionCreated { submission } => submission.submission_id,
+            PanelEvent::StatusChanged { submission_id, .. }
+            | PanelEvent::CommentAdded { submission_id, .. }
+            | PanelEvent::AssignmentChanged { submission_id, .. }
+            | PanelEvent::CommentUpdated { submission_id, .. }
+            | PanelEvent::CommentDeleted { submission_id, .. } => *submission_id,
+        }
+    }
+}
+
+/// Сообщение для открытых потоков этого экземпляра
+#[derive(Debug, Clone)]
+pub enum LiveUpdate {
+    Event(PanelEvent),
+    /// События могли потеряться (переподключение к Redis), клиенту
+    /// нужно перезагрузить данные
+    Resync,
+}
+
+/// Раздача событий из Redis открытым SSE-потокам
+pub struct EventHub {
+    sender: broadcast::Sender<LiveUpdate>,
+}
+
+impl Default for EventHub {
+    fn default() -> Self {
+        Self { sender: broadcast::channel(HUB_CAPACITY).0 }
+    }
+}
+
+impl EventHub {
+    pub fn subscribe(&self) -> broadcast::Receiver<LiveUpdate> {
+        self.sender.subscribe()
+    }
+}
+
+/// Публикация события. Ошибка Redis не отменяет уже выполненное действие,
+/// поэтому только пишется в лог
+pub async fn publish(state: &AppState, event: PanelEvent) {
+    let result = match serde_json::to_string(&event) {
+        Ok(payload) => state
+            .db_redis
+            .publish(&format!("{}{}", CHANNEL_PREFIX, event.kind()), &payload)
+            .await
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+
+    if let Err(e) = result {
+        warn!(event = event.kind(), submission_id = %event.submission_id(), error = %e, "Failed to publish panel event");
+    }
+}
+
+/// Новая заявка публикуется уже после автоназначения, чтобы эксперт
+/// получил ее в своем потоке
+pub async fn publish_submission_created(state: &AppState, submission_id: Uuid) {
+    match state.db_postgres.get_submission(submission_id).await {
+        Ok(Some(submission)) => {
+            publish(state, PanelEvent::SubmissionCreated { submission: Box::new(submission) }).await
+        }
+        Ok(None) => {}
+        Err(e) => warn!(submission_id = %submission_id, error = %e, "Failed to load submission for panel event"),
+    }
+}
+
+/// Подписка на каналы событий с переподключением. После восстановления
+/// соединения потоки получают `Resync`
+pub fn spawn_event_listener(redis: Arc<RedisDatabase>, hub: Arc<EventHub>) {
+    let channels: Vec<String> = EVENT_KINDS.iter().map(|kind| format!("{}{}", CHANNEL_PREFIX, kind)).collect();
+
+    tokio::spawn(async move {
+        let mut delay = RECONNECT_DELAY;
+        let mut reconnecting = false;
+        loop {
+            match redis.subscribe(&channels).await {
+                Ok(pubsub) => {
+                    info!("Subscribed to panel events");
+                    delay = RECONNECT_DELAY;
+                    if reconnecting {
+                        let _ = hub.sender.send(LiveUpdate::Resync);
+                    }
+
+                    let mut messages = pubsub.into_on_message();
+                    while let Some(message) = messages.next().await {
+                        let event = message
+                            .get_payload::<String>()
+                            .map_err(|e| e.to_string())
+                            .and_then(|payload| serde_json::from_str::<PanelEvent>(&payload).map_err(|e| e.to_string()));
+                        match event {
+                            // Ошибка отправки означает лишь, что открытых потоков нет
+                            Ok(event) => {
+                                let _ = hub.sender.send(LiveUpdate::Event(event));
+                            }
+                            Err(e) => warn!(channel = %message.get_channel_name(), error = %e, "Invalid panel event"),
+                        }
+                    }
+                    warn!("Panel events subscription closed");
+                }
+                Err(e) => warn!(error = %e, "Failed to subscribe to panel events"),
+            }
+
+            reconnecting = true;
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+        }
+    });
+}
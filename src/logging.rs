@@ -1,13 +1,16 @@
+use std::net::IpAddr;
 use std::time::Duration;
 use axum::{
     http::{HeaderMap, Request, Response}
 };
+use ipnet::IpNet;
 use tower_http::classify::ServerErrorsFailureClass;
 use tracing::{info, warn, error, debug, Span};
 use tracing_subscriber::{
     prelude::*,
     EnvFilter,
 };
+use crate::middleware::ClientIp;
 
 pub async fn setup_tracing() {
     let fmt_layer = tracing_subscriber::fmt::layer()
@@ -51,7 +54,10 @@ pub fn log_request<B>(req: &Request<B>, span: &Span) {
         .and_then(|v| v.to_str().ok())
         .unwrap_or("0");
 
-    let client_ip = get_client_ip(headers);
+    let client_ip = req
+        .extensions()
+        .get::<ClientIp>()
+        .map_or_else(|| "unknown".to_string(), |ip| ip.0.to_string());
     
     span.record("http.method", method.as_str());
     span.record("http.url", uri.to_string().as_str());
@@ -159,25 +165,31 @@ pub fn log_failure(failure: ServerErrorsFailureClass, latency: Duration, _span:
     }
 }
 
-fn get_client_ip(headers: &HeaderMap) -> String {
-    let ip_headers: [&'static str; 5] = [
-        "x-forwarded-for",
-        "x-real-ip", 
-        "cf-connecting-ip",
-        "x-client-ip",
-        "x-forwarded",
-    ];
-    
-    for header_name in &ip_headers {
-        if let Some(value) = headers.get(*header_name) {
-            if let Ok(ip_str) = value.to_str() {
-                let ip = ip_str.split(',').next().unwrap_or(ip_str).trim();
-                if !ip.is_empty() && ip != "unknown" {
-                    return ip.to_string();
-                }
+/// Реальный адрес клиента. Заголовки прокси учитываются, только если соединение
+/// пришло от доверенного прокси: X-Forwarded-For разбирается справа налево
+/// до первого недоверенного адреса
+pub fn resolve_client_ip(peer: IpAddr, headers: &HeaderMap, trusted_proxies: &[IpNet]) -> IpAddr {
+    let is_trusted = |ip: &IpAddr| trusted_proxies.iter().any(|net| net.contains(ip));
+    if !is_trusted(&peer) {
+        return peer;
+    }
+
+    if let Some(forwarded) = headers.get("x-forwarded-for").and_then(|v| v.to_str().ok()) {
+        let mut client = peer;
+        for hop in forwarded.rsplit(',') {
+            let Ok(ip) = hop.trim().parse::<IpAddr>() else {
+                break;
+            };
+            client = ip;
+            if !is_trusted(&ip) {
+                break;
             }
         }
+        return client;
     }
-    
-    "unknown".to_string()
-}
\ No newline at end of file
+
+    ["x-real-ip", "cf-connecting-ip"]
+        .iter()
+        .find_map(|name| headers.get(*name)?.to_str().ok()?.trim().parse().ok())
+        .unwrap_or(peer)
+}
@@ -0,0 +1,175 @@
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use std::path::Path;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::config::{MailConfig, SmtpTls};
+use crate::template;
+use crate::database::postgres::models::{
+    CreateSubmissionRequest, NotificationEmail, OutboxEntry, OutboxMessage, OutboxRecipient,
+};
+
+const NEW_SUBMISSION_TEMPLATE: &str = "new_submission.txt";
+const SUBMISSION_RECEIVED_TEMPLATE: &str = "submission_received.txt";
+const NOTIFICATION_TEMPLATE: &str = "notification.txt";
+const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, thiserror::Error)]
+pub enum MailError {
+    #[error("Invalid address: {0}")]
+    Address(#[from] lettre::address::AddressError),
+    #[error("Invalid message: {0}")]
+    Message(#[from] lettre::error::Error),
+    #[error("SMTP error: {0}")]
+    Smtp(#[from] lettre::transport::smtp::Error),
+}
+
+impl MailError {
+    /// Повтор не поможет: неверный адрес или постоянный отказ сервера (5xx)
+    pub fn is_permanent(&self) -> bool {
+        match self {
+            MailError::Address(_) | MailError::Message(_) => true,
+            MailError::Smtp(e) => e.is_permanent(),
+        }
+    }
+}
+
+/// Шаблоны писем из `MAIL_TEMPLATES_DIR`. Первая строка шаблона —
+/// `Subject: ...`, остальное — текст письма. Плейсхолдеры вида `{{name}}`
+pub struct MailTemplates {
+    new_submission: String,
+    submission_received: String,
+    notification: String,
+}
+
+impl MailTemplates {
+    pub async fn load(dir: &str) -> std::io::Result<Self> {
+        let read = |name: &str| tokio::fs::read_to_string(Path::new(dir).join(name));
+
+        Ok(Self {
+            new_submission: read(NEW_SUBMISSION_TEMPLATE).await?,
+            submission_received: read(SUBMISSION_RECEIVED_TEMPLATE).await?,
+            notification: read(NOTIFICATION_TEMPLATE).await?,
+        })
+    }
+
+    /// Письма о новой заявке: дежурным администраторам и подтверждение
+    /// клиенту, если заявку оставил он сам. Адрес клиента не подтвержден,
+    /// поэтому в подтверждение попадает только номер заявки, а не текст формы
+    pub fn submission_emails(&self, submission: &CreateSubmissionRequest, acknowledge_client: bool) -> Vec<OutboxEntry> {
+        let submission_id = submission.submission_id.to_string();
+        let values = [
+            ("name", submission.name.as_str()),
+            ("email", submission.email.as_str()),
+            ("phone", submission.phone.as_deref().unwrap_or("не указан")),
+            ("message", submission.message.as_str()),
+            ("submission_id", submission_id.as_str()),
+        ];
+
+        let mut emails = vec![outbox_entry(&self.new_submission, &values, OutboxRecipient::OnDutyAdmins)];
+        if acknowledge_client {
+            emails.push(outbox_entry(
+                &self.submission_received,
+                &[("submission_id", submission_id.as_str())],
+                OutboxRecipient::Email(submission.email.clone()),
+            ));
+        }
+        emails
+    }
+
+    /// Письмо с уведомлением из центра уведомлений
+    pub fn notification_email(&self, title: &str, message: &str, submission_id: Uuid) -> NotificationEmail {
+        let (subject, body) = render(&self.notification, &[
+            ("title", title),
+            ("message", message),
+            ("submission_id", &submission_id.to_string()),
+        ]);
+        NotificationEmail { subject, body }
+    }
+}
+
+fn outbox_entry(template: &str, values: &[(&str, &str)], recipient: OutboxRecipient) -> OutboxEntry {
+    let (subject, body) = render(template, values);
+    OutboxEntry {
+        recipient,
+        subject: Some(subject),
+        body,
+    }
+}
+
+/// Подстановка значений. Возвращает тему и текст письма. Шаблон делится
+/// на тему и текст до подстановки, а из значений в теме удаляются переводы
+/// строк и управляющие символы: значение не может изменить заголовки письма
+fn render(template: &str, values: &[(&str, &str)]) -> (String, String) {
+    let (first_line, body) = template.split_once('\n').unwrap_or((template, ""));
+    let subject_template = first_line.strip_prefix("Subject:").unwrap_or(first_line).trim();
+    let lookup = |key: &str| values.iter().find(|(k, _)| *k == key).map(|(_, value)| *value);
+
+    let subject = template::fill(subject_template, |key| {
+        lookup(key).map(|value| value.chars().filter(|c| !c.is_control()).collect())
+    });
+    let body = template::fill(body, |key| lookup(key).map(str::to_string));
+
+    (subject, body)
+}
+
+/// Отправка писем через SMTP
+pub struct Mailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl Mailer {
+    pub fn new(config: &MailConfig) -> Result<Self, MailError> {
+        let builder = match config.tls {
+            SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host),
+            SmtpTls::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)?,
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)?,
+        };
+
+        let builder = builder.port(config.port).timeout(Some(SMTP_TIMEOUT));
+        let builder = match (&config.username, &config.password) {
+            (Some(username), Some(password)) => {
+                builder.credentials(Credentials::new(username.clone(), password.clone()))
+            }
+            _ => builder,
+        };
+
+        Ok(Self {
+            transport: builder.build(),
+            from: config.from.parse()?,
+        })
+    }
+
+    pub async fn send(&self, message: &OutboxMessage) -> Result<(), MailError> {
+        let email = Message::builder()
+            .from(self.from.clone())
+            .to(message.recipient.parse()?)
+            .subject(message.subject.as_deref().unwrap_or_default())
+            .header(ContentType::TEXT_PLAIN)
+            .body(message.body.clone())?;
+
+        self.transport.send(email).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+
+    #[test]
+    fn value_cannot_move_the_subject_boundary() {
+        let (subject, body) = render(
+            "Subject: Новая заявка от {{name}}\nИмя: {{name}}",
+            &[("name", "Иван\nBcc: attacker@example.com")],
+        );
+
+        assert_eq!(subject, "Новая заявка от ИванBcc: attacker@example.com");
+        assert_eq!(body, "Имя: Иван\nBcc: attacker@example.com");
+    }
+}
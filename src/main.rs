@@ -7,11 +7,29 @@ mod config;
 mod logging;
 mod server;
 mod error;
+mod roles;
+mod password;
+mod totp;
+mod spam;
+mod pow;
+mod validation;
+mod quote;
+mod workflow;
+mod audit;
+mod mail;
+mod outbox;
+mod telegram;
+mod events;
+mod notifications;
+mod storage;
+mod uploads;
+mod template;
 
 use crate::config::Config;
 use crate::logging::setup_tracing;
 use crate::server::create_app;
 use axum::serve;
+use std::net::SocketAddr;
 use tokio::net::TcpListener;
 use tracing::info;
 
@@ -26,7 +44,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     let listener: TcpListener = TcpListener::bind(&config.server_address).await?;
     info!("🚀 Starting server http://{}", config.server_address);
-    serve(listener, app).await?;
+    serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
 
     Ok(())
 }
\ No newline at end of file
@@ -1,30 +1,163 @@
 use axum::{
-    extract::{Request},
+    body::to_bytes,
+    extract::{ConnectInfo, Request, State},
         http::{
-        header::{CONTENT_SECURITY_POLICY, X_CONTENT_TYPE_OPTIONS, X_FRAME_OPTIONS, X_XSS_PROTECTION},
-        HeaderValue, StatusCode
+        header::{
+            CONTENT_LENGTH, CONTENT_SECURITY_POLICY, CONTENT_TYPE, RETRY_AFTER,
+            X_CONTENT_TYPE_OPTIONS, X_FRAME_OPTIONS, X_XSS_PROTECTION,
+        },
+        HeaderMap, HeaderName, HeaderValue, StatusCode,
     },
-    response::Response,
+    response::{IntoResponse, Response},
     middleware::{Next},
 };
-use std::{
-    collections::HashMap,
-    sync::{Arc},
-    time::{Duration, Instant},
-};
-use tokio::{
-    sync::Mutex,
-    time::sleep,
-};
+use chrono::Utc;
+use redis::Script;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use uuid::Uuid;
 
+use crate::error::{error_response, AppError};
+use crate::logging::resolve_client_ip;
+use crate::state::AppState;
 
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+/// Максимальный размер текстового тела ошибки, которое переносится в `message`
+const PLAIN_ERROR_BODY_LIMIT: usize = 4096;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Идентификатор запроса, выданный `request_id_middleware`
 #[derive(Debug, Clone)]
-struct RateLimitEntry {
-    count: u32,
-    window_start: Instant,
+pub struct RequestId(pub String);
+
+/// Идентификатор текущего запроса, если код выполняется внутри его обработки
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Адрес клиента с учетом доверенных прокси, вычисляется `client_ip_middleware`
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub IpAddr);
+
+/// Политика ограничения частоты запросов: не более `limit` запросов за `window` секунд
+#[derive(Debug)]
+pub struct RateLimitPolicy {
+    name: &'static str,
+    limit: u32,
+    window: u64,
+}
+
+const CONTACT_FORM_POLICY: RateLimitPolicy = RateLimitPolicy { name: "contact", limit: 5, window: 600 };
+const LOGIN_POLICY: RateLimitPolicy = RateLimitPolicy { name: "login", limit: 10, window: 300 };
+const ADMIN_API_POLICY: RateLimitPolicy = RateLimitPolicy { name: "admin_api", limit: 300, window: 60 };
+const DEFAULT_POLICY: RateLimitPolicy = RateLimitPolicy { name: "default", limit: 100, window: 60 };
+
+impl RateLimitPolicy {
+    /// Политика для пути запроса. Статика не ограничивается
+    fn for_path(path: &str) -> Option<&'static RateLimitPolicy> {
+        match path {
+            "/api/v1/contact-submissions" => Some(&CONTACT_FORM_POLICY),
+            "/admin/api/v1/change-password" => Some(&LOGIN_POLICY),
+            p if p.starts_with("/admin/api/v1/login") => Some(&LOGIN_POLICY),
+            p if p.starts_with("/admin/api/") => Some(&ADMIN_API_POLICY),
+            p if p.starts_with("/static/") || p.starts_with("/admin/static/") => None,
+            _ => Some(&DEFAULT_POLICY),
+        }
+    }
 }
 
-type RateLimitStore = Arc<Mutex<HashMap<String, RateLimitEntry>>>;
+/// Результат проверки лимита
+struct RateLimitDecision {
+    allowed: bool,
+    remaining: u32,
+    /// Секунд до освобождения слота в окне
+    reset: u64,
+}
+
+/// Определение адреса клиента по `ConnectInfo` и заголовкам доверенных прокси
+pub async fn client_ip_middleware(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED), |info| info.0.ip());
+    let client_ip = resolve_client_ip(peer, request.headers(), &state.trusted_proxies);
+
+    request.extensions_mut().insert(ClientIp(client_ip));
+    next.run(request).await
+}
+
+/// Выдача идентификатора запроса. Он попадает в span `http_request`,
+/// в тело ошибок и в заголовок `X-Request-Id` ответа
+pub async fn request_id_middleware(mut request: Request, next: Next) -> Response {
+    let request_id = Uuid::new_v4().to_string();
+    request.extensions_mut().insert(RequestId(request_id.clone()));
+
+    let mut response = REQUEST_ID.scope(request_id.clone(), next.run(request)).await;
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+/// Ошибки API, сформированные не через `AppError` (отказы экстракторов axum,
+/// неизвестный маршрут, таймаут), приводятся к общему формату
+pub async fn error_envelope_middleware(request: Request, next: Next) -> Response {
+    let path = request.uri().path();
+    let is_api = path.starts_with("/api/") || path.starts_with("/admin/api/");
+    let response = next.run(request).await;
+
+    let status = response.status();
+    let is_json = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("application/json"));
+    if !is_api || is_json || !(status.is_client_error() || status.is_server_error()) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let is_plain_text = parts
+        .headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("text/plain"));
+
+    let body_text = if is_plain_text && !status.is_server_error() {
+        to_bytes(body, PLAIN_ERROR_BODY_LIMIT)
+            .await
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes.to_vec()).ok())
+            .filter(|text| !text.trim().is_empty())
+    } else {
+        None
+    };
+    let message = body_text.unwrap_or_else(|| status.canonical_reason().unwrap_or("Error").to_string());
+
+    let code = match status {
+        StatusCode::BAD_REQUEST => "BAD_REQUEST",
+        StatusCode::NOT_FOUND => "NOT_FOUND",
+        StatusCode::METHOD_NOT_ALLOWED => "METHOD_NOT_ALLOWED",
+        StatusCode::REQUEST_TIMEOUT => "TIMEOUT",
+        StatusCode::PAYLOAD_TOO_LARGE => "PAYLOAD_TOO_LARGE",
+        StatusCode::UNSUPPORTED_MEDIA_TYPE => "UNSUPPORTED_MEDIA_TYPE",
+        StatusCode::UNPROCESSABLE_ENTITY => "INVALID_BODY",
+        s if s.is_server_error() => "INTERNAL_ERROR",
+        _ => "HTTP_ERROR",
+    };
+
+    let mut envelope = error_response(status, code, message, None);
+    parts.headers.remove(CONTENT_TYPE);
+    parts.headers.remove(CONTENT_LENGTH);
+    envelope.headers_mut().extend(parts.headers);
+    envelope
+}
 
 pub async fn security_headers_middleware(request: Request, next: Next) -> Response {
     let response = next.run(request).await;
@@ -84,42 +217,93 @@ pub async fn security_headers_middleware(request: Request, next: Next) -> Respon
     response
 }
 
+/// Ограничение частоты запросов по адресу клиента (скользящее окно в Redis).
+/// При недоступности Redis запросы пропускаются, чтобы не блокировать сайт
 pub async fn rate_limit_middleware(
+    State(state): State<AppState>,
     request: Request,
     next: Next,
-) -> Result<Response, StatusCode> {
-    use std::sync::OnceLock;
-    static RATE_LIMIT_STORE: OnceLock<RateLimitStore> = OnceLock::new();
-    let rate_limit_store = RATE_LIMIT_STORE.get_or_init(|| Arc::new(Mutex::new(HashMap::new())));
-    let now = Instant::now();
-    let window_duration = Duration::from_secs(60); // 1 минута
-    let max_requests = 100; // максимум 100 запросов в минуту
-    
-    let mut store = rate_limit_store.lock().await;
-    
-    let client_ip = "127.0.0.1".to_string(); // TODO: заменить на реальный IP клиента
-    
-    let entry = store.entry(client_ip.clone()).or_insert(RateLimitEntry {
-        count: 0,
-        window_start: now,
-    });
-    
-    // сброс, если прошло больше минуты
-    if now.duration_since(entry.window_start) > window_duration {
-        entry.count = 0;
-        entry.window_start = now;
-    }
-    
-    entry.count += 1;
-    
-    if entry.count > max_requests {
-        // небольшая задержка
-        drop(store);
-        sleep(Duration::from_millis(1000)).await;
-        return Err(StatusCode::TOO_MANY_REQUESTS);
-    }
-    
-    drop(store);
-    Ok(next.run(request).await)
+) -> Response {
+    let Some(policy) = RateLimitPolicy::for_path(request.uri().path()) else {
+        return next.run(request).await;
+    };
+    let client_ip = request
+        .extensions()
+        .get::<ClientIp>()
+        .map_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED), |ip| ip.0);
+
+    let decision = match check_rate_limit(&state, policy, client_ip).await {
+        Ok(decision) => decision,
+        Err(e) => {
+            tracing::warn!(error = %e, "Rate limiter unavailable, request allowed");
+            return next.run(request).await;
+        }
+    };
+
+    let mut response = if decision.allowed {
+        next.run(request).await
+    } else {
+        tracing::warn!(client_ip = %client_ip, policy = policy.name, "Rate limit exceeded");
+        let mut response = AppError::too_many_requests("Too many requests, try again later").into_response();
+        response.headers_mut().insert(RETRY_AFTER, HeaderValue::from(decision.reset));
+        response
+    };
+
+    set_rate_limit_headers(response.headers_mut(), policy, &decision);
+    response
 }
 
+async fn check_rate_limit(
+    state: &AppState,
+    policy: &RateLimitPolicy,
+    client_ip: IpAddr,
+) -> Result<RateLimitDecision, AppError> {
+    // Журнал запросов в sorted set: метка времени в мс как score
+    let script = Script::new(
+        r#"
+        local now = tonumber(ARGV[1])
+        local window = tonumber(ARGV[2])
+        local limit = tonumber(ARGV[3])
+        redis.call('ZREMRANGEBYSCORE', KEYS[1], '-inf', now - window)
+        local count = redis.call('ZCARD', KEYS[1])
+        local allowed = 0
+        if count < limit then
+            redis.call('ZADD', KEYS[1], now, ARGV[4])
+            count = count + 1
+            allowed = 1
+        end
+        redis.call('PEXPIRE', KEYS[1], window)
+        local oldest = redis.call('ZRANGE', KEYS[1], 0, 0, 'WITHSCORES')
+        local reset = window
+        if oldest[2] then reset = tonumber(oldest[2]) + window - now end
+        return {allowed, count, reset}
+        "#,
+    );
+
+    let window_ms = policy.window * 1000;
+    let mut conn = state.db_redis.get_connection().await?;
+    let (allowed, count, reset_ms): (i64, u32, u64) = script
+        .key(format!("rate_limit:{}:{}", policy.name, client_ip))
+        .arg(Utc::now().timestamp_millis())
+        .arg(window_ms)
+        .arg(policy.limit)
+        .arg(Uuid::new_v4().to_string())
+        .invoke_async(&mut conn)
+        .await?;
+
+    Ok(RateLimitDecision {
+        allowed: allowed == 1,
+        remaining: policy.limit.saturating_sub(count),
+        reset: reset_ms.div_ceil(1000).max(1),
+    })
+}
+
+/// Заголовки по draft-ietf-httpapi-ratelimit-headers
+fn set_rate_limit_headers(headers: &mut HeaderMap, policy: &RateLimitPolicy, decision: &RateLimitDecision) {
+    headers.insert("RateLimit-Limit", HeaderValue::from(policy.limit));
+    headers.insert("RateLimit-Remaining", HeaderValue::from(decision.remaining));
+    headers.insert("RateLimit-Reset", HeaderValue::from(decision.reset));
+    if let Ok(value) = HeaderValue::from_str(&format!("{};w={}", policy.limit, policy.window)) {
+        headers.insert("RateLimit-Policy", value);
+    }
+}
@@ -0,0 +1,198 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::database::postgres::models::NewNotification;
+use crate::database::postgres::postgres_interface::PostgresDatabase;
+use crate::mail::MailTemplates;
+use crate::state::AppState;
+use crate::workflow::SubmissionStatus;
+
+const DEADLINE_CHECK_INTERVAL: Duration = Duration::from_secs(300);
+/// За сколько до срока исполнитель получает уведомление
+const DEADLINE_WARNING_SECS: i64 = 24 * 3600;
+const DEADLINE_BATCH_SIZE: i64 = 100;
+/// Длина цитаты комментария в уведомлении об упоминании
+const MENTION_EXCERPT_CHARS: usize = 200;
+
+/// Вид уведомления (колонка `notifications.kind`, ограничение `check_notification_kind_valid`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    Assignment,
+    Mention,
+    Deadline,
+    StatusChange,
+}
+
+impl NotificationKind {
+    pub const ALL: [NotificationKind; 4] = [
+        NotificationKind::Assignment,
+        NotificationKind::Mention,
+        NotificationKind::Deadline,
+        NotificationKind::StatusChange,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::Assignment => "assignment",
+            NotificationKind::Mention => "mention",
+            NotificationKind::Deadline => "deadline",
+            NotificationKind::StatusChange => "status_change",
+        }
+    }
+
+    /// Заголовок уведомления и тема письма
+    pub fn title(&self) -> &'static str {
+        match self {
+            NotificationKind::Assignment => "Вам назначена заявка",
+            NotificationKind::Mention => "Вас упомянули в комментарии",
+            NotificationKind::Deadline => "Приближается срок по заявке",
+            NotificationKind::StatusChange => "Изменен статус вашей заявки",
+        }
+    }
+}
+
+impl fmt::Display for NotificationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl TryFrom<String> for NotificationKind {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        NotificationKind::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == value)
+            .ok_or_else(|| format!("Unknown notification kind: {}", value))
+    }
+}
+
+/// Уведомление с текстом для входящих и, если почта настроена, письмом.
+/// Отправлять ли письмо, решают настройки получателя
+fn compose(
+    templates: Option<&MailTemplates>,
+    kind: NotificationKind,
+    submission_id: Uuid,
+    actor_id: Option<Uuid>,
+    message: String,
+) -> NewNotification {
+    NewNotification {
+        kind,
+        submission_id,
+        actor_id,
+        email: templates.map(|templates| templates.notification_email(kind.title(), &message, submission_id)),
+        message,
+    }
+}
+
+/// Упоминание в комментарии. Получателей определяет запрос сохранения комментария
+pub fn mention(state: &AppState, submission_id: Uuid, actor_id: Uuid, actor_name: &str, text: &str) -> NewNotification {
+    let excerpt: String = if text.chars().count() > MENTION_EXCERPT_CHARS {
+        text.chars().take(MENTION_EXCERPT_CHARS - 1).chain(['…']).collect()
+    } else {
+        text.to_string()
+    };
+
+    compose(
+        state.mail_templates.as_deref(),
+        NotificationKind::Mention,
+        submission_id,
+        Some(actor_id),
+        format!("{}: «{}»", actor_name, excerpt),
+    )
+}
+
+/// Назначение заявки. `actor` — `None` при автоназначении.
+/// Назначивший заявку сам на себя не уведомляется
+pub async fn assignment(state: &AppState, submission_id: Uuid, assignee_id: Uuid, actor: Option<(Uuid, &str)>) {
+    if actor.is_some_and(|(actor_id, _)| actor_id == assignee_id) {
+        return;
+    }
+
+    let message = match actor {
+        Some((_, actor_name)) => format!("Назначил: {}", actor_name),
+        None => "Заявка назначена автоматически".to_string(),
+    };
+    let notification = compose(
+        state.mail_templates.as_deref(),
+        NotificationKind::Assignment,
+        submission_id,
+        actor.map(|(actor_id, _)| actor_id),
+        message,
+    );
+    send(&state.db_postgres, &notification, assignee_id).await;
+}
+
+/// Смена статуса заявки другим администратором уведомляет ее исполнителя
+pub async fn status_change(
+    state: &AppState,
+    submission_id: Uuid,
+    assignee_id: Option<Uuid>,
+    from: SubmissionStatus,
+    to: SubmissionStatus,
+    actor: (Uuid, &str),
+) {
+    let Some(assignee_id) = assignee_id.filter(|assignee_id| *assignee_id != actor.0) else {
+        return;
+    };
+
+    let notification = compose(
+        state.mail_templates.as_deref(),
+        NotificationKind::StatusChange,
+        submission_id,
+        Some(actor.0),
+        format!("{} → {} ({})", from.label(), to.label(), actor.1),
+    );
+    send(&state.db_postgres, &notification, assignee_id).await;
+}
+
+/// Ошибка не отменяет действие, вызвавшее уведомление, поэтому только пишется в лог
+async fn send(db: &PostgresDatabase, notification: &NewNotification, recipient: Uuid) {
+    if let Err(e) = db.create_notification(notification, recipient).await {
+        warn!(
+            kind = %notification.kind,
+            submission_id = %notification.submission_id,
+            error = %e,
+            "Failed to create notification"
+        );
+    }
+}
+
+/// Фоновая проверка сроков: исполнитель открытой заявки получает
+/// уведомление за сутки до срока. Заявка отмечается в той же транзакции,
+/// поэтому несколько экземпляров приложения не дублируют уведомления
+pub fn spawn_deadline_worker(db: Arc<PostgresDatabase>, templates: Option<Arc<MailTemplates>>) {
+    tokio::spawn(async move {
+        loop {
+            let result = db
+                .create_deadline_notifications(DEADLINE_WARNING_SECS, DEADLINE_BATCH_SIZE, |submission_id, due_at| {
+                    compose(templates.as_deref(), NotificationKind::Deadline, submission_id, None, deadline_message(due_at))
+                })
+                .await;
+
+            match result {
+                Ok(0) => {}
+                Ok(count) => {
+                    info!(count, "Deadline notifications created");
+                    // Полная пачка: возможно, есть еще заявки
+                    if count as i64 == DEADLINE_BATCH_SIZE {
+                        continue;
+                    }
+                }
+                Err(e) => warn!(error = %e, "Failed to check submission deadlines"),
+            }
+            tokio::time::sleep(DEADLINE_CHECK_INTERVAL).await;
+        }
+    });
+}
+
+fn deadline_message(due_at: DateTime<Utc>) -> String {
+    format!("Срок: {} UTC", due_at.format("%d.%m.%Y %H:%M"))
+}
@@ -0,0 +1,155 @@
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::database::postgres::models::{
+    CreateSubmissionRequest, OutboxEntry, OutboxMessage, OUTBOX_CHANNEL_EMAIL, OUTBOX_CHANNEL_TELEGRAM,
+};
+use crate::database::postgres::postgres_interface::PostgresDatabase;
+use crate::mail::{MailError, Mailer};
+use crate::state::AppState;
+use crate::telegram::{TelegramClient, TelegramError};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Общий предел одной доставки: SMTP_TIMEOUT ограничивает каждую команду
+/// SMTP по отдельности, а письмо требует нескольких команд
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(180);
+/// Сообщение берется по одному и блокируется дольше, чем может длиться
+/// его доставка, поэтому другой обработчик не отправит его повторно
+const LOCK_DURATION: Duration = Duration::from_secs(DELIVERY_TIMEOUT.as_secs() * 2);
+/// После стольких неудачных попыток сообщение помечается как `failed`
+const MAX_ATTEMPTS: i32 = 8;
+const FIRST_RETRY_DELAY_SECS: i64 = 30;
+const MAX_RETRY_DELAY_SECS: i64 = 3600;
+
+/// Каналы доставки. Сообщения отключенного канала остаются в очереди
+pub struct OutboxSenders {
+    pub mailer: Option<Mailer>,
+    pub telegram: Option<TelegramClient>,
+}
+
+impl OutboxSenders {
+    fn channels(&self) -> Vec<&'static str> {
+        let mut channels = Vec::new();
+        if self.mailer.is_some() {
+            channels.push(OUTBOX_CHANNEL_EMAIL);
+        }
+        if self.telegram.is_some() {
+            channels.push(OUTBOX_CHANNEL_TELEGRAM);
+        }
+        channels
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+enum DeliveryError {
+    #[error(transparent)]
+    Mail(#[from] MailError),
+    #[error(transparent)]
+    Telegram(#[from] TelegramError),
+    #[error("Unsupported outbox channel: {0}")]
+    Channel(String),
+    #[error("Delivery timed out after {} s", .0.as_secs())]
+    Timeout(Duration),
+}
+
+impl DeliveryError {
+    fn is_permanent(&self) -> bool {
+        match self {
+            DeliveryError::Mail(e) => e.is_permanent(),
+            DeliveryError::Telegram(e) => e.is_permanent(),
+            DeliveryError::Channel(_) => true,
+            DeliveryError::Timeout(_) => false,
+        }
+    }
+}
+
+/// Уведомления о новой заявке, которые сохраняются вместе с ней
+pub fn submission_notifications(
+    state: &AppState,
+    submission: &CreateSubmissionRequest,
+    acknowledge_client: bool,
+) -> Vec<OutboxEntry> {
+    let mut entries = Vec::new();
+    if let Some(templates) = &state.mail_templates {
+        entries.extend(templates.submission_emails(submission, acknowledge_client));
+    }
+    if let Some(bot) = &state.telegram {
+        entries.push(bot.submission_post(submission));
+    }
+    entries
+}
+
+/// Фоновая отправка сообщений из outbox. Недоступность почтового сервера
+/// или Telegram не влияет на прием заявок: сообщения ждут в таблице
+/// и отправляются повторно с увеличивающейся паузой
+pub fn spawn_outbox_worker(db: Arc<PostgresDatabase>, senders: OutboxSenders) {
+    let channels = senders.channels();
+    if channels.is_empty() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        info!(channels = ?channels, "Outbox worker started");
+        loop {
+            match db.claim_outbox_message(&channels, LOCK_DURATION.as_secs() as i64).await {
+                // Следующее сообщение берется сразу, пока очередь не опустеет
+                Ok(Some(message)) => {
+                    deliver(&db, &senders, &message).await;
+                    continue;
+                }
+                Ok(None) => {}
+                Err(e) => warn!(error = %e, "Failed to read outbox"),
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+async fn send(senders: &OutboxSenders, message: &OutboxMessage) -> Result<(), DeliveryError> {
+    match (message.channel.as_str(), &senders.mailer, &senders.telegram) {
+        (OUTBOX_CHANNEL_EMAIL, Some(mailer), _) => Ok(mailer.send(message).await?),
+        (OUTBOX_CHANNEL_TELEGRAM, _, Some(telegram)) => Ok(telegram
+            .send_submission(&message.recipient, &message.body, message.submission_id)
+            .await?),
+        (channel, _, _) => Err(DeliveryError::Channel(channel.to_string())),
+    }
+}
+
+async fn deliver(db: &PostgresDatabase, senders: &OutboxSenders, message: &OutboxMessage) {
+    let sent = tokio::time::timeout(DELIVERY_TIMEOUT, send(senders, message))
+        .await
+        .unwrap_or(Err(DeliveryError::Timeout(DELIVERY_TIMEOUT)));
+    let result = match sent {
+        Ok(()) => {
+            info!(outbox_id = %message.id, channel = %message.channel, "Outbox message sent");
+            db.mark_outbox_sent(message.id).await
+        }
+        Err(e) => {
+            let attempt = message.attempts + 1;
+            let retry_at = (!e.is_permanent() && attempt < MAX_ATTEMPTS)
+                .then(|| Utc::now() + retry_delay(attempt));
+            warn!(
+                outbox_id = %message.id,
+                channel = %message.channel,
+                attempt,
+                retry = retry_at.is_some(),
+                error = %e,
+                "Outbox delivery failed"
+            );
+            db.mark_outbox_failed(message.id, &e.to_string(), retry_at).await
+        }
+    };
+
+    if let Err(e) = result {
+        warn!(outbox_id = %message.id, error = %e, "Failed to update outbox message");
+    }
+}
+
+/// 30 с, 1 мин, 2 мин... но не больше часа
+fn retry_delay(attempt: i32) -> chrono::Duration {
+    let exponent = (attempt - 1).clamp(0, 16) as u32;
+    let secs = FIRST_RETRY_DELAY_SECS.saturating_mul(1 << exponent).min(MAX_RETRY_DELAY_SECS);
+    chrono::Duration::seconds(secs)
+}
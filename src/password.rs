@@ -0,0 +1,53 @@
+use crate::error::AppError;
+
+pub const MIN_PASSWORD_LENGTH: usize = 12;
+/// bcrypt учитывает только первые 72 байта пароля
+pub const MAX_PASSWORD_BYTES: usize = 72;
+
+/// Проверка, что значение уже является bcrypt-хэшем (`$2a$`, `$2b$`, `$2x$`, `$2y$`)
+pub fn is_bcrypt_hash(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    value.len() == 60
+        && bytes[0] == b'$'
+        && bytes[1] == b'2'
+        && matches!(bytes[2], b'a' | b'b' | b'x' | b'y')
+        && bytes[3] == b'$'
+        && bytes[4].is_ascii_digit()
+        && bytes[5].is_ascii_digit()
+        && bytes[6] == b'$'
+}
+
+/// Серверные правила сложности пароля администратора
+pub fn validate_password_strength(password: &str, username: &str) -> Result<(), AppError> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(AppError::bad_request("Password must contain at least 12 characters"));
+    }
+    if password.len() > MAX_PASSWORD_BYTES {
+        return Err(AppError::bad_request("Password must not exceed 72 bytes"));
+    }
+    // Форма входа обрезает пробелы по краям, такой пароль нельзя будет ввести
+    if password.trim() != password {
+        return Err(AppError::bad_request("Password must not start or end with whitespace"));
+    }
+
+    let has_lower = password.chars().any(char::is_lowercase);
+    let has_upper = password.chars().any(char::is_uppercase);
+    let has_digit = password.chars().any(|c| c.is_ascii_digit());
+    let has_symbol = password.chars().any(|c| !c.is_alphanumeric() && !c.is_whitespace());
+    let classes = [has_lower, has_upper, has_digit, has_symbol]
+        .iter()
+        .filter(|present| **present)
+        .count();
+    if classes < 3 {
+        return Err(AppError::bad_request(
+            "Password must contain at least three of: lowercase, uppercase, digits, symbols",
+        ));
+    }
+
+    let username = username.trim().to_lowercase();
+    if !username.is_empty() && password.to_lowercase().contains(&username) {
+        return Err(AppError::bad_request("Password must not contain the username"));
+    }
+
+    Ok(())
+}
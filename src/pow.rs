@@ -0,0 +1,64 @@
+use rand::{distr::Alphanumeric, rng, Rng};
+use redis::AsyncCommands;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+const POW_CHALLENGE_PREFIX: &str = "pow";
+/// Число ведущих нулевых бит SHA-256(challenge:nonce)
+pub const POW_DIFFICULTY: u32 = 14;
+pub const POW_CHALLENGE_TTL: u64 = 900;
+
+#[derive(Serialize)]
+pub struct PowChallengeResponse {
+    pub challenge: String,
+    pub difficulty: u32,
+    pub expires_in: u64,
+}
+
+/// Proof-of-work для публичной формы: клиент перебирает nonce,
+/// пока хэш не наберет нужное число ведущих нулей
+pub struct PowService {
+    redis_con: redis::aio::ConnectionManager,
+}
+
+impl PowService {
+    pub fn new(redis_con: redis::aio::ConnectionManager) -> Self {
+        Self { redis_con }
+    }
+
+    pub async fn create_challenge(&mut self) -> Result<String, redis::RedisError> {
+        let challenge: String = rng()
+            .sample_iter(&Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect();
+
+        let key = format!("{}:{}", POW_CHALLENGE_PREFIX, challenge);
+        let _: () = self.redis_con.set_ex(&key, POW_DIFFICULTY, POW_CHALLENGE_TTL).await?;
+
+        Ok(challenge)
+    }
+
+    /// Проверка решения. Задача одноразовая и удаляется при успешной проверке
+    pub async fn verify_solution(&mut self, challenge: &str, nonce: &str) -> Result<bool, redis::RedisError> {
+        if leading_zero_bits(challenge, nonce) < POW_DIFFICULTY {
+            return Ok(false);
+        }
+
+        let key = format!("{}:{}", POW_CHALLENGE_PREFIX, challenge);
+        let removed: i64 = self.redis_con.del(&key).await?;
+        Ok(removed == 1)
+    }
+}
+
+fn leading_zero_bits(challenge: &str, nonce: &str) -> u32 {
+    let digest = Sha256::digest(format!("{}:{}", challenge, nonce).as_bytes());
+    let mut bits = 0;
+    for byte in digest {
+        bits += byte.leading_zeros();
+        if byte != 0 {
+            break;
+        }
+    }
+    bits
+}
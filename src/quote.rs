@@ -0,0 +1,69 @@
+use crate::database::postgres::models::Submission;
+use crate::template;
+
+/// Шаблон коммерческого предложения для клиента
+pub const QUOTE_TEMPLATE_PATH: &str = "templates/quote.html";
+
+/// Подстановка данных заявки в шаблон за один проход. Плейсхолдеры вида
+/// `{{name}}`, все значения экранируются
+pub fn render_quote_document(template: &str, submission: &Submission) -> String {
+    let quote_date = submission
+        .quoted_at
+        .unwrap_or(submission.created_at)
+        .format("%d.%m.%Y")
+        .to_string();
+
+    let values = [
+        ("quote_number", submission.submission_id.simple().to_string()[..8].to_uppercase()),
+        ("quote_date", quote_date),
+        ("submission_id", submission.submission_id.to_string()),
+        ("client_name", submission.name.clone()),
+        ("client_email", submission.email.clone()),
+        ("client_phone", submission.phone.clone().unwrap_or_else(|| "—".to_string())),
+        ("message", submission.message.clone()),
+        ("price", format_price(submission.price, &submission.currency)),
+        ("currency", submission.currency.clone()),
+    ];
+
+    template::fill(template, |key| {
+        values.iter().find(|(k, _)| *k == key).map(|(_, value)| escape_html(value))
+    })
+}
+
+/// Сумма в минимальных единицах в виде `1 500,00 ₽`
+fn format_price(minor_units: i64, currency: &str) -> String {
+    let major = (minor_units / 100).to_string();
+    let minor = minor_units % 100;
+
+    // Разряды разделяются неразрывным пробелом
+    let mut grouped = String::with_capacity(major.len() + major.len() / 3);
+    for (i, digit) in major.chars().enumerate() {
+        if i > 0 && (major.len() - i).is_multiple_of(3) {
+            grouped.push('\u{a0}');
+        }
+        grouped.push(digit);
+    }
+
+    let symbol = match currency {
+        "RUB" => "₽",
+        "USD" => "$",
+        "EUR" => "€",
+        other => other,
+    };
+    format!("{},{:02}\u{a0}{}", grouped, minor, symbol)
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Роль администратора панели (колонка `admin.role`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdminRole {
+    Owner,
+    Manager,
+    Expert,
+    ReadOnly,
+}
+
+/// Действия в админ-панели, доступ к которым зависит от роли
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    /// Просмотр заявок, статистики и комментариев
+    ViewSubmissions,
+    /// Ручное добавление заявок из панели
+    CreateSubmissions,
+    /// Изменение статуса заявки
+    ChangeStatus,
+    /// Комментирование заявок
+    Comment,
+    /// Назначение заявок экспертам
+    AssignSubmissions,
+    /// Оценка стоимости заявки
+    ManagePricing,
+    /// Управление учетными записями администраторов
+    ManageAdmins,
+    /// Просмотр журнала действий администраторов
+    ViewAuditLog,
+}
+
+impl AdminRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AdminRole::Owner => "owner",
+            AdminRole::Manager => "manager",
+            AdminRole::Expert => "expert",
+            AdminRole::ReadOnly => "read_only",
+        }
+    }
+
+    /// Проверка, разрешено ли роли действие
+    pub fn can(&self, permission: Permission) -> bool {
+        match self {
+            AdminRole::Owner => true,
+            AdminRole::Manager => !matches!(permission, Permission::ManageAdmins | Permission::ViewAuditLog),
+            AdminRole::Expert => matches!(
+                permission,
+                Permission::ViewSubmissions | Permission::Comment
+            ),
+            AdminRole::ReadOnly => matches!(permission, Permission::ViewSubmissions),
+        }
+    }
+
+    /// Эксперт видит только назначенные ему заявки
+    pub fn sees_only_assigned(&self) -> bool {
+        matches!(self, AdminRole::Expert)
+    }
+
+    /// Заявку можно назначить любому, кто работает с заявками
+    pub fn can_be_assigned(&self) -> bool {
+        !matches!(self, AdminRole::ReadOnly)
+    }
+}
+
+impl fmt::Display for AdminRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl TryFrom<String> for AdminRole {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "owner" => Ok(AdminRole::Owner),
+            "manager" => Ok(AdminRole::Manager),
+            "expert" => Ok(AdminRole::Expert),
+            "read_only" => Ok(AdminRole::ReadOnly),
+            other => Err(format!("Unknown admin role: {}", other)),
+        }
+    }
+}
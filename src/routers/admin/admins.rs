@@ -0,0 +1,284 @@
+use axum::{
+    http::StatusCode,
+    response::{Json, IntoResponse},
+    extract::{Json as ExtractJson, Path, State},
+};
+use bcrypt::{hash, DEFAULT_COST};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::info;
+use uuid::Uuid;
+use crate::audit::{self, AuditAction};
+use crate::state::AppState;
+use crate::error::AppError;
+use crate::roles::AdminRole;
+use crate::password::validate_password_strength;
+use crate::database::postgres::error::DatabaseError;
+use crate::database::postgres::models::{AccessChange, AdminAccount};
+use crate::routers::admin::auth::{ClientInfo, CurrentAdmin};
+use crate::routers::admin::sessions::revoke_all_admin_sessions;
+use crate::validation::{is_valid_email, FieldError};
+
+const MIN_USERNAME_LENGTH: usize = 3;
+const MAX_USERNAME_LENGTH: usize = 255;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateAdminRequest {
+    username: String,
+    password: String,
+    role: AdminRole,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateAdminActiveRequest {
+    is_active: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateAdminRoleRequest {
+    role: AdminRole,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResetAdminPasswordRequest {
+    password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateAdminMailRequest {
+    email: Option<String>,
+    on_duty: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateAdminTelegramRequest {
+    /// Числовой id пользователя Telegram, `null` отвязывает аккаунт
+    telegram_user_id: Option<i64>,
+}
+
+// GET /admin/api/v1/admins
+pub async fn list_admins(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<AdminAccount>>, AppError> {
+    let admins = state.db_postgres.list_admins().await?;
+    Ok(Json(admins))
+}
+
+// POST /admin/api/v1/admins
+pub async fn create_admin(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    client: ClientInfo,
+    ExtractJson(data): ExtractJson<CreateAdminRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let username = data.username.trim();
+    let username_length = username.chars().count();
+    if !(MIN_USERNAME_LENGTH..=MAX_USERNAME_LENGTH).contains(&username_length) {
+        return Err(AppError::bad_request("Username must contain 3-255 characters"));
+    }
+    validate_password_strength(&data.password, username)?;
+
+    let password_hash = hash(&data.password, DEFAULT_COST)?;
+    let created = state.db_postgres
+        .create_admin(username, &password_hash, data.role)
+        .await?
+        .ok_or_else(|| AppError::conflict("Username is already taken"))?;
+
+    audit::record(&state, &client, &admin.username, AuditAction::AdminCreate, json!({
+        "admin_id": created.id,
+        "username": created.username,
+        "role": created.role,
+    })).await;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+// PUT /admin/api/v1/admins/{id}/active
+pub async fn update_admin_active(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    client: ClientInfo,
+    Path(admin_id): Path<Uuid>,
+    ExtractJson(data): ExtractJson<UpdateAdminActiveRequest>,
+) -> Result<StatusCode, AppError> {
+    if !data.is_active {
+        ensure_not_self(&admin, admin_id)?;
+    }
+
+    ensure_applied(state.db_postgres.set_admin_active(admin_id, data.is_active).await?)?;
+    if !data.is_active {
+        revoke_all_admin_sessions(&state, &admin_id.to_string(), None).await?;
+    }
+
+    info!("Admin {} set is_active={} for {}", admin.id, data.is_active, admin_id);
+    audit::record(&state, &client, &admin.username, AuditAction::AdminActiveChange, json!({
+        "admin_id": admin_id,
+        "is_active": data.is_active,
+    })).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// PUT /admin/api/v1/admins/{id}/role
+pub async fn update_admin_role(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    client: ClientInfo,
+    Path(admin_id): Path<Uuid>,
+    ExtractJson(data): ExtractJson<UpdateAdminRoleRequest>,
+) -> Result<StatusCode, AppError> {
+    if data.role != AdminRole::Owner {
+        ensure_not_self(&admin, admin_id)?;
+    }
+
+    ensure_applied(state.db_postgres.set_admin_role(admin_id, data.role).await?)?;
+    // Роль зашита в токены, поэтому старые сессии завершаются
+    if admin.id != admin_id {
+        revoke_all_admin_sessions(&state, &admin_id.to_string(), None).await?;
+    }
+
+    info!("Admin {} set role={} for {}", admin.id, data.role, admin_id);
+    audit::record(&state, &client, &admin.username, AuditAction::AdminRoleChange, json!({
+        "admin_id": admin_id,
+        "role": data.role,
+    })).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// PUT /admin/api/v1/admins/{id}/password
+pub async fn reset_admin_password(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    client: ClientInfo,
+    Path(admin_id): Path<Uuid>,
+    ExtractJson(data): ExtractJson<ResetAdminPasswordRequest>,
+) -> Result<StatusCode, AppError> {
+    let target = state.db_postgres
+        .get_admin_account(admin_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Admin not found"))?;
+    validate_password_strength(&data.password, &target.username)?;
+
+    let password_hash = hash(&data.password, DEFAULT_COST)?;
+    if !state.db_postgres.update_admin_password(admin_id, &password_hash, true).await? {
+        return Err(AppError::not_found("Admin not found"));
+    }
+    revoke_all_admin_sessions(&state, &admin_id.to_string(), None).await?;
+
+    info!("Admin {} reset password for {}", admin.id, admin_id);
+    audit::record(&state, &client, &admin.username, AuditAction::AdminPasswordReset, json!({
+        "admin_id": admin_id,
+    })).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// PUT /admin/api/v1/admins/{id}/mail
+// Почта администратора и дежурство: дежурные получают письма о новых заявках
+pub async fn update_admin_mail(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    client: ClientInfo,
+    Path(admin_id): Path<Uuid>,
+    ExtractJson(data): ExtractJson<UpdateAdminMailRequest>,
+) -> Result<StatusCode, AppError> {
+    let email = data.email.as_deref().map(str::trim).filter(|e| !e.is_empty());
+
+    if email.is_some_and(|e| !is_valid_email(e)) {
+        return Err(AppError::Validation(vec![
+            FieldError::new("email", "invalid_format", "Некорректный email"),
+        ]));
+    }
+    if data.on_duty && email.is_none() {
+        return Err(AppError::Validation(vec![
+            FieldError::new("email", "required", "Дежурному нужен email для уведомлений"),
+        ]));
+    }
+
+    if !state.db_postgres.set_admin_mail(admin_id, email, data.on_duty).await? {
+        return Err(AppError::not_found("Admin not found"));
+    }
+
+    info!("Admin {} set mail settings for {}: on_duty={}", admin.id, admin_id, data.on_duty);
+    audit::record(&state, &client, &admin.username, AuditAction::AdminMailChange, json!({
+        "admin_id": admin_id,
+        "email": email,
+        "on_duty": data.on_duty,
+    })).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// PUT /admin/api/v1/admins/{id}/telegram
+// Привязка аккаунта Telegram: от его имени обрабатываются кнопки бота
+pub async fn update_admin_telegram(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    client: ClientInfo,
+    Path(admin_id): Path<Uuid>,
+    ExtractJson(data): ExtractJson<UpdateAdminTelegramRequest>,
+) -> Result<StatusCode, AppError> {
+    if data.telegram_user_id.is_some_and(|id| id <= 0) {
+        return Err(AppError::Validation(vec![
+            FieldError::new("telegram_user_id", "invalid_format", "Некорректный id пользователя Telegram"),
+        ]));
+    }
+
+    let updated = state.db_postgres
+        .set_admin_telegram(admin_id, data.telegram_user_id)
+        .await
+        .map_err(|e| match e {
+            DatabaseError::UniqueViolation { .. } => {
+                AppError::conflict("Telegram account is already linked to another admin")
+            }
+            e => AppError::from(e),
+        })?;
+    if !updated {
+        return Err(AppError::not_found("Admin not found"));
+    }
+
+    info!("Admin {} set Telegram account for {}", admin.id, admin_id);
+    audit::record(&state, &client, &admin.username, AuditAction::AdminTelegramChange, json!({
+        "admin_id": admin_id,
+        "telegram_user_id": data.telegram_user_id,
+    })).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// DELETE /admin/api/v1/admins/{id}
+pub async fn delete_admin(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    client: ClientInfo,
+    Path(admin_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    ensure_not_self(&admin, admin_id)?;
+
+    // Комментарии удаляются каскадно вместе с автором, поэтому такие
+    // учетные записи можно только деактивировать
+    if state.db_postgres.admin_has_comments(admin_id).await? {
+        return Err(AppError::conflict("Admin has comments, deactivate the account instead"));
+    }
+
+    ensure_applied(state.db_postgres.delete_admin(admin_id).await?)?;
+    revoke_all_admin_sessions(&state, &admin_id.to_string(), None).await?;
+
+    info!("Admin {} deleted admin {}", admin.id, admin_id);
+    audit::record(&state, &client, &admin.username, AuditAction::AdminDelete, json!({
+        "admin_id": admin_id,
+    })).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn ensure_not_self(admin: &CurrentAdmin, admin_id: Uuid) -> Result<(), AppError> {
+    if admin.id == admin_id {
+        return Err(AppError::bad_request("You cannot change your own access"));
+    }
+    Ok(())
+}
+
+/// В системе всегда должен оставаться хотя бы один активный владелец:
+/// проверка выполняется в той же транзакции, что и изменение
+fn ensure_applied(change: AccessChange) -> Result<(), AppError> {
+    match change {
+        AccessChange::Applied => Ok(()),
+        AccessChange::NotFound => Err(AppError::not_found("Admin not found")),
+        AccessChange::LastOwner => Err(AppError::conflict("At least one active owner is required")),
+    }
+}
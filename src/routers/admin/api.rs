@@ -1,17 +1,32 @@
 use axum::{
     http::StatusCode,
     response::{Json, IntoResponse},
-    extract::{Json as ExtractJson, State},
+    extract::{Json as ExtractJson, Path, Query, State},
 };
-use jsonwebtoken::{decode, DecodingKey, Validation, Algorithm};
-use axum_extra::extract::cookie::CookieJar;
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use tracing::info;
 use uuid::Uuid;
+use crate::audit::{self, AuditAction};
 use crate::state::AppState;
-use crate::database::postgres::models::{PaginationResult, DatabaseStats};
+use crate::database::postgres::models::{
+    CountMode, CursorPage, DatabaseStats, KeysetSort, PaginationResult, StatusHistoryEntry, Submission,
+    SubmissionCursor, SubmissionFilter, QUOTE_CURRENCIES,
+};
 use crate::error::AppError;
-use crate::database::postgres::models::CreateSubmissionRequest;
+use crate::database::postgres::models::{CreateSubmissionRequest, SpamAssessment};
+use crate::routers::admin::auth::{ClientInfo, CurrentAdmin};
+use crate::routers::admin::assignment::auto_assign_new_submission;
+use crate::routers::admin::filters::{day_range, non_empty};
+use crate::outbox::submission_notifications;
+use crate::events::{self, PanelEvent};
+use crate::notifications;
+use crate::validation::{normalize_ru_phone, validate_contact, FieldError};
+use crate::workflow::SubmissionStatus;
+
+const DEFAULT_CURSOR_LIMIT: i64 = 50;
+const MAX_STATUS_REASON_CHARS: usize = 1000;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AdminContactSubmission {
@@ -21,18 +36,127 @@ pub struct AdminContactSubmission {
     message: String,
 }
 
-#[derive(Debug, Serialize)]
-pub struct AdminErrorResponse {
-    message: String,
-    code: String,
+#[derive(Debug, Deserialize)]
+pub struct PaginationQuery {
+    page: i64,
+    per_page: i64,
+    sort_by: Option<String>,
+    order: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
-pub struct PaginationQuery {
-    page: usize,
-    per_page: usize,
+pub struct CursorQuery {
+    /// Курсор из `next_cursor` предыдущей страницы. Сортировка берется из него
+    cursor: Option<String>,
+    limit: Option<i64>,
     sort_by: Option<String>,
-    order: Option<String>
+    order: Option<String>,
+    #[serde(default)]
+    count: CountMode,
+}
+
+/// Фильтры списка заявок, общие для постраничного режима и режима курсора
+#[derive(Debug, Deserialize)]
+pub struct SubmissionFilterQuery {
+    #[serde(default)]
+    include_spam: bool,
+    /// Полнотекстовый поиск по имени и сообщению
+    q: Option<String>,
+    email: Option<String>,
+    phone: Option<String>,
+    /// Статусы через запятую: `new,in_progress`
+    status: Option<String>,
+    /// Даты в UTC, обе границы включаются
+    date_from: Option<NaiveDate>,
+    date_to: Option<NaiveDate>,
+    /// UUID администратора или `me` для своих заявок
+    assigned_to: Option<String>,
+    price_min: Option<i64>,
+    price_max: Option<i64>,
+    /// Обязательна вместе с price_min или price_max: цены в разных валютах
+    /// не сравниваются
+    currency: Option<String>,
+}
+
+impl SubmissionFilterQuery {
+    /// Проверка и нормализация фильтров. Эксперт всегда видит только свои заявки
+    fn to_filter(&self, admin: &CurrentAdmin) -> Result<SubmissionFilter, AppError> {
+        let mut errors = Vec::new();
+
+        let phone = match non_empty(&self.phone) {
+            Some(raw) => {
+                let phone = normalize_ru_phone(raw);
+                if phone.is_none() {
+                    errors.push(FieldError::new("phone", "invalid_format", "Некорректный номер телефона"));
+                }
+                phone
+            }
+            None => None,
+        };
+
+        let statuses = non_empty(&self.status).map(|raw| {
+            raw.split(',')
+                .map(|status| status.trim().to_lowercase())
+                .filter(|status| !status.is_empty())
+                .collect::<Vec<_>>()
+        });
+        if let Some(unknown) = statuses.iter().flatten().find(|s| s.parse::<SubmissionStatus>().is_err()) {
+            errors.push(FieldError::new("status", "invalid_value", format!("Неизвестный статус: {}", unknown)));
+        }
+
+        let assigned_to = match non_empty(&self.assigned_to) {
+            Some("me") => Some(admin.id),
+            Some(raw) => {
+                let id = Uuid::parse_str(raw).ok();
+                if id.is_none() {
+                    errors.push(FieldError::new("assigned_to", "invalid_format", "Ожидается UUID или me"));
+                }
+                id
+            }
+            None => None,
+        };
+
+        if let (Some(from), Some(to)) = (self.date_from, self.date_to) && from > to {
+            errors.push(FieldError::new("date_from", "invalid_range", "Начальная дата позже конечной"));
+        }
+
+        if self.price_min.is_some_and(|p| p < 0) || self.price_max.is_some_and(|p| p < 0) {
+            errors.push(FieldError::new("price_min", "invalid_range", "Цена не может быть отрицательной"));
+        } else if let (Some(min), Some(max)) = (self.price_min, self.price_max) && min > max {
+            errors.push(FieldError::new("price_min", "invalid_range", "Минимальная цена больше максимальной"));
+        }
+
+        let currency = non_empty(&self.currency).map(str::to_uppercase);
+        match currency.as_deref() {
+            Some(currency) if !QUOTE_CURRENCIES.contains(&currency) => {
+                errors.push(FieldError::new("currency", "invalid_value", format!("Неподдерживаемая валюта: {}", currency)));
+            }
+            None if self.price_min.is_some() || self.price_max.is_some() => {
+                errors.push(FieldError::new("currency", "required", "Для фильтра по цене нужна валюта"));
+            }
+            _ => {}
+        }
+
+        if !errors.is_empty() {
+            return Err(AppError::Validation(errors));
+        }
+
+        let (created_from, created_before) = day_range(self.date_from, self.date_to);
+
+        Ok(SubmissionFilter {
+            query: non_empty(&self.q).map(str::to_string),
+            email: non_empty(&self.email).map(str::to_lowercase),
+            phone,
+            statuses,
+            created_from,
+            created_before,
+            assigned_to: if admin.role.sees_only_assigned() { Some(admin.id) } else { assigned_to },
+            price_min: self.price_min,
+            price_max: self.price_max,
+            currency,
+            include_spam: self.include_spam,
+        })
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -42,15 +166,21 @@ pub struct AdminSuccessResponse {
 }
 
 // /api/v1/admin/dashboard-page?page=1&per_page=10&sort_by=date&order=desc
+// Фильтры: q, email, phone, status=new,viewed, date_from, date_to, assigned_to, price_min, price_max, currency
 pub async fn post_admin_dashboard(
     State(state): State<AppState>,
-    pagination: axum::extract::Query<PaginationQuery>,
+    admin: CurrentAdmin,
+    pagination: Query<PaginationQuery>,
+    filter: Query<SubmissionFilterQuery>,
 ) -> Result<Json<PaginationResult>, AppError> {
+    let filter = filter.to_filter(&admin)?;
+
     let result: PaginationResult = state.db_postgres.get_submissions_paginated(
-        pagination.page.try_into().unwrap(),
-        pagination.per_page.try_into().unwrap(),
+        pagination.page,
+        pagination.per_page,
         pagination.sort_by.as_deref(),
-        pagination.order.as_deref()
+        pagination.order.as_deref(),
+        &filter,
     )
     .await
     .map_err(AppError::DatabaseError)?;
@@ -58,6 +188,46 @@ pub async fn post_admin_dashboard(
     Ok(Json(result))
 }
 
+// GET /admin/api/v1/submissions?limit=50&cursor=...&count=estimate
+// Режим курсора: сортировка sort_by=created_at|status, фильтры как у dashboard-page
+pub async fn list_submissions(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    query: Query<CursorQuery>,
+    filter: Query<SubmissionFilterQuery>,
+) -> Result<Json<CursorPage>, AppError> {
+    let filter = filter.to_filter(&admin)?;
+
+    let cursor = match non_empty(&query.cursor) {
+        Some(raw) => Some(SubmissionCursor::decode(raw).ok_or_else(|| AppError::bad_request("Invalid cursor"))?),
+        None => None,
+    };
+    let (sort, descending) = match &cursor {
+        Some(cursor) => (cursor.sort, cursor.descending),
+        None => {
+            let sort = match query.sort_by.as_deref().map(str::trim) {
+                None | Some("created_at" | "created" | "date") => KeysetSort::CreatedAt,
+                Some("status") => KeysetSort::Status,
+                Some(_) => return Err(AppError::bad_request("Cursor pagination supports sort_by=created_at or status")),
+            };
+            (sort, !query.order.as_deref().is_some_and(|o| o.eq_ignore_ascii_case("asc")))
+        }
+    };
+
+    let page = state.db_postgres
+        .get_submissions_keyset(
+            query.limit.unwrap_or(DEFAULT_CURSOR_LIMIT),
+            sort,
+            descending,
+            cursor.as_ref(),
+            query.count,
+            &filter,
+        )
+        .await?;
+
+    Ok(Json(page))
+}
+
 pub async fn get_admin_statistics(
     State(state): State<AppState>,
 ) -> Result<Json<DatabaseStats>, AppError> {
@@ -70,181 +240,152 @@ pub async fn get_admin_statistics(
 #[derive(Debug, Deserialize, Clone)]
 pub struct UpdateViewedParams {
     submission_id: uuid::Uuid,
-    status: String,
+    status: SubmissionStatus,
+    /// Причина смены статуса, сохраняется в истории
+    #[serde(default)]
+    reason: Option<String>,
 }
 // api/v1/admin/update-submission-status
-// Пример запроса PUT /api/v1/admin/update-submission-status?submission_id=123&viewed=true
+// Пример запроса PUT /api/v1/admin/update-submission-status {"submission_id": "...", "status": "in_progress"}
 pub async fn update_admin_status(
     State(state): State<AppState>,
+    admin: CurrentAdmin,
+    client: ClientInfo,
     Json(params): Json<UpdateViewedParams>,
 ) -> Result<StatusCode, AppError> {
-    state.db_postgres
-        .update_submissions_status(params.submission_id, params.status)
+    let reason = params.reason.as_deref().map(str::trim).filter(|r| !r.is_empty());
+    if reason.is_some_and(|r| r.chars().count() > MAX_STATUS_REASON_CHARS) {
+        return Err(AppError::Validation(vec![
+            FieldError::new("reason", "too_long", "Причина не должна превышать 1000 символов"),
+        ]));
+    }
+
+    let submission = state.db_postgres
+        .get_submission(params.submission_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Submission not found"))?;
+
+    let from = submission.status;
+    let to = params.status;
+    if from == to {
+        return Ok(StatusCode::NO_CONTENT);
+    }
+    if !from.can_transition_to(to, admin.role) {
+        return Err(AppError::InvalidStatusTransition { from, to });
+    }
+
+    let changed = state.db_postgres
+        .change_submission_status(params.submission_id, from, to, admin.id, reason)
         .await?;
+    if !changed {
+        return Err(AppError::conflict("Submission status was changed concurrently, reload and try again"));
+    }
 
+    info!(actor = %admin.username, submission_id = %params.submission_id, from = %from, to = %to, "Submission status changed");
+    audit::record(&state, &client, &admin.username, AuditAction::StatusChange, json!({
+        "submission_id": params.submission_id,
+        "from": from,
+        "to": to,
+        "reason": reason,
+    })).await;
+    notifications::status_change(&state, params.submission_id, submission.admin_id, from, to, (admin.id, &admin.username)).await;
+    events::publish(&state, PanelEvent::StatusChanged { submission_id: params.submission_id, from, to }).await;
     Ok(StatusCode::NO_CONTENT)
 }
 
+// GET /admin/api/v1/submissions/{id}
+// Первое открытие новой заявки переводит ее в статус viewed
+pub async fn get_submission_details(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    Path(submission_id): Path<Uuid>,
+) -> Result<Json<Submission>, AppError> {
+    ensure_submission_access(&state, &admin, submission_id).await?;
+
+    let mut submission = state.db_postgres
+        .get_submission(submission_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Submission not found"))?;
+
+    if submission.status == SubmissionStatus::New {
+        let viewed = state.db_postgres
+            .change_submission_status(
+                submission_id,
+                SubmissionStatus::New,
+                SubmissionStatus::Viewed,
+                admin.id,
+                Some("Открыта в панели"),
+            )
+            .await?;
+        if viewed {
+            submission.status = SubmissionStatus::Viewed;
+            events::publish(&state, PanelEvent::StatusChanged {
+                submission_id,
+                from: SubmissionStatus::New,
+                to: SubmissionStatus::Viewed,
+            }).await;
+        }
+    }
+
+    Ok(Json(submission))
+}
+
+// GET /admin/api/v1/submissions/{id}/status-history
+pub async fn get_submission_status_history(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    Path(submission_id): Path<Uuid>,
+) -> Result<Json<Vec<StatusHistoryEntry>>, AppError> {
+    ensure_submission_access(&state, &admin, submission_id).await?;
+
+    let history = state.db_postgres.get_status_history(submission_id).await?;
+    Ok(Json(history))
+}
+
 // /api/v1/admin/add-submissions
 pub async fn create_contact_submission(
     State(state): State<AppState>,
     ExtractJson(data): ExtractJson<AdminContactSubmission>,
-) -> impl IntoResponse {
-    if data.name.len() < 2 || data.name.len() > 255 {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(AdminErrorResponse {
-                message: "Имя должно содержать 2-255 символов".to_string(),
-                code: "VALIDATION_ERROR".to_string(),
-            }),
-        ).into_response();
-    }
-
-    // Валидация email
-    if !data.email.contains('@') || data.email.len() > 254 {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(AdminErrorResponse {
-                message: "Некорректный email адрес".to_string(),
-                code: "VALIDATION_ERROR".to_string(),
-            }),
-        ).into_response();
-    }
+) -> Result<impl IntoResponse, AppError> {
+    let contact = validate_contact(&data.name, &data.email, data.phone.as_deref(), &data.message)?;
 
-    // Валидация сообщения
-    if data.message.len() < 10 || data.message.len() > 1000 {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(AdminErrorResponse {
-                message: "Сообщение должно содержать 10-1000 символов".to_string(),
-                code: "VALIDATION_ERROR".to_string(),
-            }),
-        ).into_response();
-    }
     let submission_id = uuid::Uuid::new_v4();
     let submission = CreateSubmissionRequest {
-        submission_id: submission_id.clone(),
-        name: data.name,
-        email: data.email,
-        phone: data.phone,
-        message: data.message,
+        submission_id,
+        name: contact.name,
+        email: contact.email,
+        phone: contact.phone,
+        message: contact.message,
+        spam: SpamAssessment::default(),
     };
-    state.db_postgres.save_submission(submission).await.unwrap();
+    // Заявку вносит администратор, подтверждение клиенту не отправляется
+    let notifications = submission_notifications(&state, &submission, false);
+    state.db_postgres.save_submission(submission, &notifications, &[]).await?;
     info!("Admin submission saved: {}", submission_id);
-    (
+    auto_assign_new_submission(&state, submission_id).await;
+    events::publish_submission_created(&state, submission_id).await;
+    Ok((
         StatusCode::OK,
         Json(AdminSuccessResponse {
             message: "Сообщение успешно отправлено".to_string(),
             submission_id: submission_id.to_string(),
         }),
-    ).into_response()
-}
-
-#[derive(Debug, Deserialize)]
-pub struct CreateAdminComment {
-    pub submissions_id: Uuid,
-    pub text: String
+    ))
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct AdminTokenClaims {
-    sub: String,    // Здесь хранится admin_id
-    iat: i64,
-    exp: i64,
-    jti: String,
-    role: String,
-    session_id: String,
-}
-
-// /api/v1/admin/create-submissions-comment
-pub async fn crate_submission_comment(
-    State(state): State<AppState>,
-    jar: CookieJar,
-    ExtractJson(data): ExtractJson<CreateAdminComment>,
-) -> impl IntoResponse {
-    let cookie_value = match jar.get("__Secure-admin-session") {
-        Some(cookie) => cookie.value().to_owned(),
-        None => {
-            tracing::warn!("Missing session cookie");
-            return (StatusCode::UNAUTHORIZED, "Missing session cookie").into_response();
-        }
-    };
-
-    let mut tokens = cookie_value.split(':');
-    let access_token = match tokens.next() {
-        Some(token) => token.trim(),
-        None => {
-            tracing::error!("Invalid cookie format: no access token");
-            return (StatusCode::UNAUTHORIZED, "Invalid session format").into_response();
-        }
-    };
-
-    let token_data = match decode::<AdminTokenClaims>(
-        access_token,
-        &DecodingKey::from_secret(state.jwt_secret.as_ref()),
-        &Validation::new(Algorithm::HS256),
-    ) {
-        Ok(data) => data,
-        Err(e) => {
-            
-            let msg = match e.kind() {
-                jsonwebtoken::errors::ErrorKind::InvalidToken => "Invalid token format",
-                jsonwebtoken::errors::ErrorKind::InvalidSignature => "Invalid signature",
-                jsonwebtoken::errors::ErrorKind::ExpiredSignature => "Token expired",
-                jsonwebtoken::errors::ErrorKind::InvalidIssuer => "Invalid issuer",
-                jsonwebtoken::errors::ErrorKind::InvalidAudience => "Invalid audience",
-                _ => "Invalid token",
-            };
-            
-            return (StatusCode::UNAUTHORIZED, msg).into_response();
-        }
-    };
+/// Эксперт имеет доступ только к назначенным ему заявкам
+pub async fn ensure_submission_access(
+    state: &AppState,
+    admin: &CurrentAdmin,
+    submission_id: Uuid,
+) -> Result<(), AppError> {
+    if !admin.role.sees_only_assigned() {
+        return Ok(());
+    }
 
-    // 5. Парсинг UUID администратора
-    let admin_id = match Uuid::parse_str(&token_data.claims.sub) {
-        Ok(id) => id,
-        Err(_) => {
-            return (StatusCode::UNAUTHORIZED, "Invalid admin ID").into_response();
-        }
-    };
-    // 7. Создание комментария в базе данных
-    match state
-        .db_postgres
-        .create_admin_comments(admin_id, data.submissions_id, data.text)
-        .await
-    {
-        Ok(_) => StatusCode::CREATED.into_response(),
-        Err(e) => {
-            tracing::error!(
-                "DB error for admin {}: {:?}",
-                admin_id,
-                e
-            );
-            
-            let msg = if e.to_string().contains("foreign key constraint") {
-                "Invalid submission ID"
-            } else {
-                "Internal server error"
-            };
-            
-            (StatusCode::INTERNAL_SERVER_ERROR, msg).into_response()
-        }
+    if !state.db_postgres.is_submission_assigned_to(submission_id, admin.id).await? {
+        return Err(AppError::forbidden("Submission is not assigned to you"));
     }
-}
 
-#[derive(Deserialize)]
-pub struct GetAdminComments { 
-    pub submissions_id: Uuid
+    Ok(())
 }
-
-// /api/v1/admin/get-submissions-comment
-pub async fn get_submission_comments(
-    State(state): State<AppState>,
-    ExtractJson(params): ExtractJson<GetAdminComments>,
-) -> Result<impl IntoResponse, AppError> {
-    let submission_comments = state.db_postgres
-        .get_admin_comments(params.submissions_id)
-        .await?;
-    println!("{:?}", submission_comments);
-    Ok((StatusCode::OK, Json(submission_comments)))
-}
\ No newline at end of file
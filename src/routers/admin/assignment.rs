@@ -0,0 +1,130 @@
+use axum::{
+    extract::{Json as ExtractJson, Path, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::audit::{self, AuditAction};
+use crate::error::AppError;
+use crate::events::{self, PanelEvent};
+use crate::notifications;
+use crate::state::AppState;
+use crate::routers::admin::auth::{ClientInfo, CurrentAdmin};
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateAssigneeRequest {
+    admin_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateDueDateRequest {
+    /// `null` снимает срок
+    due_at: Option<DateTime<Utc>>,
+}
+
+// PUT /admin/api/v1/submissions/{id}/assignee
+// Назначение и переназначение заявки
+pub async fn update_submission_assignee(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    client: ClientInfo,
+    Path(submission_id): Path<Uuid>,
+    ExtractJson(payload): ExtractJson<UpdateAssigneeRequest>,
+) -> Result<StatusCode, AppError> {
+    let assignee = state
+        .db_postgres
+        .get_admin_account(payload.admin_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Admin not found"))?;
+
+    if !assignee.is_active {
+        return Err(AppError::bad_request("Cannot assign a submission to a deactivated admin"));
+    }
+    if !assignee.role.can_be_assigned() {
+        return Err(AppError::bad_request("Cannot assign a submission to a read-only admin"));
+    }
+
+    if !state.db_postgres.assign_submission(submission_id, Some(assignee.id)).await? {
+        return Err(AppError::not_found("Submission not found"));
+    }
+
+    info!(
+        actor = %admin.username,
+        submission_id = %submission_id,
+        assignee = %assignee.username,
+        "Submission assigned"
+    );
+    audit::record(&state, &client, &admin.username, AuditAction::AssignmentChange, json!({
+        "submission_id": submission_id,
+        "assignee_id": assignee.id,
+    })).await;
+    notifications::assignment(&state, submission_id, assignee.id, Some((admin.id, &admin.username))).await;
+    events::publish(&state, PanelEvent::AssignmentChanged {
+        submission_id,
+        assignee_id: Some(assignee.id),
+        assignee_name: Some(assignee.username),
+    }).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// DELETE /admin/api/v1/submissions/{id}/assignee
+pub async fn remove_submission_assignee(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    client: ClientInfo,
+    Path(submission_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    if !state.db_postgres.assign_submission(submission_id, None).await? {
+        return Err(AppError::not_found("Submission not found"));
+    }
+
+    info!(actor = %admin.username, submission_id = %submission_id, "Submission unassigned");
+    audit::record(&state, &client, &admin.username, AuditAction::AssignmentChange, json!({
+        "submission_id": submission_id,
+        "assignee_id": null,
+    })).await;
+    events::publish(&state, PanelEvent::AssignmentChanged {
+        submission_id,
+        assignee_id: None,
+        assignee_name: None,
+    }).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// PUT /admin/api/v1/submissions/{id}/due-date
+// Срок выполнения. Исполнитель получает напоминание за сутки до него
+pub async fn update_submission_due_date(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    client: ClientInfo,
+    Path(submission_id): Path<Uuid>,
+    ExtractJson(payload): ExtractJson<UpdateDueDateRequest>,
+) -> Result<StatusCode, AppError> {
+    if !state.db_postgres.set_submission_due_at(submission_id, payload.due_at).await? {
+        return Err(AppError::not_found("Submission not found"));
+    }
+
+    info!(actor = %admin.username, submission_id = %submission_id, due_at = ?payload.due_at, "Submission due date changed");
+    audit::record(&state, &client, &admin.username, AuditAction::DueDateChange, json!({
+        "submission_id": submission_id,
+        "due_at": payload.due_at,
+    })).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Автоназначение новой заявки по стратегии `AUTO_ASSIGN`. Ошибка не мешает
+/// сохранению заявки: она останется без исполнителя до ручного назначения
+pub async fn auto_assign_new_submission(state: &AppState, submission_id: Uuid) {
+    match state.db_postgres.auto_assign_submission(submission_id, state.auto_assign).await {
+        Ok(Some(expert_id)) => {
+            info!(submission_id = %submission_id, expert_id = %expert_id, "Submission auto-assigned");
+            notifications::assignment(state, submission_id, expert_id, None).await;
+        }
+        Ok(None) => {}
+        Err(e) => warn!(submission_id = %submission_id, error = %e, "Submission auto-assignment failed"),
+    }
+}
@@ -0,0 +1,124 @@
+use axum::{
+    extract::{Multipart, Path, State},
+    http::{
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+        StatusCode,
+    },
+    response::{IntoResponse, Json, Response},
+};
+use serde_json::json;
+use std::fmt::Write;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::audit::{self, AuditAction};
+use crate::database::postgres::models::Attachment;
+use crate::error::AppError;
+use crate::state::AppState;
+use crate::storage::StorageError;
+use crate::routers::admin::api::ensure_submission_access;
+use crate::routers::admin::auth::{ClientInfo, CurrentAdmin};
+use crate::uploads::{self, MultipartForm};
+
+// GET /admin/api/v1/submissions/{id}/attachments
+pub async fn list_submission_attachments(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    Path(submission_id): Path<Uuid>,
+) -> Result<Json<Vec<Attachment>>, AppError> {
+    ensure_submission_access(&state, &admin, submission_id).await?;
+
+    let attachments = state.db_postgres.get_submission_attachments(submission_id).await?;
+    Ok(Json(attachments))
+}
+
+// POST /admin/api/v1/submissions/{id}/attachments
+// multipart/form-data, файлы в поле `files`
+pub async fn upload_submission_attachments(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    client: ClientInfo,
+    Path(submission_id): Path<Uuid>,
+    multipart: Multipart,
+) -> Result<(StatusCode, Json<Vec<Attachment>>), AppError> {
+    ensure_submission_access(&state, &admin, submission_id).await?;
+
+    let form = MultipartForm::read(multipart, state.upload_limits).await?;
+    if form.files.is_empty() {
+        return Err(AppError::bad_request("No files uploaded"));
+    }
+
+    let attachments = uploads::store(&state, &form.files).await?;
+    if !state.db_postgres.add_submission_attachments(submission_id, admin.id, &attachments).await? {
+        return Err(AppError::not_found("Submission not found"));
+    }
+
+    info!(
+        submission_id = %submission_id,
+        count = attachments.len(),
+        admin = %admin.username,
+        "Attachments uploaded"
+    );
+    audit::record(&state, &client, &admin.username, AuditAction::AttachmentUpload, json!({
+        "submission_id": submission_id,
+        "files": attachments.iter().map(|a| json!({
+            "file_name": a.file_name,
+            "sha256": a.sha256,
+            "size_bytes": a.size_bytes,
+        })).collect::<Vec<_>>(),
+    })).await;
+
+    let attachments = state.db_postgres.get_submission_attachments(submission_id).await?;
+    Ok((StatusCode::CREATED, Json(attachments)))
+}
+
+// GET /admin/api/v1/attachments/{id}
+// Скачивание вложения. Доступ проверяется по заявке, как и для ее просмотра
+pub async fn download_attachment(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    Path(attachment_id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let attachment = state
+        .db_postgres
+        .get_attachment(attachment_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Attachment not found"))?;
+    ensure_submission_access(&state, &admin, attachment.submission_id).await?;
+
+    let data = state.storage.get(&attachment.storage_key).await.map_err(|e| match e {
+        StorageError::NotFound(_) => {
+            tracing::error!(attachment_id = %attachment_id, key = %attachment.storage_key, "Attachment content is missing");
+            AppError::not_found("Attachment content not found")
+        }
+        e => AppError::from(e),
+    })?;
+
+    Ok((
+        [
+            (CONTENT_TYPE, attachment.content_type.clone()),
+            (CONTENT_DISPOSITION, content_disposition(&attachment.file_name)),
+        ],
+        data,
+    )
+        .into_response())
+}
+
+/// `Content-Disposition` с ASCII-именем для старых клиентов и полным в `filename*` (RFC 6266)
+fn content_disposition(file_name: &str) -> String {
+    let fallback: String = file_name
+        .chars()
+        .map(|c| if c.is_ascii_graphic() && c != '"' && c != '\\' || c == ' ' { c } else { '_' })
+        .collect();
+
+    let mut encoded = String::new();
+    for byte in file_name.bytes() {
+        if byte.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            let _ = write!(encoded, "%{:02X}", byte);
+        }
+    }
+
+    format!("attachment; filename=\"{}\"; filename*=UTF-8''{}", fallback, encoded)
+}
@@ -0,0 +1,83 @@
+use axum::{
+    extract::{Query, State},
+    response::Json,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::net::IpAddr;
+
+use crate::audit::AuditAction;
+use crate::database::postgres::models::{AuditLogEntry, AuditLogFilter, PaginationResult};
+use crate::error::AppError;
+use crate::routers::admin::filters::{day_range, non_empty};
+use crate::state::AppState;
+use crate::validation::FieldError;
+
+const DEFAULT_AUDIT_PAGE_SIZE: i64 = 50;
+
+#[derive(Debug, Deserialize)]
+pub struct AuditLogQuery {
+    page: Option<i64>,
+    per_page: Option<i64>,
+    username: Option<String>,
+    action: Option<String>,
+    ip: Option<String>,
+    /// Даты в UTC, обе границы включаются
+    date_from: Option<NaiveDate>,
+    date_to: Option<NaiveDate>,
+}
+
+impl AuditLogQuery {
+    fn to_filter(&self) -> Result<AuditLogFilter, AppError> {
+        let mut errors = Vec::new();
+
+        let action = non_empty(&self.action).map(str::to_string);
+        if let Some(action) = &action
+            && action.parse::<AuditAction>().is_err()
+        {
+            errors.push(FieldError::new("action", "invalid_value", format!("Неизвестное действие: {}", action)));
+        }
+
+        let ip = non_empty(&self.ip).map(str::to_string);
+        if ip.as_deref().is_some_and(|ip| ip.parse::<IpAddr>().is_err()) {
+            errors.push(FieldError::new("ip", "invalid_format", "Некорректный IP-адрес"));
+        }
+
+        if let (Some(from), Some(to)) = (self.date_from, self.date_to) && from > to {
+            errors.push(FieldError::new("date_from", "invalid_range", "Начальная дата позже конечной"));
+        }
+
+        if !errors.is_empty() {
+            return Err(AppError::Validation(errors));
+        }
+
+        let (created_from, created_before) = day_range(self.date_from, self.date_to);
+
+        Ok(AuditLogFilter {
+            username: non_empty(&self.username).map(str::to_string),
+            action,
+            ip,
+            created_from,
+            created_before,
+        })
+    }
+}
+
+// GET /admin/api/v1/audit-log
+// Фильтры: username, action, ip, date_from, date_to
+pub async fn get_audit_log(
+    State(state): State<AppState>,
+    Query(query): Query<AuditLogQuery>,
+) -> Result<Json<PaginationResult<AuditLogEntry>>, AppError> {
+    let filter = query.to_filter()?;
+
+    let result = state.db_postgres
+        .get_audit_log(
+            query.page.unwrap_or(1),
+            query.per_page.unwrap_or(DEFAULT_AUDIT_PAGE_SIZE),
+            &filter,
+        )
+        .await?;
+
+    Ok(Json(result))
+}
@@ -1,21 +1,29 @@
 use axum::{
-    extract::{State, Request},
-    http::{StatusCode, HeaderMap},
+    extract::{FromRequestParts, State, Request},
+    http::{header::SET_COOKIE, request::Parts, StatusCode, HeaderMap},
     middleware::Next,
     response::{IntoResponse, Response, Redirect},
     Json,
 };
 use axum_extra::extract::cookie::{CookieJar, Cookie, SameSite};
-use bcrypt::{verify};
+use bcrypt::{hash, verify, DEFAULT_COST};
 use chrono::Utc;
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
-use redis::AsyncCommands;
+use redis::{AsyncCommands, ExistenceCheck, Script, SetExpiry, SetOptions};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::convert::Infallible;
 use uuid::Uuid;
 
 use crate::{error::AppError};
+use crate::audit::{self, AuditAction};
+use crate::database::postgres::postgres_interface::PostgresDatabase;
+use crate::database::redis::redis_interface::RedisDatabase;
 use crate::state::AppState;
-use crate::csrf::CsrfService;
+use crate::roles::{AdminRole, Permission};
+use crate::password::validate_password_strength;
+use crate::middleware::ClientIp;
+use crate::routers::admin::two_factor::start_two_factor;
 
 // Структуры для запросов и ответов
 #[derive(Debug, Deserialize)]
@@ -24,6 +32,11 @@ pub struct AdminLoginRequest {
     password: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ChangePasswordRequest {
+    new_password: String,
+}
+
 #[derive(Debug, Serialize)]
 pub struct AdminLoginResponse {
     redirect_url: String,
@@ -36,83 +49,275 @@ pub struct AdminClaims {
     pub iat: i64,         // issued at
     pub exp: i64,         // expires at
     pub jti: String,      // JWT ID (для отзыва токенов)
-    pub role: String, 
+    pub role: AdminRole,
     pub session_id: String, // ID сессии
+    pub token_type: TokenType,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenType {
+    Access,
+    Refresh,
+}
+
+/// Аутентифицированный администратор текущего запроса.
+/// Кладется в extensions `admin_auth_middleware`, в обработчиках берется экстрактором:
+/// `async fn handler(admin: CurrentAdmin, ...)`
+#[derive(Debug, Clone)]
+pub struct CurrentAdmin {
+    pub id: Uuid,
+    pub username: String,
+    pub role: AdminRole,
+    pub session_id: String,
+}
+
+impl CurrentAdmin {
+    fn from_claims(claims: &AdminClaims, username: String) -> Result<Self, AppError> {
+        let id = Uuid::parse_str(&claims.sub).map_err(|_| AppError::unauthorized("Invalid admin ID"))?;
+        Ok(Self {
+            id,
+            username,
+            role: claims.role,
+            session_id: claims.session_id.clone(),
+        })
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for CurrentAdmin {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<CurrentAdmin>()
+            .cloned()
+            .ok_or_else(|| AppError::unauthorized("Missing admin session"))
+    }
 }
 
 #[derive(Debug, Serialize)]
+pub struct AdminRefreshResponse {
+    expires_in: i64,
+}
+
+/// Сведения о клиенте, сохраняемые в сессии
+pub struct ClientInfo {
+    pub ip: String,
+    pub user_agent: Option<String>,
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self {
+            ip: parts
+                .extensions
+                .get::<ClientIp>()
+                .map_or_else(|| "unknown".to_string(), |ip| ip.0.to_string()),
+            user_agent: parts
+                .headers
+                .get("user-agent")
+                .and_then(|v| v.to_str().ok())
+                .map(|s| s.to_string()),
+        })
+    }
+}
+
+/// Пара токенов, выпущенная для сессии
+struct IssuedTokens {
+    access_token: String,
+    refresh_token: String,
+    access_claims: AdminClaims,
+    refresh_jti: String,
+}
+
+/// Результат атомарной замены refresh-токена в Redis
+enum RotationOutcome {
+    Rotated,
+    /// Токен уже заменен параллельным запросом в пределах grace-периода
+    AlreadyRotated,
+    /// Предъявлен ранее использованный токен
+    Reused,
+    SessionMissing,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct AdminUser {
     pub id: Uuid,
     pub username: String,
-    pub role: String,
+    pub role: AdminRole,
+    pub must_change_password: bool,
+    pub totp_enabled: bool,
 }
 
 // Константы безопасности
 const ACCESS_TOKEN_EXPIRY: i64 = 3600; // 1 hour
 const REFRESH_TOKEN_EXPIRY: i64 = 86400 * 7; // 7 days
+const ACCESS_TOKEN_REFRESH_THRESHOLD: i64 = 300; // 5 minutes before expiry
+const REFRESH_REUSE_GRACE: i64 = 30; // seconds
 const MAX_LOGIN_ATTEMPTS: i32 = 5;
 const LOGIN_ATTEMPT_WINDOW: i64 = 900; // 15 minutes
 const SECURE_COOKIE_NAME: &str = "__Secure-admin-session";
+const PASSWORD_CHANGE_COOKIE_NAME: &str = "__Secure-admin-password-change";
+const PASSWORD_CHANGE_EXPIRY: i64 = 600; // 10 minutes
 
 /// Основной обработчик авторизации админа. 
 /// PATH admin/api/v1/login
 pub async fn admin_login_handler(
     State(state): State<AppState>,
-    headers: HeaderMap,
-    jar: CookieJar,
+    client: ClientInfo,
     Json(payload): Json<AdminLoginRequest>,
-) -> Result<impl IntoResponse, AppError> {
+) -> Result<Response, AppError> {
 
     let admin_login = payload.username.trim();
     let admin_password = payload.password.trim();
 
     if admin_login.is_empty() || admin_password.is_empty() {
-        increment_failed_attempts(&state, &payload.username).await?;
+        increment_failed_attempts(&state.db_redis, &payload.username).await?;
         return Err(AppError::bad_request("Username and password are required"));
     }
 
-    let conn = state
-        .db_redis
-        .get_connection()
-        .await
-        .map_err(AppError::RedisError)?;
+    let admin_user: AdminUser = match check_login(&state.db_postgres, &state.db_redis, admin_login, admin_password).await {
+        Ok(Ok(admin_user)) => admin_user,
+        // Причина отказа остается в журнале, клиент получает общий ответ
+        Ok(Err(failure)) => {
+            audit::record(&state, &client, admin_login, AuditAction::LoginFailure, json!({ "reason": failure.reason() })).await;
+            return Err(AppError::unauthorized("Invalid credentials"));
+        }
+        Err(e) => {
+            audit::record(&state, &client, admin_login, AuditAction::LoginFailure, json!({ "reason": e.to_string() })).await;
+            return Err(e);
+        }
+    };
 
-    let mut csrf = CsrfService::new(conn, 3600);
+    // Clearing the failed login attempts counter
+    clear_failed_attempts(&state.db_redis, admin_login).await?;
+
+    // Второй фактор проверяется до выдачи сессии
+    if admin_user.totp_enabled {
+        return start_two_factor(&state, &admin_user).await;
+    }
 
-    let csrf_token = headers
-        .get("X-CSRF-Token")
-        .and_then(|v| v.to_str().ok())
-        .ok_or_else(|| AppError::unauthorized("Missing CSRF token"))?;
+    complete_login(&state, &admin_user, &client).await
+}
+
+/// Завершение входа после проверки всех факторов
+pub async fn complete_login(
+    state: &AppState,
+    admin_user: &AdminUser,
+    client: &ClientInfo,
+) -> Result<Response, AppError> {
+    // Сессия не выдается, пока не будет сменен временный пароль
+    if admin_user.must_change_password {
+        return start_password_change(state, admin_user).await;
+    }
 
-    let session_id: &str = jar
-        .get("session_id")
-        .map(|cookie| cookie.value())
-        .ok_or_else(|| AppError::unauthorized("Missing session_id"))?;
+    issue_admin_session(state, admin_user, client).await
+}
 
+/// Смена временного пароля перед первым входом.
+/// PATH admin/api/v1/change-password
+pub async fn admin_change_password_handler(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    client: ClientInfo,
+    Json(payload): Json<ChangePasswordRequest>,
+) -> Result<Response, AppError> {
 
-    csrf.validate_csrf_token(session_id, csrf_token).await?;
+    let ticket = jar
+        .get(PASSWORD_CHANGE_COOKIE_NAME)
+        .map(|cookie| cookie.value().to_string())
+        .ok_or_else(|| AppError::unauthorized("Password change session expired"))?;
 
-    check_rate_limit(&state, &admin_login).await?;
-    let admin_user: AdminUser = authenticate_admin(&state, &admin_login, &admin_password).await?;
-    let session_id = Uuid::new_v4().to_string();
-    let (access_token, refresh_token) = create_admin_tokens(&state, &admin_user, &session_id).await?;
+    let mut conn = state.db_redis.get_connection().await?;
+    let ticket_key = format!("password_change:{}", ticket);
+    let ticket_data: Option<String> = conn.get(&ticket_key).await?;
+    let mut admin_user: AdminUser = match ticket_data {
+        Some(data) => serde_json::from_str(&data)?,
+        None => return Err(AppError::unauthorized("Password change session expired")),
+    };
 
-    // Saving the session in Redis
-    save_admin_session(&state, &session_id, &admin_user, &access_token, &refresh_token).await?;
+    validate_password_strength(&payload.new_password, &admin_user.username)?;
 
-    // Clearing the failed login attempts counter
-    clear_failed_attempts(&state, &admin_login).await?;
+    let current_hash = state.db_postgres
+        .get_admin_password_hash(admin_user.id)
+        .await?
+        .ok_or_else(|| AppError::unauthorized("Invalid credentials"))?;
+    if verify(&payload.new_password, &current_hash).unwrap_or(false) {
+        return Err(AppError::bad_request("New password must differ from the current one"));
+    }
 
-    let cookie_value = format!("{}:{}", access_token, refresh_token);
-    let secure_cookie = format!(
-        "{}={}; HttpOnly; Secure; SameSite=Strict; Path=/; Max-Age={}",
-        SECURE_COOKIE_NAME,
-        cookie_value,
-        ACCESS_TOKEN_EXPIRY
+    let password_hash = hash(&payload.new_password, DEFAULT_COST)?;
+    state.db_postgres
+        .update_admin_password(admin_user.id, &password_hash, false)
+        .await?;
+    let _: () = conn.del(&ticket_key).await?;
+
+    tracing::info!("Admin {} changed temporary password", admin_user.id);
+    audit::record(&state, &client, &admin_user.username, AuditAction::PasswordChange, json!({})).await;
+
+    admin_user.must_change_password = false;
+    let mut response = issue_admin_session(&state, &admin_user, &client).await?;
+    let expired_ticket_cookie = format!(
+        "{}=; HttpOnly; Secure; SameSite=Strict; Path=/admin; Max-Age=0",
+        PASSWORD_CHANGE_COOKIE_NAME
+    );
+    response.headers_mut().append(SET_COOKIE, expired_ticket_cookie.parse().unwrap());
+
+    Ok(response)
+}
+
+/// Выдача короткоживущего разрешения на смену пароля вместо сессии
+async fn start_password_change(state: &AppState, admin: &AdminUser) -> Result<Response, AppError> {
+    let mut conn = state.db_redis.get_connection().await?;
+    let ticket = Uuid::new_v4().to_string();
+    let ticket_key = format!("password_change:{}", ticket);
+    let _: () = conn
+        .set_ex(&ticket_key, serde_json::to_string(admin)?, PASSWORD_CHANGE_EXPIRY as u64)
+        .await?;
+
+    let ticket_cookie = format!(
+        "{}={}; HttpOnly; Secure; SameSite=Strict; Path=/admin; Max-Age={}",
+        PASSWORD_CHANGE_COOKIE_NAME,
+        ticket,
+        PASSWORD_CHANGE_EXPIRY
+    );
+
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert("Set-Cookie", ticket_cookie.parse().unwrap());
+    response_headers.insert(
+        "Cache-Control",
+        "no-store, no-cache, must-revalidate, private".parse().unwrap(),
     );
 
+    Ok((
+        StatusCode::OK,
+        response_headers,
+        Json(AdminLoginResponse {
+            redirect_url: "/admin/change-password".to_string(),
+            expires_in: PASSWORD_CHANGE_EXPIRY,
+        }),
+    ).into_response())
+}
+
+/// Создание сессии и установка cookie после успешной аутентификации
+async fn issue_admin_session(
+    state: &AppState,
+    admin_user: &AdminUser,
+    client: &ClientInfo,
+) -> Result<Response, AppError> {
+    let session_id = Uuid::new_v4().to_string();
+    let tokens = create_admin_tokens(state, admin_user, &session_id).await?;
+
+    // Saving the session in Redis
+    save_admin_session(state, &session_id, admin_user, &tokens, client).await?;
+    state.db_postgres.record_admin_login(admin_user.id).await?;
+    audit::record(state, client, &admin_user.username, AuditAction::LoginSuccess, json!({ "session_id": session_id })).await;
+
     let mut response_headers = HeaderMap::new();
-    response_headers.insert("Set-Cookie", secure_cookie.parse().unwrap());
+    response_headers.insert("Set-Cookie", session_cookie(&tokens).parse().unwrap());
     response_headers.insert(
         "Cache-Control",
         "no-store, no-cache, must-revalidate, private".parse().unwrap(),
@@ -124,35 +329,68 @@ pub async fn admin_login_handler(
         Json(AdminLoginResponse {
             redirect_url: "/admin/dashboard".to_string(),
             expires_in: ACCESS_TOKEN_EXPIRY,
-
         }),
-    ))
+    ).into_response())
 }
 
+/// Причина отказа во входе по паролю
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LoginFailure {
+    UnknownUser,
+    Inactive,
+    WrongPassword,
+}
+
+impl LoginFailure {
+    fn reason(&self) -> &'static str {
+        match self {
+            LoginFailure::UnknownUser => "unknown_user",
+            LoginFailure::Inactive => "inactive",
+            LoginFailure::WrongPassword => "wrong_password",
+        }
+    }
+}
+
+/// Проверка логина и пароля. Каждая неудача увеличивает счетчик попыток,
+/// после `MAX_LOGIN_ATTEMPTS` неудач вход блокируется до истечения окна
+async fn check_login(
+    db: &PostgresDatabase,
+    redis: &RedisDatabase,
+    username: &str,
+    password: &str,
+) -> Result<Result<AdminUser, LoginFailure>, AppError> {
+    check_rate_limit(redis, username).await?;
+
+    let result = authenticate_admin(db, username, password).await?;
+    if result.is_err() {
+        increment_failed_attempts(redis, username).await?;
+    }
+    Ok(result)
+}
 
 /// Проверка лимита попыток входа
-async fn check_rate_limit(state: &AppState, username: &str) -> Result<(), AppError> {
-    let mut conn = state.db_redis.get_connection().await?;
+async fn check_rate_limit(redis: &RedisDatabase, username: &str) -> Result<(), AppError> {
+    let mut conn = redis.get_connection().await?;
     let key = format!("login_attempts:{}", username);
 
     let attempts: Option<i32> = conn
         .get(&key)
         .await?;
 
-    if let Some(count) = attempts {
-        if count >= MAX_LOGIN_ATTEMPTS {
-            return Err(AppError::too_many_requests(
-                "Too many login attempts. Please try again later."
-            ));
-        }
+    if let Some(count) = attempts
+        && count >= MAX_LOGIN_ATTEMPTS
+    {
+        return Err(AppError::too_many_requests(
+            "Too many login attempts. Please try again later."
+        ));
     }
 
     Ok(())
 }
 
 /// Увеличение счетчика неудачных попыток
-async fn increment_failed_attempts(state: &AppState, username: &str) -> Result<(), AppError> {
-    let mut conn = state.db_redis.get_connection().await?;
+async fn increment_failed_attempts(redis: &RedisDatabase, username: &str) -> Result<(), AppError> {
+    let mut conn = redis.get_connection().await?;
     let key = format!("login_attempts:{}", username);
 
     let _: () = conn.incr(&key, 1).await?;
@@ -162,8 +400,8 @@ async fn increment_failed_attempts(state: &AppState, username: &str) -> Result<(
 }
 
 /// Очистка счетчика неудачных попыток
-async fn clear_failed_attempts(state: &AppState, username: &str) -> Result<(), AppError> {
-    let mut conn = state.db_redis.get_connection().await?;
+async fn clear_failed_attempts(redis: &RedisDatabase, username: &str) -> Result<(), AppError> {
+    let mut conn = redis.get_connection().await?;
     let key = format!("login_attempts:{}", username);
 
     let _: () = conn.del(&key).await?;
@@ -172,32 +410,30 @@ async fn clear_failed_attempts(state: &AppState, username: &str) -> Result<(), A
 }
 
 /// Аутентификация админа
-async fn authenticate_admin(state: &AppState, username: &str, password: &str) -> Result<AdminUser, AppError> {
-    let admin_password_opt = state.db_postgres.get_admin_password(username).await?;
-
-    if let Some((id, password_hash)) = admin_password_opt {
-        println!("\npasswor {}", password_hash);
-
-        match verify(&password, password_hash.as_str()) {
-            Ok(true) => {
-                Ok(AdminUser {
-                    id,
-                    username: username.to_string(),
-                    role: "admin".to_string(),
-                })
-            }
-            Ok(false) | Err(_) => {
-                Err(AppError::unauthorized("Invalid credentials"))
-            }
-        }
-    } else {
-        Err(AppError::unauthorized("Invalid credentials"))
-    }
-}
+async fn authenticate_admin(
+    db: &PostgresDatabase,
+    username: &str,
+    password: &str,
+) -> Result<Result<AdminUser, LoginFailure>, AppError> {
+    let Some(credentials) = db.get_admin_credentials(username).await? else {
+        return Ok(Err(LoginFailure::UnknownUser));
+    };
 
+    Ok(match verify(password, credentials.password.as_str()) {
+        Ok(true) if !credentials.is_active => Err(LoginFailure::Inactive),
+        Ok(true) => Ok(AdminUser {
+            id: credentials.id,
+            username: username.to_string(),
+            role: credentials.role,
+            must_change_password: credentials.must_change_password,
+            totp_enabled: credentials.totp_enabled,
+        }),
+        Ok(false) | Err(_) => Err(LoginFailure::WrongPassword),
+    })
+}
 
 /// Создание JWT токенов
-async fn create_admin_tokens(state: &AppState, admin: &AdminUser, session_id: &str) -> Result<(String, String), AppError> {
+async fn create_admin_tokens(state: &AppState, admin: &AdminUser, session_id: &str) -> Result<IssuedTokens, AppError> {
     let jwt_secret = state.jwt_secret.clone();
     let now = Utc::now().timestamp();
     
@@ -207,8 +443,9 @@ async fn create_admin_tokens(state: &AppState, admin: &AdminUser, session_id: &s
         iat: now,
         exp: now + ACCESS_TOKEN_EXPIRY,
         jti: Uuid::new_v4().to_string(),
-        role: admin.role.clone(),
+        role: admin.role,
         session_id: session_id.to_string(),
+        token_type: TokenType::Access,
     };
 
     // Refresh token
@@ -217,8 +454,9 @@ async fn create_admin_tokens(state: &AppState, admin: &AdminUser, session_id: &s
         iat: now,
         exp: now + REFRESH_TOKEN_EXPIRY,
         jti: Uuid::new_v4().to_string(),
-        role: admin.role.clone(),
+        role: admin.role,
         session_id: session_id.to_string(),
+        token_type: TokenType::Refresh,
     };
 
     let encoding_key = EncodingKey::from_secret(jwt_secret.as_ref());
@@ -226,7 +464,12 @@ async fn create_admin_tokens(state: &AppState, admin: &AdminUser, session_id: &s
     let access_token = encode(&Header::default(), &access_claims, &encoding_key)?;
     let refresh_token = encode(&Header::default(), &refresh_claims, &encoding_key)?;
 
-    Ok((access_token, refresh_token))
+    Ok(IssuedTokens {
+        access_token,
+        refresh_token,
+        access_claims,
+        refresh_jti: refresh_claims.jti,
+    })
 }
 
 /// Save session the Redis
@@ -234,8 +477,8 @@ async fn save_admin_session(
     state: &AppState,
     session_id: &str,
     admin: &AdminUser,
-    access_token: &str,
-    refresh_token: &str,
+    tokens: &IssuedTokens,
+    client: &ClientInfo,
 ) -> Result<(), AppError> {
     let mut conn = state.db_redis.get_connection().await?;
 
@@ -243,29 +486,201 @@ async fn save_admin_session(
         "admin_id": admin.id,
         "username": admin.username,
         "role": admin.role,
-        "access_token": access_token,
-        "refresh_token": refresh_token,
+        "ip": client.ip,
+        "user_agent": client.user_agent,
         "created_at": Utc::now().timestamp(),
         "last_activity": Utc::now().timestamp()
     });
 
     let session_key = format!("admin_session:{}", session_id);
     
-    // Сохраняем сессию
+    // Сессия живет столько же, сколько refresh-токен
     let _: () = conn
-        .set_ex(&session_key, session_data.to_string(), ACCESS_TOKEN_EXPIRY as u64)
+        .set_ex(&session_key, session_data.to_string(), REFRESH_TOKEN_EXPIRY as u64)
+        .await?;
+
+    // Текущий действительный refresh-токен сессии
+    let refresh_key = format!("session_refresh:{}", session_id);
+    let _: () = conn
+        .set_ex(&refresh_key, &tokens.refresh_jti, REFRESH_TOKEN_EXPIRY as u64)
         .await?;
 
     // Сохраняем mapping токена к сессии для быстрого поиска
-    let token_key = format!("token_session:{}", access_token);
+    let token_key = format!("token_session:{}", tokens.access_token);
     let _: () = conn
         .set_ex(&token_key, session_id, ACCESS_TOKEN_EXPIRY as u64)
         .await?;
 
+    // Индекс сессий администратора для просмотра и отзыва
+    let index_key = format!("admin_sessions:{}", admin.id);
+    let _: () = conn.sadd(&index_key, session_id).await?;
+    let _: () = conn.expire(&index_key, REFRESH_TOKEN_EXPIRY).await?;
+
+    Ok(())
+}
+
+fn session_cookie(tokens: &IssuedTokens) -> String {
+    format!(
+        "{}={}:{}; HttpOnly; Secure; SameSite=Strict; Path=/; Max-Age={}",
+        SECURE_COOKIE_NAME,
+        tokens.access_token,
+        tokens.refresh_token,
+        REFRESH_TOKEN_EXPIRY
+    )
+}
+
+/// Обновление пары токенов по refresh-токену.
+/// PATH admin/api/v1/refresh
+pub async fn admin_refresh_handler(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<impl IntoResponse, AppError> {
+    let refresh_token = jar
+        .get(SECURE_COOKIE_NAME)
+        .and_then(|cookie| cookie.value().split_once(':').map(|(_, refresh)| refresh.to_string()))
+        .filter(|refresh| !refresh.is_empty())
+        .ok_or_else(|| AppError::unauthorized("Missing refresh token"))?;
+
+    let tokens = rotate_admin_session(&state, &refresh_token).await?;
+
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert("Set-Cookie", session_cookie(&tokens).parse().unwrap());
+    response_headers.insert(
+        "Cache-Control",
+        "no-store, no-cache, must-revalidate, private".parse().unwrap(),
+    );
+
+    Ok((
+        StatusCode::OK,
+        response_headers,
+        Json(AdminRefreshResponse {
+            expires_in: ACCESS_TOKEN_EXPIRY,
+        }),
+    ))
+}
+
+/// Ротация токенов сессии с обнаружением повторного использования:
+/// предъявление уже замененного refresh-токена завершает всю сессию
+async fn rotate_admin_session(state: &AppState, refresh_token: &str) -> Result<IssuedTokens, AppError> {
+    let claims = validate_admin_token(state, refresh_token, TokenType::Refresh).await?;
+
+    let mut conn = state.db_redis.get_connection().await?;
+    let session_key = format!("admin_session:{}", claims.session_id);
+    let session_data: Option<String> = conn.get(&session_key).await?;
+    let Some(session_data) = session_data else {
+        return Err(AppError::unauthorized("Session not found or expired"));
+    };
+
+    // Роль и статус учетной записи перечитываются при каждой ротации
+    let admin_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::unauthorized("Invalid admin ID"))?;
+    let account = match state.db_postgres.get_admin_account(admin_id).await? {
+        Some(account) if account.is_active => account,
+        _ => {
+            revoke_admin_session(state, &claims.sub, &claims.session_id).await?;
+            return Err(AppError::unauthorized("Account is disabled"));
+        }
+    };
+    let admin_user = AdminUser {
+        id: account.id,
+        username: account.username,
+        role: account.role,
+        must_change_password: account.must_change_password,
+        totp_enabled: account.totp_enabled,
+    };
+
+    let tokens = create_admin_tokens(state, &admin_user, &claims.session_id).await?;
+
+    let outcome = swap_refresh_token(state, &claims.session_id, &claims.jti, &tokens.refresh_jti).await?;
+    match outcome {
+        RotationOutcome::Rotated => {}
+        RotationOutcome::AlreadyRotated => {
+            return Err(AppError::conflict("Session was refreshed by a concurrent request"));
+        }
+        RotationOutcome::Reused => {
+            tracing::warn!(
+                admin_id = %claims.sub,
+                session_id = %claims.session_id,
+                "Refresh token reuse detected, revoking session"
+            );
+            revoke_admin_session(state, &claims.sub, &claims.session_id).await?;
+            return Err(AppError::unauthorized("Refresh token reuse detected"));
+        }
+        RotationOutcome::SessionMissing => {
+            return Err(AppError::unauthorized("Session not found or expired"));
+        }
+    }
+
+    let mut session: serde_json::Value = serde_json::from_str(&session_data)?;
+    session["role"] = serde_json::json!(admin_user.role);
+    let _: () = conn
+        .set_ex(&session_key, session.to_string(), REFRESH_TOKEN_EXPIRY as u64)
+        .await?;
+
+    let token_key = format!("token_session:{}", tokens.access_token);
+    let _: () = conn
+        .set_ex(&token_key, &claims.session_id, ACCESS_TOKEN_EXPIRY as u64)
+        .await?;
+
+    Ok(tokens)
+}
+
+/// Атомарная замена jti refresh-токена (compare-and-swap в Lua)
+async fn swap_refresh_token(
+    state: &AppState,
+    session_id: &str,
+    presented_jti: &str,
+    new_jti: &str,
+) -> Result<RotationOutcome, AppError> {
+    let script = Script::new(
+        r#"
+        local current = redis.call('GET', KEYS[1])
+        if not current then return -1 end
+        if current == ARGV[1] then
+            redis.call('SET', KEYS[1], ARGV[2], 'EX', ARGV[3])
+            redis.call('SET', KEYS[2], ARGV[1], 'EX', ARGV[4])
+            return 1
+        end
+        if redis.call('GET', KEYS[2]) == ARGV[1] then return 2 end
+        return 0
+        "#,
+    );
+
+    let mut conn = state.db_redis.get_connection().await?;
+    let result: i32 = script
+        .key(format!("session_refresh:{}", session_id))
+        .key(format!("session_refresh_prev:{}", session_id))
+        .arg(presented_jti)
+        .arg(new_jti)
+        .arg(REFRESH_TOKEN_EXPIRY)
+        .arg(REFRESH_REUSE_GRACE)
+        .invoke_async(&mut conn)
+        .await?;
+
+    Ok(match result {
+        1 => RotationOutcome::Rotated,
+        2 => RotationOutcome::AlreadyRotated,
+        -1 => RotationOutcome::SessionMissing,
+        _ => RotationOutcome::Reused,
+    })
+}
+
+/// Удаление сессии вместе со всеми ее refresh-токенами
+pub async fn revoke_admin_session(state: &AppState, admin_id: &str, session_id: &str) -> Result<(), AppError> {
+    let mut conn = state.db_redis.get_connection().await?;
+    let _: () = conn
+        .del(&[
+            format!("admin_session:{}", session_id),
+            format!("session_refresh:{}", session_id),
+            format!("session_refresh_prev:{}", session_id),
+        ])
+        .await?;
+    let _: () = conn.srem(format!("admin_sessions:{}", admin_id), session_id).await?;
     Ok(())
 }
 
-/// Middleware для проверки авторизации админа
+/// Middleware для проверки авторизации админа.
+/// Access-токен, истекающий в ближайшие минуты, прозрачно обновляется по refresh-токену
 pub async fn admin_auth_middleware(
     State(state): State<AppState>,
     jar: CookieJar,
@@ -280,33 +695,77 @@ pub async fn admin_auth_middleware(
         }
     };
 
-    let token = {
-        let value = cookie.value();
-        match value.splitn(2, ':').next().filter(|s| !s.is_empty()) {
-            Some(t) => t.to_string(),
-            None => {
-                return Ok(Redirect::to("/admin/login").into_response());
+    let (access_token, refresh_token) = match cookie.value().split_once(':') {
+        Some((access, refresh)) => (access, Some(refresh).filter(|s| !s.is_empty())),
+        None => (cookie.value(), None),
+    };
+
+    let access_claims = validate_admin_token(&state, access_token, TokenType::Access).await.ok();
+    let needs_refresh = access_claims
+        .as_ref()
+        .is_none_or(|c| c.exp - Utc::now().timestamp() < ACCESS_TOKEN_REFRESH_THRESHOLD);
+
+    if needs_refresh && let Some(refresh_token) = refresh_token {
+        match rotate_admin_session(&state, refresh_token).await {
+            Ok(tokens) => {
+                let Some(username) = touch_admin_session(&state, &tokens.access_claims.session_id).await? else {
+                    return Ok(Redirect::to("/admin/login").into_response());
+                };
+                request
+                    .extensions_mut()
+                    .insert(CurrentAdmin::from_claims(&tokens.access_claims, username)?);
+
+                let mut response = next.run(request).await;
+                response
+                    .headers_mut()
+                    .append(SET_COOKIE, session_cookie(&tokens).parse().unwrap());
+                return Ok(response);
             }
+            Err(e) => tracing::debug!(error = %e, "Transparent token refresh failed"),
         }
-    };
+    }
 
-    let claims = match validate_admin_token(&state, &token).await {
-        Ok(c) => c,
-        Err(_) => return Ok(Redirect::to("/admin/login").into_response()),
+    let claims = match access_claims {
+        Some(c) => c,
+        None => return Ok(Redirect::to("/admin/login").into_response()),
     };
 
-    if validate_admin_session(&state, &claims).await.is_err() {
+    let Some(username) = touch_admin_session(&state, &claims.session_id).await? else {
         return Ok(Redirect::to("/admin/login").into_response());
-    }
+    };
+
+    request.extensions_mut().insert(CurrentAdmin::from_claims(&claims, username)?);
+    Ok(next.run(request).await)
+}
 
-    update_session_activity(&state, &claims.session_id).await?;
+/// Проверка прав роли на маршрут. Подключается поверх `admin_auth_middleware`:
+/// `.route_layer(from_fn_with_state(Permission::ChangeStatus, require_permission))`
+pub async fn require_permission(
+    State(permission): State<Permission>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let admin = request
+        .extensions()
+        .get::<CurrentAdmin>()
+        .ok_or_else(|| AppError::unauthorized("Missing admin session"))?;
+
+    if !admin.role.can(permission) {
+        tracing::warn!(
+            admin_id = %admin.id,
+            username = %admin.username,
+            role = %admin.role,
+            ?permission,
+            "Permission denied"
+        );
+        return Err(AppError::forbidden("Insufficient permissions"));
+    }
 
-    request.extensions_mut().insert(claims);
     Ok(next.run(request).await)
 }
 
 /// Валидация JWT токена
-async fn validate_admin_token(state: &AppState, token: &str) -> Result<AdminClaims, AppError> {
+async fn validate_admin_token(state: &AppState, token: &str, token_type: TokenType) -> Result<AdminClaims, AppError> {
     let jwt_secret = state.jwt_secret.clone();
 
     let decoding_key = DecodingKey::from_secret(jwt_secret.as_ref());
@@ -315,59 +774,68 @@ async fn validate_admin_token(state: &AppState, token: &str) -> Result<AdminClai
     let token_data = decode::<AdminClaims>(token, &decoding_key, &validation)
         .map_err(|_| AppError::unauthorized("Invalid or expired token"))?;
 
-    Ok(token_data.claims)
-}
-
-/// Валидация сессии в Redis
-async fn validate_admin_session(state: &AppState, claims: &AdminClaims) -> Result<(), AppError> {
-    let mut conn = state.db_redis.get_connection().await?;
-    let session_key = format!("admin_session:{}", claims.session_id);
-
-    let session_exists: bool = conn.exists(&session_key).await?;
-
-    if !session_exists {
-        return Err(AppError::unauthorized("Session not found or expired"));
+    if token_data.claims.token_type != token_type {
+        return Err(AppError::unauthorized("Invalid token type"));
     }
 
-    Ok(())
+    Ok(token_data.claims)
 }
 
-/// Обновление активности сессии
-async fn update_session_activity(state: &AppState, session_id: &str) -> Result<(), AppError> {
+/// Обновление активности сессии.
+/// Возвращает имя администратора из сессии или `None`, если сессия отозвана или истекла
+async fn touch_admin_session(state: &AppState, session_id: &str) -> Result<Option<String>, AppError> {
     let mut conn = state.db_redis.get_connection().await?;
     let session_key = format!("admin_session:{}", session_id);
 
-    // Получаем текущую сессию
-    let session_data: Option<String> = conn.get(&session_key).await?;
-
-    if let Some(data) = session_data {
-        let mut session: serde_json::Value = serde_json::from_str(&data)?;
-
-        session["last_activity"] = serde_json::json!(Utc::now().timestamp());
-
-        let _: () = conn
-            .set_ex(&session_key, session.to_string(), ACCESS_TOKEN_EXPIRY as u64)
-            .await?;
-    }
+    let Some(data) = conn.get::<_, Option<String>>(&session_key).await? else {
+        return Ok(None);
+    };
+    let mut session: serde_json::Value = serde_json::from_str(&data)?;
+    let username = session["username"].as_str().unwrap_or_default().to_string();
+
+    session["last_activity"] = serde_json::json!(Utc::now().timestamp());
+
+    // TTL сессии задается при входе и ротации refresh-токена. XX: сессию,
+    // отозванную между чтением и записью, запись не восстанавливает
+    let updated: Option<String> = conn
+        .set_options(
+            &session_key,
+            session.to_string(),
+            SetOptions::default()
+                .conditional_set(ExistenceCheck::XX)
+                .with_expiration(SetExpiry::KEEPTTL),
+        )
+        .await?;
 
-    Ok(())
+    Ok(updated.map(|_| username))
 }
 
-/// Обработчик выхода из системы
+/// Обработчик выхода из системы. Принимает только POST, чтобы выход проходил
+/// проверку CSRF; на страницу входа панель переходит сама
 pub async fn admin_logout_handler(
     State(state): State<AppState>,
     jar: CookieJar,
+    client: ClientInfo,
 ) -> Result<impl IntoResponse, AppError> {
     // Если в куках есть session_id — удаляем сессию в Redis
     if let Some(cookie) = jar.get(SECURE_COOKIE_NAME) {
-        let token_str = cookie.value().splitn(2, ':').next().unwrap_or("");
-        if let Ok(claims) = validate_admin_token(&state, token_str).await {
+        let (access_token, refresh_token) = cookie.value().split_once(':').unwrap_or((cookie.value(), ""));
+        let claims = match validate_admin_token(&state, access_token, TokenType::Access).await {
+            Ok(claims) => Ok(claims),
+            Err(_) => validate_admin_token(&state, refresh_token, TokenType::Refresh).await,
+        };
+        if let Ok(claims) = claims {
             let mut conn = state.db_redis.get_connection().await?;
-            let session_key = format!("admin_session:{}", claims.session_id);
-            let token_key = format!("token_session:{}", token_str);
+            let token_key = format!("token_session:{}", access_token);
 
-            let _: () = conn.del(&session_key).await.unwrap_or(());
+            revoke_admin_session(&state, &claims.sub, &claims.session_id).await.unwrap_or(());
             let _: () = conn.del(&token_key).await.unwrap_or(());
+
+            if let Ok(admin_id) = Uuid::parse_str(&claims.sub)
+                && let Ok(Some(account)) = state.db_postgres.get_admin_account(admin_id).await
+            {
+                audit::record(&state, &client, &account.username, AuditAction::Logout, json!({ "session_id": claims.session_id })).await;
+            }
         }
     }
 
@@ -381,5 +849,39 @@ pub async fn admin_logout_handler(
             .build()    
     );
 
-    Ok((jar, Redirect::to("/admin/login")))
-}
\ No newline at end of file
+    Ok((jar, StatusCode::NO_CONTENT))
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::postgres::postgres_interface::tests::{create_test_admin, delete_test_admin, test_database};
+    use crate::database::redis::redis_interface::tests::test_redis;
+
+    #[tokio::test]
+    async fn wrong_passwords_lock_the_login() {
+        let (Some(db), Some(redis)) = (test_database().await, test_redis().await) else { return };
+        let password_hash = hash("Right-Passw0rd!", 4).unwrap();
+        let (id, username) = create_test_admin(&db, &password_hash, AdminRole::Expert).await;
+
+        let mut results = Vec::new();
+        for _ in 0..MAX_LOGIN_ATTEMPTS {
+            results.push(check_login(&db, &redis, &username, "wrong").await);
+        }
+        // После блокировки не принимается и верный пароль
+        let locked = check_login(&db, &redis, &username, "Right-Passw0rd!").await;
+        let missing = format!("{}-missing", username);
+        let unknown = check_login(&db, &redis, &missing, "wrong").await;
+
+        clear_failed_attempts(&redis, &username).await.unwrap();
+        clear_failed_attempts(&redis, &missing).await.unwrap();
+        let after_reset = check_login(&db, &redis, &username, "Right-Passw0rd!").await;
+        delete_test_admin(&db, id).await;
+
+        for result in results {
+            assert!(matches!(result, Ok(Err(LoginFailure::WrongPassword))));
+        }
+        assert!(matches!(locked, Err(AppError::TooManyRequests(_))));
+        assert!(matches!(unknown, Ok(Err(LoginFailure::UnknownUser))));
+        assert!(matches!(after_reset, Ok(Ok(ref admin)) if admin.id == id));
+    }
+}
@@ -0,0 +1,247 @@
+use axum::{
+    extract::{Json as ExtractJson, Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::audit::{self, AuditAction};
+use crate::database::postgres::error::DatabaseError;
+use crate::database::postgres::models::{
+    CommentEdit, CommentRecord, CommentThread, PaginationResult, MAX_COMMENT_CHARS,
+};
+use crate::error::AppError;
+use crate::events::{self, PanelEvent};
+use crate::notifications;
+use crate::state::AppState;
+use crate::routers::admin::api::ensure_submission_access;
+use crate::routers::admin::auth::{ClientInfo, CurrentAdmin};
+use crate::validation::FieldError;
+
+const DEFAULT_COMMENT_PAGE_SIZE: i64 = 20;
+/// Больше упоминаний в одном комментарии не обрабатывается
+const MAX_MENTIONS: usize = 20;
+
+#[derive(Debug, Deserialize)]
+pub struct CommentsQuery {
+    page: Option<i64>,
+    per_page: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCommentRequest {
+    text: String,
+    /// Ответ на комментарий. Ответ на ответ попадает в ту же ветку
+    parent_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateCommentRequest {
+    text: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreatedComment {
+    comment_id: Uuid,
+    /// Логины администраторов, получивших уведомление об упоминании
+    mentioned: Vec<String>,
+}
+
+// GET /admin/api/v1/submissions/{id}/comments
+pub async fn list_submission_comments(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    Path(submission_id): Path<Uuid>,
+    Query(query): Query<CommentsQuery>,
+) -> Result<Json<PaginationResult<CommentThread>>, AppError> {
+    ensure_submission_access(&state, &admin, submission_id).await?;
+
+    let threads = state.db_postgres
+        .get_comment_threads(
+            submission_id,
+            admin.id,
+            query.page.unwrap_or(1),
+            query.per_page.unwrap_or(DEFAULT_COMMENT_PAGE_SIZE),
+        )
+        .await?;
+
+    Ok(Json(threads))
+}
+
+// POST /admin/api/v1/submissions/{id}/comments
+pub async fn create_submission_comment(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    client: ClientInfo,
+    Path(submission_id): Path<Uuid>,
+    ExtractJson(payload): ExtractJson<CreateCommentRequest>,
+) -> Result<(StatusCode, Json<CreatedComment>), AppError> {
+    ensure_submission_access(&state, &admin, submission_id).await?;
+    let text = validate_comment_text(&payload.text)?;
+
+    let parent_id = match payload.parent_id {
+        Some(parent_id) => {
+            let parent = state.db_postgres
+                .get_comment(parent_id)
+                .await?
+                .filter(|parent| parent.submission_id == submission_id)
+                .ok_or_else(|| AppError::not_found("Parent comment not found"))?;
+            if parent.deleted_at.is_some() {
+                return Err(AppError::conflict("Cannot reply to a deleted comment"));
+            }
+            // Ветки одноуровневые: ответ на ответ привязывается к корню
+            Some(parent.parent_id.unwrap_or(parent.id))
+        }
+        None => None,
+    };
+
+    let mentions = extract_mentions(text);
+    let notification = notifications::mention(&state, submission_id, admin.id, &admin.username, text);
+    let (comment_id, mentioned) = state.db_postgres
+        .create_admin_comment(admin.id, submission_id, parent_id, text, &mentions, &notification)
+        .await
+        .map_err(|e| match e {
+            DatabaseError::ForeignKeyViolation { .. } => AppError::bad_request("Invalid submission ID"),
+            e => AppError::from(e),
+        })?;
+
+    audit::record(&state, &client, &admin.username, AuditAction::Comment, json!({
+        "submission_id": submission_id,
+        "comment_id": comment_id,
+        "parent_id": parent_id,
+        "mentioned": mentioned,
+    })).await;
+    events::publish(&state, PanelEvent::CommentAdded { submission_id, comment_id, parent_id }).await;
+    Ok((StatusCode::CREATED, Json(CreatedComment { comment_id, mentioned })))
+}
+
+// PUT /admin/api/v1/comments/{id}
+pub async fn update_comment(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    client: ClientInfo,
+    Path(comment_id): Path<Uuid>,
+    ExtractJson(payload): ExtractJson<UpdateCommentRequest>,
+) -> Result<Json<CreatedComment>, AppError> {
+    let comment = load_own_comment(&state, &admin, comment_id).await?;
+    let text = validate_comment_text(&payload.text)?;
+
+    let notification = notifications::mention(&state, comment.submission_id, admin.id, &admin.username, text);
+    let mentioned = state.db_postgres
+        .update_admin_comment(comment_id, admin.id, text, &extract_mentions(text), &notification)
+        .await?
+        .ok_or_else(|| AppError::conflict("Comment has been deleted"))?;
+
+    info!(actor = %admin.username, comment_id = %comment_id, "Comment edited");
+    audit::record(&state, &client, &admin.username, AuditAction::CommentEdit, json!({
+        "submission_id": comment.submission_id,
+        "comment_id": comment_id,
+        "mentioned": mentioned,
+    })).await;
+    events::publish(&state, PanelEvent::CommentUpdated { submission_id: comment.submission_id, comment_id }).await;
+    Ok(Json(CreatedComment { comment_id, mentioned }))
+}
+
+// DELETE /admin/api/v1/comments/{id}
+pub async fn delete_comment(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    client: ClientInfo,
+    Path(comment_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let comment = load_own_comment(&state, &admin, comment_id).await?;
+
+    if !state.db_postgres.soft_delete_admin_comment(comment_id).await? {
+        return Err(AppError::conflict("Comment has already been deleted"));
+    }
+
+    info!(actor = %admin.username, comment_id = %comment_id, "Comment deleted");
+    audit::record(&state, &client, &admin.username, AuditAction::CommentDelete, json!({
+        "submission_id": comment.submission_id,
+        "comment_id": comment_id,
+    })).await;
+    events::publish(&state, PanelEvent::CommentDeleted { submission_id: comment.submission_id, comment_id }).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// GET /admin/api/v1/comments/{id}/edits
+pub async fn get_comment_edits(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    Path(comment_id): Path<Uuid>,
+) -> Result<Json<Vec<CommentEdit>>, AppError> {
+    let comment = state.db_postgres
+        .get_comment(comment_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Comment not found"))?;
+    ensure_submission_access(&state, &admin, comment.submission_id).await?;
+
+    // История удаленного комментария скрыта вместе с его текстом
+    if comment.deleted_at.is_some() {
+        return Err(AppError::not_found("Comment not found"));
+    }
+
+    let edits = state.db_postgres.get_comment_edits(comment_id).await?;
+    Ok(Json(edits))
+}
+
+/// Править и удалять комментарий может только его автор
+async fn load_own_comment(
+    state: &AppState,
+    admin: &CurrentAdmin,
+    comment_id: Uuid,
+) -> Result<CommentRecord, AppError> {
+    let comment = state.db_postgres
+        .get_comment(comment_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Comment not found"))?;
+    ensure_submission_access(state, admin, comment.submission_id).await?;
+
+    if comment.admin_id != admin.id {
+        return Err(AppError::forbidden("Only the author can change a comment"));
+    }
+    if comment.deleted_at.is_some() {
+        return Err(AppError::conflict("Comment has been deleted"));
+    }
+
+    Ok(comment)
+}
+
+fn validate_comment_text(text: &str) -> Result<&str, AppError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(AppError::Validation(vec![
+            FieldError::new("text", "required", "Комментарий не может быть пустым"),
+        ]));
+    }
+    if text.chars().count() > MAX_COMMENT_CHARS {
+        return Err(AppError::Validation(vec![
+            FieldError::new("text", "too_long", "Комментарий не должен превышать 5000 символов"),
+        ]));
+    }
+    Ok(text)
+}
+
+/// Логины из упоминаний вида `@username`. Знаки препинания после логина
+/// отбрасываются, повторы учитываются один раз
+fn extract_mentions(text: &str) -> Vec<String> {
+    let mut mentions: Vec<String> = Vec::new();
+
+    for word in text.split_whitespace() {
+        let Some(username) = word.strip_prefix('@') else {
+            continue;
+        };
+        let username = username.trim_end_matches(|c: char| !(c.is_alphanumeric() || c == '_'));
+        if !username.is_empty() && !mentions.iter().any(|m| m == username) {
+            mentions.push(username.to_string());
+        }
+        if mentions.len() == MAX_MENTIONS {
+            break;
+        }
+    }
+
+    mentions
+}
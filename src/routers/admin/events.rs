@@ -0,0 +1,104 @@
+use axum::{
+    extract::State,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use std::convert::Infallible;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::debug;
+
+use crate::events::{LiveUpdate, PanelEvent};
+use crate::state::AppState;
+use crate::routers::admin::auth::CurrentAdmin;
+
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(20);
+/// Поток закрывается по времени событием `reconnect`, и браузер подключается
+/// заново через `admin_auth_middleware`: так отзыв сессии действует и на
+/// открытые потоки, а токен доступа обновляется до истечения
+const MAX_STREAM_DURATION: Duration = Duration::from_secs(300);
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+const STREAM_BUFFER: usize = 32;
+
+// GET /admin/api/v1/events
+// События панели: submission_created, status_changed, comment_added,
+// comment_updated, comment_deleted, assignment_changed, resync, если часть
+// событий потеряна, и reconnect
+pub async fn panel_events(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+) -> Sse<ReceiverStream<Result<Event, Infallible>>> {
+    let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+    let updates = state.events.subscribe();
+    tokio::spawn(forward_events(state, admin, updates, tx));
+
+    Sse::new(ReceiverStream::new(rx)).keep_alive(KeepAlive::new().interval(KEEP_ALIVE_INTERVAL))
+}
+
+async fn forward_events(
+    state: AppState,
+    admin: CurrentAdmin,
+    mut updates: broadcast::Receiver<LiveUpdate>,
+    tx: mpsc::Sender<Result<Event, Infallible>>,
+) {
+    let deadline = tokio::time::sleep(MAX_STREAM_DURATION);
+    tokio::pin!(deadline);
+
+    if tx.send(Ok(Event::default().retry(RECONNECT_DELAY).comment("connected"))).await.is_err() {
+        return;
+    }
+
+    loop {
+        let update = tokio::select! {
+            update = updates.recv() => update,
+            _ = tx.closed() => break,
+            _ = &mut deadline => {
+                let _ = tx.send(Ok(Event::default().event("reconnect").data("{}"))).await;
+                break;
+            }
+        };
+
+        let event = match update {
+            Ok(LiveUpdate::Event(event)) => {
+                if !can_see(&state, &admin, &event).await {
+                    continue;
+                }
+                match Event::default().event(event.kind()).json_data(&event) {
+                    Ok(event) => event,
+                    Err(e) => {
+                        debug!(error = %e, "Failed to serialize panel event");
+                        continue;
+                    }
+                }
+            }
+            // Поток не успевал за событиями
+            Ok(LiveUpdate::Resync) | Err(broadcast::error::RecvError::Lagged(_)) => {
+                Event::default().event("resync").data("{}")
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+
+        if tx.send(Ok(event)).await.is_err() {
+            break;
+        }
+    }
+
+    debug!(admin = %admin.username, "Panel event stream closed");
+}
+
+/// Эксперт получает события только по назначенным ему заявкам
+async fn can_see(state: &AppState, admin: &CurrentAdmin, event: &PanelEvent) -> bool {
+    if !admin.role.sees_only_assigned() {
+        return true;
+    }
+    if let PanelEvent::AssignmentChanged { assignee_id: Some(assignee_id), .. } = event
+        && *assignee_id == admin.id
+    {
+        return true;
+    }
+
+    state.db_postgres
+        .is_submission_assigned_to(event.submission_id(), admin.id)
+        .await
+        .unwrap_or(false)
+}
@@ -0,0 +1,24 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+
+/// Значение параметра фильтра без пробелов по краям. Пустая строка
+/// означает, что фильтр не задан
+pub fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// Начало суток в UTC
+pub fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
+    date.and_time(NaiveTime::MIN).and_utc()
+}
+
+/// Границы `[date_from, date_to + 1 день)` для фильтра по датам,
+/// в котором обе даты включаются
+pub fn day_range(
+    date_from: Option<NaiveDate>,
+    date_to: Option<NaiveDate>,
+) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+    (
+        date_from.map(start_of_day),
+        date_to.and_then(|date| date.succ_opt()).map(start_of_day),
+    )
+}
@@ -1,3 +1,14 @@
 pub mod api;
 pub mod web;
-pub mod auth;
\ No newline at end of file
+pub mod auth;
+pub mod admins;
+pub mod two_factor;
+pub mod sessions;
+pub mod assignment;
+pub mod quotes;
+pub mod audit;
+pub mod filters;
+pub mod comments;
+pub mod events;
+pub mod notifications;
+pub mod attachments;
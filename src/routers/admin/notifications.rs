@@ -0,0 +1,106 @@
+use axum::{
+    extract::{Json as ExtractJson, Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::database::postgres::models::{Notification, NotificationPreference, PaginationResult};
+use crate::error::AppError;
+use crate::state::AppState;
+use crate::routers::admin::auth::CurrentAdmin;
+
+const DEFAULT_NOTIFICATION_PAGE_SIZE: i64 = 20;
+
+#[derive(Debug, Deserialize)]
+pub struct NotificationsQuery {
+    /// Только непрочитанные
+    #[serde(default)]
+    unread: bool,
+    page: Option<i64>,
+    per_page: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NotificationsPage {
+    #[serde(flatten)]
+    page: PaginationResult<Notification>,
+    unread_count: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateNotificationRequest {
+    read: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MarkedRead {
+    updated: u64,
+}
+
+// GET /admin/api/v1/notifications
+// Входящие текущего администратора
+pub async fn list_notifications(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    Query(query): Query<NotificationsQuery>,
+) -> Result<Json<NotificationsPage>, AppError> {
+    let page = state.db_postgres
+        .list_notifications(
+            admin.id,
+            query.unread,
+            query.page.unwrap_or(1),
+            query.per_page.unwrap_or(DEFAULT_NOTIFICATION_PAGE_SIZE),
+        )
+        .await?;
+    let unread_count = state.db_postgres.count_unread_notifications(admin.id).await?;
+
+    Ok(Json(NotificationsPage { page, unread_count }))
+}
+
+// PUT /admin/api/v1/notifications/{id}
+// Пример запроса {"read": false}
+pub async fn update_notification(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    Path(notification_id): Path<Uuid>,
+    ExtractJson(payload): ExtractJson<UpdateNotificationRequest>,
+) -> Result<StatusCode, AppError> {
+    if !state.db_postgres.set_notification_read(notification_id, admin.id, payload.read).await? {
+        return Err(AppError::not_found("Notification not found"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// POST /admin/api/v1/notifications/read-all
+pub async fn mark_all_notifications_read(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+) -> Result<Json<MarkedRead>, AppError> {
+    let updated = state.db_postgres.mark_all_notifications_read(admin.id).await?;
+    Ok(Json(MarkedRead { updated }))
+}
+
+// GET /admin/api/v1/notifications/preferences
+pub async fn get_notification_preferences(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+) -> Result<Json<Vec<NotificationPreference>>, AppError> {
+    let preferences = state.db_postgres.get_notification_preferences(admin.id).await?;
+    Ok(Json(preferences))
+}
+
+// PUT /admin/api/v1/notifications/preferences
+// Пример запроса [{"kind": "mention", "inbox": true, "email": true}].
+// Виды, не указанные в запросе, не меняются
+pub async fn update_notification_preferences(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    ExtractJson(preferences): ExtractJson<Vec<NotificationPreference>>,
+) -> Result<Json<Vec<NotificationPreference>>, AppError> {
+    state.db_postgres.set_notification_preferences(admin.id, &preferences).await?;
+
+    let preferences = state.db_postgres.get_notification_preferences(admin.id).await?;
+    Ok(Json(preferences))
+}
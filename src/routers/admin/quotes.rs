@@ -0,0 +1,125 @@
+use axum::{
+    extract::{Json as ExtractJson, Path, State},
+    http::StatusCode,
+    response::{Html, Json},
+};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::fs;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::audit::{self, AuditAction};
+use crate::database::postgres::models::{QuoteHistoryEntry, QUOTE_CURRENCIES};
+use crate::error::AppError;
+use crate::quote::{render_quote_document, QUOTE_TEMPLATE_PATH};
+use crate::state::AppState;
+use crate::routers::admin::api::ensure_submission_access;
+use crate::routers::admin::auth::{ClientInfo, CurrentAdmin};
+use crate::validation::FieldError;
+
+const MAX_QUOTE_NOTE_CHARS: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateQuoteRequest {
+    /// Стоимость в копейках (центах)
+    price: i64,
+    #[serde(default = "default_currency")]
+    currency: String,
+    note: Option<String>,
+}
+
+fn default_currency() -> String {
+    "RUB".to_string()
+}
+
+// PUT /admin/api/v1/submissions/{id}/quote
+pub async fn update_submission_quote(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    client: ClientInfo,
+    Path(submission_id): Path<Uuid>,
+    ExtractJson(payload): ExtractJson<UpdateQuoteRequest>,
+) -> Result<StatusCode, AppError> {
+    let mut errors = Vec::new();
+
+    if payload.price < 0 {
+        errors.push(FieldError::new("price", "invalid_range", "Стоимость не может быть отрицательной"));
+    }
+
+    let currency = payload.currency.trim().to_uppercase();
+    if !QUOTE_CURRENCIES.contains(&currency.as_str()) {
+        errors.push(FieldError::new("currency", "invalid_value", format!("Неподдерживаемая валюта: {}", currency)));
+    }
+
+    let note = payload.note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    if note.is_some_and(|n| n.chars().count() > MAX_QUOTE_NOTE_CHARS) {
+        errors.push(FieldError::new("note", "too_long", "Примечание не должно превышать 1000 символов"));
+    }
+
+    if !errors.is_empty() {
+        return Err(AppError::Validation(errors));
+    }
+
+    let updated = state
+        .db_postgres
+        .set_submission_quote(submission_id, admin.id, payload.price, &currency, note)
+        .await?;
+    if !updated {
+        return Err(AppError::not_found("Submission not found"));
+    }
+
+    info!(
+        actor = %admin.username,
+        submission_id = %submission_id,
+        price = payload.price,
+        currency = %currency,
+        "Submission quote updated"
+    );
+    audit::record(&state, &client, &admin.username, AuditAction::PriceChange, json!({
+        "submission_id": submission_id,
+        "price": payload.price,
+        "currency": currency,
+    })).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// GET /admin/api/v1/submissions/{id}/quote/history
+pub async fn get_submission_quote_history(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    Path(submission_id): Path<Uuid>,
+) -> Result<Json<Vec<QuoteHistoryEntry>>, AppError> {
+    ensure_submission_access(&state, &admin, submission_id).await?;
+
+    let history = state.db_postgres.get_quote_history(submission_id).await?;
+    Ok(Json(history))
+}
+
+// GET /admin/api/v1/submissions/{id}/quote/document
+// Коммерческое предложение для отправки клиенту
+pub async fn get_submission_quote_document(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    Path(submission_id): Path<Uuid>,
+) -> Result<Html<String>, AppError> {
+    ensure_submission_access(&state, &admin, submission_id).await?;
+
+    let submission = state
+        .db_postgres
+        .get_submission(submission_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Submission not found"))?;
+    if submission.quoted_at.is_none() {
+        return Err(AppError::conflict("Submission has not been quoted yet"));
+    }
+
+    let template = fs::read_to_string(QUOTE_TEMPLATE_PATH)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, path = QUOTE_TEMPLATE_PATH, "Quote template is unavailable");
+            AppError::from(e)
+        })?;
+
+    Ok(Html(render_quote_document(&template, &submission)))
+}
@@ -0,0 +1,153 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::audit::{self, AuditAction};
+use crate::error::AppError;
+use crate::state::AppState;
+use crate::routers::admin::auth::{revoke_admin_session, ClientInfo, CurrentAdmin};
+
+/// Данные сессии, записанные `save_admin_session`/`touch_admin_session`
+#[derive(Debug, Deserialize)]
+struct StoredSession {
+    #[serde(default)]
+    ip: Option<String>,
+    #[serde(default)]
+    user_agent: Option<String>,
+    created_at: i64,
+    last_activity: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AdminSessionInfo {
+    session_id: String,
+    ip: Option<String>,
+    user_agent: Option<String>,
+    created_at: i64,
+    last_activity: i64,
+    current: bool,
+}
+
+// GET /admin/api/v1/sessions
+pub async fn list_own_sessions(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+) -> Result<Json<Vec<AdminSessionInfo>>, AppError> {
+    let sessions = load_admin_sessions(&state, &admin.id.to_string(), Some(&admin.session_id)).await?;
+    Ok(Json(sessions))
+}
+
+// DELETE /admin/api/v1/sessions/{session_id}
+pub async fn revoke_own_session(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    client: ClientInfo,
+    Path(session_id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let mut conn = state.db_redis.get_connection().await?;
+    let owned: bool = conn
+        .sismember(format!("admin_sessions:{}", admin.id), &session_id)
+        .await?;
+    if !owned {
+        return Err(AppError::not_found("Session not found"));
+    }
+
+    revoke_admin_session(&state, &admin.id.to_string(), &session_id).await?;
+    audit::record(&state, &client, &admin.username, AuditAction::SessionRevoke, json!({
+        "session_id": session_id,
+    })).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// POST /admin/api/v1/sessions/revoke-others
+pub async fn revoke_other_sessions(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    client: ClientInfo,
+) -> Result<StatusCode, AppError> {
+    revoke_all_admin_sessions(&state, &admin.id.to_string(), Some(&admin.session_id)).await?;
+    audit::record(&state, &client, &admin.username, AuditAction::SessionRevoke, json!({
+        "scope": "other_sessions",
+    })).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// GET /admin/api/v1/admins/{id}/sessions
+pub async fn list_admin_sessions(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    Path(admin_id): Path<Uuid>,
+) -> Result<Json<Vec<AdminSessionInfo>>, AppError> {
+    let sessions = load_admin_sessions(&state, &admin_id.to_string(), Some(&admin.session_id)).await?;
+    Ok(Json(sessions))
+}
+
+// DELETE /admin/api/v1/admins/{id}/sessions
+pub async fn force_logout_admin(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    client: ClientInfo,
+    Path(admin_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    revoke_all_admin_sessions(&state, &admin_id.to_string(), None).await?;
+    tracing::info!("Admin {} forced logout of admin {}", admin.id, admin_id);
+    audit::record(&state, &client, &admin.username, AuditAction::SessionRevoke, json!({
+        "admin_id": admin_id,
+        "scope": "all_sessions",
+    })).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Отзыв всех сессий администратора, кроме `except`
+pub async fn revoke_all_admin_sessions(
+    state: &AppState,
+    admin_id: &str,
+    except: Option<&str>,
+) -> Result<(), AppError> {
+    let mut conn = state.db_redis.get_connection().await?;
+    let session_ids: Vec<String> = conn.smembers(format!("admin_sessions:{}", admin_id)).await?;
+
+    for session_id in session_ids.iter().filter(|id| Some(id.as_str()) != except) {
+        revoke_admin_session(state, admin_id, session_id).await?;
+    }
+    Ok(())
+}
+
+/// Загрузка сессий из индекса. Истекшие сессии удаляются из индекса по ходу
+async fn load_admin_sessions(
+    state: &AppState,
+    admin_id: &str,
+    current_session_id: Option<&str>,
+) -> Result<Vec<AdminSessionInfo>, AppError> {
+    let mut conn = state.db_redis.get_connection().await?;
+    let index_key = format!("admin_sessions:{}", admin_id);
+    let session_ids: Vec<String> = conn.smembers(&index_key).await?;
+
+    let mut sessions = Vec::with_capacity(session_ids.len());
+    for session_id in session_ids {
+        let data: Option<String> = conn.get(format!("admin_session:{}", session_id)).await?;
+        let Some(data) = data else {
+            let _: () = conn.srem(&index_key, &session_id).await?;
+            continue;
+        };
+
+        let stored: StoredSession = serde_json::from_str(&data)?;
+        sessions.push(AdminSessionInfo {
+            current: current_session_id == Some(session_id.as_str()),
+            session_id,
+            ip: stored.ip,
+            user_agent: stored.user_agent,
+            created_at: stored.created_at,
+            last_activity: stored.last_activity,
+        });
+    }
+
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.last_activity));
+    Ok(sessions)
+}
@@ -0,0 +1,236 @@
+use axum::{
+    extract::State,
+    http::{header::SET_COOKIE, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+use chrono::Utc;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::audit::{self, AuditAction};
+use crate::error::AppError;
+use crate::state::AppState;
+use crate::totp;
+use crate::routers::admin::auth::{complete_login, AdminUser, ClientInfo, CurrentAdmin};
+
+const PENDING_2FA_COOKIE_NAME: &str = "__Secure-admin-2fa";
+const PENDING_2FA_EXPIRY: i64 = 300; // 5 minutes
+const MAX_2FA_ATTEMPTS: i64 = 5;
+const TOTP_SETUP_EXPIRY: u64 = 600; // 10 minutes
+const TOTP_ISSUER: &str = "Expertiza";
+
+#[derive(Debug, Deserialize)]
+pub struct TwoFactorCodeRequest {
+    code: Option<String>,
+    recovery_code: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TwoFactorChallengeResponse {
+    two_factor_required: bool,
+    expires_in: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TotpSetupResponse {
+    secret: String,
+    otpauth_uri: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecoveryCodesResponse {
+    recovery_codes: Vec<String>,
+}
+
+/// Состояние «ожидается второй фактор» после успешной проверки пароля
+pub async fn start_two_factor(state: &AppState, admin: &AdminUser) -> Result<Response, AppError> {
+    let mut conn = state.db_redis.get_connection().await?;
+    let token = Uuid::new_v4().to_string();
+    let pending_key = format!("pending_2fa:{}", token);
+    let _: () = conn
+        .set_ex(&pending_key, serde_json::to_string(admin)?, PENDING_2FA_EXPIRY as u64)
+        .await?;
+
+    let pending_cookie = format!(
+        "{}={}; HttpOnly; Secure; SameSite=Strict; Path=/admin; Max-Age={}",
+        PENDING_2FA_COOKIE_NAME,
+        token,
+        PENDING_2FA_EXPIRY
+    );
+
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert("Set-Cookie", pending_cookie.parse().unwrap());
+    response_headers.insert(
+        "Cache-Control",
+        "no-store, no-cache, must-revalidate, private".parse().unwrap(),
+    );
+
+    Ok((
+        StatusCode::OK,
+        response_headers,
+        Json(TwoFactorChallengeResponse {
+            two_factor_required: true,
+            expires_in: PENDING_2FA_EXPIRY,
+        }),
+    ).into_response())
+}
+
+/// Второй шаг входа: проверка TOTP-кода или кода восстановления.
+/// PATH admin/api/v1/login/2fa
+pub async fn admin_login_2fa_handler(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    client: ClientInfo,
+    Json(payload): Json<TwoFactorCodeRequest>,
+) -> Result<Response, AppError> {
+    let token = jar
+        .get(PENDING_2FA_COOKIE_NAME)
+        .map(|cookie| cookie.value().to_string())
+        .ok_or_else(|| AppError::unauthorized("Two-factor session expired"))?;
+
+    let mut conn = state.db_redis.get_connection().await?;
+    let pending_key = format!("pending_2fa:{}", token);
+    let attempts_key = format!("pending_2fa_attempts:{}", token);
+
+    let pending: Option<String> = conn.get(&pending_key).await?;
+    let admin_user: AdminUser = match pending {
+        Some(data) => serde_json::from_str(&data)?,
+        None => return Err(AppError::unauthorized("Two-factor session expired")),
+    };
+
+    let attempts: i64 = conn.incr(&attempts_key, 1).await?;
+    let _: () = conn.expire(&attempts_key, PENDING_2FA_EXPIRY).await?;
+    if attempts > MAX_2FA_ATTEMPTS {
+        let _: () = conn.del(&[&pending_key, &attempts_key]).await?;
+        audit::record(&state, &client, &admin_user.username, AuditAction::LoginFailure, json!({ "reason": "too_many_two_factor_attempts" })).await;
+        return Err(AppError::too_many_requests("Too many two-factor attempts. Please log in again."));
+    }
+
+    if !verify_second_factor(&state, admin_user.id, &payload, Utc::now().timestamp()).await? {
+        audit::record(&state, &client, &admin_user.username, AuditAction::LoginFailure, json!({ "reason": "invalid_two_factor_code" })).await;
+        return Err(AppError::unauthorized("Invalid two-factor code"));
+    }
+
+    let _: () = conn.del(&[&pending_key, &attempts_key]).await?;
+
+    let mut response = complete_login(&state, &admin_user, &client).await?;
+    let expired_cookie = format!(
+        "{}=; HttpOnly; Secure; SameSite=Strict; Path=/admin; Max-Age=0",
+        PENDING_2FA_COOKIE_NAME
+    );
+    response.headers_mut().append(SET_COOKIE, expired_cookie.parse().unwrap());
+
+    Ok(response)
+}
+
+// POST /admin/api/v1/2fa/setup
+pub async fn setup_totp(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+) -> Result<Json<TotpSetupResponse>, AppError> {
+    let admin_id = admin.id;
+    let account = state.db_postgres
+        .get_admin_account(admin_id)
+        .await?
+        .ok_or_else(|| AppError::unauthorized("Admin not found"))?;
+
+    if account.totp_enabled {
+        return Err(AppError::conflict("Two-factor authentication is already enabled"));
+    }
+
+    // Секрет хранится в Redis до подтверждения первым кодом
+    let secret = totp::generate_secret();
+    let mut conn = state.db_redis.get_connection().await?;
+    let _: () = conn
+        .set_ex(format!("totp_setup:{}", admin_id), &secret, TOTP_SETUP_EXPIRY)
+        .await?;
+
+    Ok(Json(TotpSetupResponse {
+        otpauth_uri: totp::otpauth_uri(TOTP_ISSUER, &account.username, &secret),
+        secret,
+    }))
+}
+
+// POST /admin/api/v1/2fa/confirm
+pub async fn confirm_totp(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    client: ClientInfo,
+    Json(payload): Json<TwoFactorCodeRequest>,
+) -> Result<Json<RecoveryCodesResponse>, AppError> {
+    let admin_id = admin.id;
+    let code = payload.code.as_deref().unwrap_or_default();
+
+    let mut conn = state.db_redis.get_connection().await?;
+    let setup_key = format!("totp_setup:{}", admin_id);
+    let secret: Option<String> = conn.get(&setup_key).await?;
+    let secret = secret.ok_or_else(|| AppError::bad_request("Two-factor setup expired, start again"))?;
+
+    let step = totp::verify_code(&secret, code, Utc::now().timestamp(), None)
+        .ok_or_else(|| AppError::bad_request("Invalid two-factor code"))?;
+
+    let recovery_codes = totp::generate_recovery_codes();
+    let hashes: Vec<String> = recovery_codes.iter().map(|c| totp::hash_recovery_code(c)).collect();
+
+    state.db_postgres.enable_admin_totp(admin_id, &secret, &hashes).await?;
+    // Код подтверждения нельзя повторно использовать для входа
+    state.db_postgres.consume_totp_step(admin_id, step).await?;
+    let _: () = conn.del(&setup_key).await?;
+
+    audit::record(&state, &client, &admin.username, AuditAction::TwoFactorEnable, json!({})).await;
+    Ok(Json(RecoveryCodesResponse { recovery_codes }))
+}
+
+// POST /admin/api/v1/2fa/disable
+pub async fn disable_totp(
+    State(state): State<AppState>,
+    admin: CurrentAdmin,
+    client: ClientInfo,
+    Json(payload): Json<TwoFactorCodeRequest>,
+) -> Result<StatusCode, AppError> {
+    let admin_id = admin.id;
+
+    if !verify_second_factor(&state, admin_id, &payload, Utc::now().timestamp()).await? {
+        return Err(AppError::unauthorized("Invalid two-factor code"));
+    }
+
+    state.db_postgres.disable_admin_totp(admin_id).await?;
+    audit::record(&state, &client, &admin.username, AuditAction::TwoFactorDisable, json!({})).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Проверка TOTP-кода (с защитой от повтора) или одноразового кода восстановления
+/// на момент `now`
+async fn verify_second_factor(
+    state: &AppState,
+    admin_id: Uuid,
+    payload: &TwoFactorCodeRequest,
+    now: i64,
+) -> Result<bool, AppError> {
+    if let Some(recovery_code) = payload.recovery_code.as_deref().filter(|c| !c.trim().is_empty()) {
+        let hash = totp::hash_recovery_code(recovery_code);
+        return Ok(state.db_postgres.consume_recovery_code(admin_id, &hash).await?);
+    }
+
+    let Some(code) = payload.code.as_deref() else {
+        return Ok(false);
+    };
+
+    let (secret, last_step) = match state.db_postgres.get_admin_totp(admin_id).await? {
+        Some(totp) if totp.totp_enabled => (totp.totp_secret, totp.totp_last_step),
+        _ => (None, None),
+    };
+    let Some(secret) = secret else {
+        return Ok(false);
+    };
+
+    // Повтор отсекается и здесь, и атомарно в consume_totp_step
+    match totp::verify_code(&secret, code, now, last_step) {
+        Some(step) => Ok(state.db_postgres.consume_totp_step(admin_id, step).await?),
+        None => Ok(false),
+    }
+}
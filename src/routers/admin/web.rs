@@ -2,6 +2,7 @@ use axum::{
     http::{StatusCode, HeaderMap, HeaderValue},
     response::{Html, IntoResponse},
 };
+use axum_extra::extract::cookie::CookieJar;
 use tokio::fs;
 use uuid::Uuid;
 
@@ -24,10 +25,43 @@ pub async fn admin_login() -> Result<impl IntoResponse, StatusCode>{
     );
     Ok((headers, Html(content)))
 }
+// /admin/change-password
+pub async fn admin_change_password() -> Result<impl IntoResponse, StatusCode> {
+    let content = fs::read_to_string("templates/admin/admin_change_password.html")
+        .await
+        .map_err(|_| StatusCode::NOT_FOUND)?;
+
+    let session_id = Uuid::new_v4().to_string();
+    let cookie = format!(
+        "session_id={}; Path=/; HttpOnly; Secure; SameSite=Strict",
+        session_id
+    );
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        axum::http::header::SET_COOKIE,
+        HeaderValue::from_str(&cookie).unwrap()
+    );
+    Ok((headers, Html(content)))
+}
+
 // /admin/dashboard
-pub async fn admin_dashboard() -> Result<Html<String>, StatusCode> {
-    match fs::read_to_string("templates/admin/admin_dashboard.html").await {
-        Ok(content) => Ok(Html(content)),
-        Err(_) => Err(StatusCode::NOT_FOUND),
+pub async fn admin_dashboard(jar: CookieJar) -> Result<impl IntoResponse, StatusCode> {
+    let content = fs::read_to_string("templates/admin/admin_dashboard.html")
+        .await
+        .map_err(|_| StatusCode::NOT_FOUND)?;
+
+    // session_id нужен для CSRF-токенов запросов панели
+    let mut headers = HeaderMap::new();
+    if jar.get("session_id").is_none() {
+        let cookie = format!(
+            "session_id={}; Path=/; HttpOnly; Secure; SameSite=Strict",
+            Uuid::new_v4()
+        );
+        headers.insert(
+            axum::http::header::SET_COOKIE,
+            HeaderValue::from_str(&cookie).unwrap()
+        );
     }
+    Ok((headers, Html(content)))
 }
\ No newline at end of file
@@ -1,16 +1,27 @@
 use axum::{
-    http::StatusCode,
-    response::{Json, IntoResponse},
+    http::{header::CONTENT_TYPE, StatusCode},
+    response::{Json, IntoResponse, Response},
     extract::Json as ExtractJson,
-    extract::State
+    extract::{FromRequest, Multipart, Request, State},
+    Extension,
 };
 use axum_extra::{
     extract::cookie::{CookieJar},
 };
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use crate::csrf::{CsrfService, CsrfTokenResponse};
+use std::collections::HashMap;
+use crate::csrf::{CsrfIssuedAt, CsrfService, CsrfTokenResponse, CSRF_TOKEN_TTL};
+use crate::pow::{PowChallengeResponse, PowService, POW_CHALLENGE_TTL, POW_DIFFICULTY};
+use crate::spam::SpamSignals;
 use crate::state::AppState;
-use crate::database::postgres::models::CreateSubmissionRequest;
+use crate::database::postgres::models::{CreateSubmissionRequest, SpamAssessment};
+use crate::error::AppError;
+use crate::routers::admin::assignment::auto_assign_new_submission;
+use crate::outbox::submission_notifications;
+use crate::events;
+use crate::validation::{validate_contact, ValidatedContact};
+use crate::uploads::{self, UploadedFile};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ContactSubmission {
@@ -18,12 +29,41 @@ pub struct ContactSubmission {
     email: String,
     phone: Option<String>,
     message: String,
+    // Антиспам: скрытое поле и решение proof-of-work
+    #[serde(default)]
+    honeypot: Option<String>,
+    #[serde(default)]
+    pow_challenge: Option<String>,
+    #[serde(default)]
+    pow_nonce: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
-pub struct ErrorResponse {
-    message: String,
-    code: String,
+/// Форма обратной связи: JSON или multipart/form-data с файлами в поле `files`
+pub enum ContactForm {
+    Json(ContactSubmission),
+    Multipart(Multipart),
+}
+
+impl FromRequest<AppState> for ContactForm {
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &AppState) -> Result<Self, Self::Rejection> {
+        let is_multipart = req
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("multipart/form-data"));
+
+        if is_multipart {
+            let multipart = Multipart::from_request(req, state).await.map_err(IntoResponse::into_response)?;
+            Ok(ContactForm::Multipart(multipart))
+        } else {
+            let ExtractJson(data) = ExtractJson::<ContactSubmission>::from_request(req, state)
+                .await
+                .map_err(IntoResponse::into_response)?;
+            Ok(ContactForm::Json(data))
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -37,95 +77,192 @@ pub async fn get_csrf_token(
     State(state): State<AppState>,
     jar: CookieJar
 ) -> impl IntoResponse {
-    let redis_conn: redis::aio::ConnectionManager = state
-        .db_redis
-        .get_connection()
-        .await
-        .expect("Unable to connect to Redis");
-
-    let session_id = match jar
-        .get("session_id")
-        .and_then(|v| Some(v))
-    {
+    let redis_conn = match state.db_redis.get_connection().await {
+        Ok(conn) => conn,
+        Err(e) => return AppError::from(e).into_response(),
+    };
+
+    let session_id = match jar.get("session_id") {
         Some(id) => id.value().to_string(),
-        None => {
-            return (
-                StatusCode::UNAUTHORIZED,
-                Json(ErrorResponse {
-                    message: "Missing or invalid session_id".to_string(),
-                    code: "MISSING_SESSION".to_string(),
-                }),
-            ).into_response();
-        }
+        None => return AppError::unauthorized("Missing or invalid session_id").into_response(),
     };
 
-    let mut csrf_svc = CsrfService::new(redis_conn, 900);
+    let mut csrf_svc = CsrfService::new(redis_conn, CSRF_TOKEN_TTL);
     match csrf_svc.create_token(session_id).await {
         Ok(token) => Json(CsrfTokenResponse {
             token,
-            expires_in: 900, // 15 min
+            expires_in: CSRF_TOKEN_TTL,
         }).into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                message: "Failed to generate CSRF token".to_string(),
-                code: "INTERNAL_ERROR".to_string(),
-            }),
-        ).into_response()
+        Err(e) => AppError::from(e).into_response(),
     }
 }
 
-// /api/v1/contact-submissions
-pub async fn accept_form(
+// /api/v1/pow-challenge
+pub async fn get_pow_challenge(
     State(state): State<AppState>,
-    ExtractJson(data): ExtractJson<ContactSubmission>,
 ) -> impl IntoResponse {
-    if data.name.len() < 2 || data.name.len() > 255 {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                message: "Имя должно содержать 2-255 символов".to_string(),
-                code: "VALIDATION_ERROR".to_string(),
-            }),
-        ).into_response();
-    }
+    let redis_conn = match state.db_redis.get_connection().await {
+        Ok(conn) => conn,
+        Err(e) => return AppError::from(e).into_response(),
+    };
 
-    // Валидация email
-    if !data.email.contains('@') || data.email.len() > 254 {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                message: "Некорректный email адрес".to_string(),
-                code: "VALIDATION_ERROR".to_string(),
-            }),
-        ).into_response();
+    match PowService::new(redis_conn).create_challenge().await {
+        Ok(challenge) => Json(PowChallengeResponse {
+            challenge,
+            difficulty: POW_DIFFICULTY,
+            expires_in: POW_CHALLENGE_TTL,
+        }).into_response(),
+        Err(e) => AppError::from(e).into_response(),
     }
+}
 
-    // Валидация сообщения
-    if data.message.len() < 10 || data.message.len() > 1000 {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                message: "Сообщение должно содержать 10-1000 символов".to_string(),
-                code: "VALIDATION_ERROR".to_string(),
-            }),
-        ).into_response();
+// /api/v1/contact-submissions
+pub async fn accept_form(
+    State(state): State<AppState>,
+    issued_at: Option<Extension<CsrfIssuedAt>>,
+    form: ContactForm,
+) -> Result<impl IntoResponse, AppError> {
+    // Время выдачи токена сохранил csrf_middleware, сам токен уже удален
+    let issued_at = issued_at.map(|Extension(CsrfIssuedAt(issued_at))| issued_at);
+    let (contact, spam, files) = match form {
+        ContactForm::Json(data) => {
+            let (contact, spam) = check_submission(&state, issued_at, &data).await?;
+            (contact, spam, Vec::new())
+        }
+        ContactForm::Multipart(multipart) => read_multipart_form(&state, issued_at, multipart).await?,
+    };
+    if spam.is_spam {
+        tracing::warn!(score = spam.score, reasons = ?spam.reasons, "Contact submission flagged as spam");
     }
+    // Файлы спама не читаются и не сохраняются. Остальные записываются до
+    // заявки: при ошибке сохранения в хранилище остается только содержимое,
+    // которое переиспользуется по хешу
+    let attachments = if spam.is_spam {
+        Vec::new()
+    } else {
+        uploads::store(&state, &files).await?
+    };
 
+    // Ответ не отличается от обычного, чтобы не подсказывать ботам
     let submission_id = uuid::Uuid::new_v4();
+    let is_spam = spam.is_spam;
     let submission = CreateSubmissionRequest {
-        submission_id: submission_id.clone(),
-        name: data.name,
-        email: data.email,
-        phone: data.phone,
-        message: data.message,
+        submission_id,
+        name: contact.name,
+        email: contact.email,
+        phone: contact.phone,
+        message: contact.message,
+        spam,
+    };
+    // О спаме не уведомляем ни дежурных, ни отправителя
+    let notifications = if is_spam {
+        Vec::new()
+    } else {
+        submission_notifications(&state, &submission, true)
     };
-    state.db_postgres.save_submission(submission).await.unwrap();
-    (
+    state.db_postgres.save_submission(submission, &notifications, &attachments).await?;
+    // Спам экспертам не распределяется
+    if !is_spam {
+        auto_assign_new_submission(&state, submission_id).await;
+        events::publish_submission_created(&state, submission_id).await;
+    }
+    Ok((
         StatusCode::OK,
         Json(SuccessResponse {
             message: "Сообщение успешно отправлено".to_string(),
             submission_id: submission_id.to_string(),
         }),
-    ).into_response()
-}
\ No newline at end of file
+    ))
+}
+
+/// Проверка полей формы и оценка спама
+async fn check_submission(
+    state: &AppState,
+    issued_at: Option<i64>,
+    data: &ContactSubmission,
+) -> Result<(ValidatedContact, SpamAssessment), AppError> {
+    let contact = validate_contact(&data.name, &data.email, data.phone.as_deref(), &data.message)?;
+    let spam = assess_spam(state, issued_at, data, &contact).await?;
+    Ok((contact, spam))
+}
+
+/// Multipart-форма. Текстовые поля проверяются до чтения первого файла,
+/// поэтому должны идти в форме раньше файлов: файлы невалидной заявки
+/// не читаются вовсе, а файлы спама пропускаются без буферизации
+async fn read_multipart_form(
+    state: &AppState,
+    issued_at: Option<i64>,
+    mut multipart: Multipart,
+) -> Result<(ValidatedContact, SpamAssessment, Vec<UploadedFile>), AppError> {
+    let mut fields = HashMap::new();
+    let mut checked = None;
+    let mut files = Vec::new();
+
+    while let Some(field) = multipart.next_field().await.map_err(uploads::multipart_error)? {
+        if !uploads::is_file_field(&field) {
+            if checked.is_some() {
+                return Err(AppError::bad_request("Form fields must precede files"));
+            }
+            let name = field.name().unwrap_or_default().to_string();
+            fields.insert(name, uploads::read_text(field).await?);
+            continue;
+        }
+
+        if checked.is_none() {
+            let data = contact_submission(&mut fields);
+            checked = Some(check_submission(state, issued_at, &data).await?);
+        }
+        if checked.as_ref().is_some_and(|(_, spam)| spam.is_spam) {
+            continue;
+        }
+        uploads::read_file_into(&mut files, field, state.upload_limits).await?;
+    }
+
+    let (contact, spam) = match checked {
+        Some(checked) => checked,
+        None => check_submission(state, issued_at, &contact_submission(&mut fields)).await?,
+    };
+    Ok((contact, spam, files))
+}
+
+fn contact_submission(fields: &mut HashMap<String, String>) -> ContactSubmission {
+    let mut field = |name: &str| fields.remove(name).filter(|value| !value.is_empty());
+    ContactSubmission {
+        name: field("name").unwrap_or_default(),
+        email: field("email").unwrap_or_default(),
+        phone: field("phone"),
+        message: field("message").unwrap_or_default(),
+        honeypot: field("honeypot"),
+        pow_challenge: field("pow_challenge"),
+        pow_nonce: field("pow_nonce"),
+    }
+}
+
+/// Сбор антиспам-сигналов и оценка заявки
+async fn assess_spam(
+    state: &AppState,
+    issued_at: Option<i64>,
+    data: &ContactSubmission,
+    contact: &ValidatedContact,
+) -> Result<SpamAssessment, AppError> {
+    let redis_conn = state.db_redis.get_connection().await?;
+
+    let pow_solved = match (data.pow_challenge.as_deref(), data.pow_nonce.as_deref()) {
+        (Some(challenge), Some(nonce)) => PowService::new(redis_conn).verify_solution(challenge, nonce).await?,
+        _ => false,
+    };
+
+    let (duplicates, recent_from_email) = state.db_postgres
+        .count_recent_submissions(&contact.email, &contact.message)
+        .await?;
+
+    Ok(state.spam_filter.evaluate(&SpamSignals {
+        name: &contact.name,
+        message: &contact.message,
+        honeypot: data.honeypot.as_deref(),
+        fill_seconds: issued_at.map(|issued_at| Utc::now().timestamp() - issued_at),
+        pow_solved,
+        duplicates,
+        recent_from_email,
+    }))
+}
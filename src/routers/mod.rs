@@ -1,3 +1,4 @@
 pub mod error;
 pub mod client;
-pub mod admin;
\ No newline at end of file
+pub mod admin;
+pub mod telegram;
@@ -0,0 +1,222 @@
+use axum::{
+    extract::{Json as ExtractJson, State},
+    http::{HeaderMap, StatusCode},
+};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::audit::{self, AuditAction};
+use crate::database::postgres::models::AdminAccount;
+use crate::error::AppError;
+use crate::events::{self, PanelEvent};
+use crate::notifications;
+use crate::roles::Permission;
+use crate::routers::admin::auth::ClientInfo;
+use crate::state::AppState;
+use crate::telegram::{TelegramAction, TelegramBot};
+use crate::totp::constant_time_eq;
+use crate::workflow::SubmissionStatus;
+
+const SECRET_TOKEN_HEADER: &str = "x-telegram-bot-api-secret-token";
+/// Причина смены статуса в истории заявки
+const STATUS_REASON: &str = "Telegram";
+
+#[derive(Debug, Deserialize)]
+pub struct TelegramUpdate {
+    callback_query: Option<CallbackQuery>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CallbackQuery {
+    id: String,
+    from: TelegramUser,
+    data: Option<String>,
+    message: Option<CallbackMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TelegramUser {
+    id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct CallbackMessage {
+    message_id: i64,
+    chat: TelegramChat,
+}
+
+#[derive(Debug, Deserialize)]
+struct TelegramChat {
+    id: i64,
+}
+
+/// Итог нажатия кнопки: текст для всплывающего ответа и нужно ли
+/// убрать кнопки под сообщением
+struct ActionOutcome {
+    answer: &'static str,
+    close: bool,
+}
+
+impl ActionOutcome {
+    fn answer(answer: &'static str) -> Self {
+        Self { answer, close: false }
+    }
+}
+
+// POST /telegram/webhook
+// Нажатия кнопок под сообщениями о заявках. Telegram повторяет запрос,
+// пока не получит 200, поэтому обработанные обновления всегда подтверждаются
+pub async fn telegram_webhook(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    headers: HeaderMap,
+    ExtractJson(update): ExtractJson<TelegramUpdate>,
+) -> Result<StatusCode, AppError> {
+    let bot = state.telegram.clone().ok_or_else(|| AppError::not_found("Telegram bot is disabled"))?;
+
+    let secret = headers
+        .get(SECRET_TOKEN_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    if !constant_time_eq(secret.as_bytes(), bot.webhook_secret.as_bytes()) {
+        return Err(AppError::unauthorized("Invalid webhook secret"));
+    }
+
+    let Some(query) = update.callback_query else {
+        return Ok(StatusCode::OK);
+    };
+
+    let outcome = match query.data.as_deref().and_then(TelegramAction::parse_callback_data) {
+        Some((action, submission_id)) => {
+            handle_action(&state, &client, query.from.id, action, submission_id)
+                .await
+                .unwrap_or_else(|e| {
+                    warn!(action = %action, submission_id = %submission_id, error = %e, "Telegram action failed");
+                    ActionOutcome::answer("Не удалось выполнить действие")
+                })
+        }
+        None => ActionOutcome::answer("Неизвестное действие"),
+    };
+
+    answer(&bot, &query.id, query.message.as_ref(), outcome).await;
+    Ok(StatusCode::OK)
+}
+
+async fn handle_action(
+    state: &AppState,
+    client: &ClientInfo,
+    telegram_user_id: i64,
+    action: TelegramAction,
+    submission_id: Uuid,
+) -> Result<ActionOutcome, AppError> {
+    let Some(admin) = state.db_postgres
+        .get_admin_by_telegram_id(telegram_user_id)
+        .await?
+        .filter(|admin| admin.is_active)
+    else {
+        return Ok(ActionOutcome::answer("Аккаунт Telegram не привязан к панели"));
+    };
+    if !admin.role.can(Permission::ChangeStatus)
+        || (action == TelegramAction::Take && !admin.role.can(Permission::AssignSubmissions))
+    {
+        return Ok(ActionOutcome::answer("Недостаточно прав"));
+    }
+
+    let Some(submission) = state.db_postgres.get_submission(submission_id).await? else {
+        return Ok(ActionOutcome { answer: "Заявка не найдена", close: true });
+    };
+
+    match action {
+        TelegramAction::Take => {
+            // Уже взятая в работу заявка просто переназначается, из остальных
+            // статусов переход проверяется до назначения
+            let start_work = submission.status != SubmissionStatus::InProgress;
+            if start_work && !submission.status.can_transition_to(SubmissionStatus::InProgress, admin.role) {
+                return Ok(ActionOutcome::answer("Заявку нельзя взять в работу в текущем статусе"));
+            }
+
+            if !state.db_postgres.assign_submission(submission_id, Some(admin.id)).await? {
+                return Ok(ActionOutcome { answer: "Заявка не найдена", close: true });
+            }
+            info!(actor = %admin.username, submission_id = %submission_id, "Submission taken via Telegram");
+            audit::record(state, client, &admin.username, AuditAction::AssignmentChange, json!({
+                "submission_id": submission_id,
+                "assignee_id": admin.id,
+                "source": "telegram",
+            })).await;
+            events::publish(state, PanelEvent::AssignmentChanged {
+                submission_id,
+                assignee_id: Some(admin.id),
+                assignee_name: Some(admin.username.clone()),
+            }).await;
+
+            if start_work {
+                change_status(state, client, &admin, submission_id, Some(admin.id), submission.status, SubmissionStatus::InProgress).await?;
+            }
+            Ok(ActionOutcome { answer: "Заявка назначена на вас", close: true })
+        }
+        TelegramAction::Reject => {
+            if submission.status == SubmissionStatus::Rejected {
+                return Ok(ActionOutcome { answer: "Заявка уже отклонена", close: true });
+            }
+            Ok(match change_status(state, client, &admin, submission_id, submission.admin_id, submission.status, SubmissionStatus::Rejected).await? {
+                true => ActionOutcome { answer: "Заявка отклонена", close: true },
+                false => ActionOutcome::answer("Заявку нельзя отклонить в текущем статусе"),
+            })
+        }
+        TelegramAction::MarkViewed => {
+            // Просмотренной можно отметить только новую заявку
+            if submission.status != SubmissionStatus::New {
+                return Ok(ActionOutcome::answer("Заявка уже просмотрена"));
+            }
+            change_status(state, client, &admin, submission_id, submission.admin_id, submission.status, SubmissionStatus::Viewed).await?;
+            Ok(ActionOutcome::answer("Заявка отмечена как просмотренная"))
+        }
+    }
+}
+
+/// Смена статуса по правилам панели. `false`, если переход не разрешен
+async fn change_status(
+    state: &AppState,
+    client: &ClientInfo,
+    admin: &AdminAccount,
+    submission_id: Uuid,
+    assignee_id: Option<Uuid>,
+    from: SubmissionStatus,
+    to: SubmissionStatus,
+) -> Result<bool, AppError> {
+    if !from.can_transition_to(to, admin.role) {
+        return Ok(false);
+    }
+
+    let changed = state.db_postgres
+        .change_submission_status(submission_id, from, to, admin.id, Some(STATUS_REASON))
+        .await?;
+    if !changed {
+        return Err(AppError::conflict("Submission status was changed concurrently"));
+    }
+
+    info!(actor = %admin.username, submission_id = %submission_id, from = %from, to = %to, "Submission status changed via Telegram");
+    audit::record(state, client, &admin.username, AuditAction::StatusChange, json!({
+        "submission_id": submission_id,
+        "from": from,
+        "to": to,
+        "reason": STATUS_REASON,
+    })).await;
+    notifications::status_change(state, submission_id, assignee_id, from, to, (admin.id, &admin.username)).await;
+    events::publish(state, PanelEvent::StatusChanged { submission_id, from, to }).await;
+    Ok(true)
+}
+
+async fn answer(bot: &TelegramBot, query_id: &str, message: Option<&CallbackMessage>, outcome: ActionOutcome) {
+    if let Err(e) = bot.client.answer_callback(query_id, outcome.answer).await {
+        warn!(error = %e, "Failed to answer Telegram callback");
+    }
+    if outcome.close && let Some(message) = message
+        && let Err(e) = bot.client.remove_keyboard(message.chat.id, message.message_id).await
+    {
+        warn!(error = %e, "Failed to remove Telegram keyboard");
+    }
+}
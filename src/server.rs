@@ -3,7 +3,8 @@ use axum::{
     http::{Method, Request},
     Router,
     body::Body,
-    routing::{get, post, put},
+    extract::DefaultBodyLimit,
+    routing::{delete, get, post, put},
 };
 use axum::middleware::{from_fn_with_state, from_fn};
 use tower::ServiceBuilder;
@@ -14,32 +15,108 @@ use tower_http::{
     trace::TraceLayer,
     services::ServeDir,
 };
-use tracing::info;
+use tracing::{info, warn};
 use std::sync::Arc;
-use crate::config::Config;
+use crate::config::{Config, UploadLimits};
 use crate::logging::{log_request, log_response, log_failure};
 use crate::routers::error::handle_404;
+use crate::routers::telegram::telegram_webhook;
 use crate::routers::client::{
     web::serve_index,
-    api::{get_csrf_token, accept_form},
+    api::{get_csrf_token, get_pow_challenge, accept_form},
 };
 use crate::routers::admin::{
-    web::{admin_dashboard, admin_login},
+    web::{admin_dashboard, admin_login, admin_change_password},
     auth::{
         admin_login_handler, 
+        admin_change_password_handler,
+        admin_refresh_handler,
         admin_logout_handler, 
-        admin_auth_middleware},
+        admin_auth_middleware,
+        require_permission},
     api::{
         create_contact_submission, 
         post_admin_dashboard, 
+        list_submissions,
+        get_submission_details,
+        get_submission_status_history,
         get_admin_statistics, 
         update_admin_status,
-        get_submission_comments,
-        crate_submission_comment
+    },
+    attachments::{
+        list_submission_attachments,
+        upload_submission_attachments,
+        download_attachment,
+    },
+    comments::{
+        list_submission_comments,
+        create_submission_comment,
+        update_comment,
+        delete_comment,
+        get_comment_edits,
+    },
+    admins::{
+        list_admins,
+        create_admin,
+        update_admin_active,
+        update_admin_role,
+        reset_admin_password,
+        update_admin_mail,
+        update_admin_telegram,
+        delete_admin,
+    },
+    assignment::{
+        update_submission_assignee,
+        remove_submission_assignee,
+        update_submission_due_date,
+    },
+    audit::get_audit_log,
+    events::panel_events,
+    notifications::{
+        list_notifications,
+        update_notification,
+        mark_all_notifications_read,
+        get_notification_preferences,
+        update_notification_preferences,
+    },
+    quotes::{
+        update_submission_quote,
+        get_submission_quote_history,
+        get_submission_quote_document,
+    },
+    two_factor::{
+        admin_login_2fa_handler,
+        setup_totp,
+        confirm_totp,
+        disable_totp,
+    },
+    sessions::{
+        list_own_sessions,
+        revoke_own_session,
+        revoke_other_sessions,
+        list_admin_sessions,
+        force_logout_admin,
     },
 };
-use crate::middleware::{security_headers_middleware, rate_limit_middleware};
+use crate::middleware::{
+    security_headers_middleware,
+    rate_limit_middleware,
+    client_ip_middleware,
+    request_id_middleware,
+    error_envelope_middleware,
+    RequestId,
+    REQUEST_ID_HEADER,
+};
 use crate::state::AppState;
+use crate::csrf::csrf_middleware;
+use crate::spam::SpamFilter;
+use crate::mail::{Mailer, MailTemplates};
+use crate::outbox::{spawn_outbox_worker, OutboxSenders};
+use crate::telegram::{TelegramBot, TelegramClient};
+use crate::events::{spawn_event_listener, EventHub};
+use crate::notifications::spawn_deadline_worker;
+use crate::uploads::spawn_cleanup_worker;
+use crate::roles::Permission;
 use crate::database::setup::{setup_redis, setup_postgres};
 
 
@@ -52,6 +129,7 @@ fn setup_cors() -> CorsLayer {
             axum::http::header::CONTENT_TYPE,
             axum::http::HeaderName::from_static("x-csrf-token"),
         ])
+        .expose_headers([REQUEST_ID_HEADER])
         .allow_origin(Any)
 }
 
@@ -60,22 +138,93 @@ fn setup_static_service() -> ServeDir {
         .append_index_html_on_directories(false)
 }
 
-fn setup_routes_admin() -> Router<AppState> {
+fn setup_routes_admin(upload_limits: UploadLimits) -> Router<AppState> {
+    let view = from_fn_with_state(Permission::ViewSubmissions, require_permission);
+    let create = from_fn_with_state(Permission::CreateSubmissions, require_permission);
+    let change_status = from_fn_with_state(Permission::ChangeStatus, require_permission);
+    let comment = from_fn_with_state(Permission::Comment, require_permission);
+    let assign = from_fn_with_state(Permission::AssignSubmissions, require_permission);
+    let pricing = from_fn_with_state(Permission::ManagePricing, require_permission);
+    let manage_admins = from_fn_with_state(Permission::ManageAdmins, require_permission);
+    let view_audit_log = from_fn_with_state(Permission::ViewAuditLog, require_permission);
+
     Router::new()
-        .route("/logout", get(admin_logout_handler))
+        .route("/logout", post(admin_logout_handler))
         .route("/dashboard", get(admin_dashboard))
-        .route("/api/v1/update-submission-status", put(update_admin_status))
-        .route("/api/v1/add-submissions", post(create_contact_submission))
-        .route("/api/v1/dashboard-page", post(post_admin_dashboard))
-        .route("/api/v1/dashboard-stats", get(get_admin_statistics))
-        .route("/api/v1/get-submissions-comment", post(get_submission_comments))
-        .route("/api/v1/create-submissions-comment", post(crate_submission_comment))
+        .route("/api/v1/update-submission-status", put(update_admin_status).route_layer(change_status))
+        .route("/api/v1/add-submissions", post(create_contact_submission).route_layer(create))
+        .route("/api/v1/dashboard-page", post(post_admin_dashboard).route_layer(view.clone()))
+        .route("/api/v1/submissions", get(list_submissions).route_layer(view.clone()))
+        .route("/api/v1/submissions/{id}", get(get_submission_details).route_layer(view.clone()))
+        .route("/api/v1/submissions/{id}/status-history", get(get_submission_status_history).route_layer(view.clone()))
+        .route("/api/v1/dashboard-stats", get(get_admin_statistics).route_layer(view.clone()))
+        .route("/api/v1/events", get(panel_events).route_layer(view.clone()))
+        .route(
+            "/api/v1/submissions/{id}/comments",
+            get(list_submission_comments).route_layer(view.clone())
+                .merge(post(create_submission_comment).route_layer(comment.clone())),
+        )
+        .route(
+            "/api/v1/submissions/{id}/attachments",
+            get(list_submission_attachments).route_layer(view.clone())
+                .merge(
+                    post(upload_submission_attachments)
+                        .route_layer(comment.clone())
+                        .layer(DefaultBodyLimit::max(upload_limits.max_request_bytes())),
+                ),
+        )
+        .route("/api/v1/attachments/{id}", get(download_attachment).route_layer(view.clone()))
+        .route("/api/v1/comments/{id}/edits", get(get_comment_edits).route_layer(view.clone()))
+        .route("/api/v1/submissions/{id}/quote/history", get(get_submission_quote_history).route_layer(view.clone()))
+        .route("/api/v1/submissions/{id}/quote/document", get(get_submission_quote_document).route_layer(view))
+        .route("/api/v1/comments/{id}", put(update_comment).delete(delete_comment).route_layer(comment))
+        .route(
+            "/api/v1/submissions/{id}/assignee",
+            put(update_submission_assignee).delete(remove_submission_assignee).route_layer(assign.clone()),
+        )
+        .route("/api/v1/submissions/{id}/due-date", put(update_submission_due_date).route_layer(assign))
+        .route("/api/v1/submissions/{id}/quote", put(update_submission_quote).route_layer(pricing))
+        .route("/api/v1/2fa/setup", post(setup_totp))
+        .route("/api/v1/2fa/confirm", post(confirm_totp))
+        .route("/api/v1/2fa/disable", post(disable_totp))
+        .route("/api/v1/admins", get(list_admins).post(create_admin).route_layer(manage_admins.clone()))
+        .route("/api/v1/admins/{id}", delete(delete_admin).route_layer(manage_admins.clone()))
+        .route("/api/v1/admins/{id}/active", put(update_admin_active).route_layer(manage_admins.clone()))
+        .route("/api/v1/admins/{id}/role", put(update_admin_role).route_layer(manage_admins.clone()))
+        .route("/api/v1/admins/{id}/password", put(reset_admin_password).route_layer(manage_admins.clone()))
+        .route("/api/v1/admins/{id}/mail", put(update_admin_mail).route_layer(manage_admins.clone()))
+        .route("/api/v1/admins/{id}/telegram", put(update_admin_telegram).route_layer(manage_admins.clone()))
+        .route("/api/v1/admins/{id}/sessions", get(list_admin_sessions).delete(force_logout_admin).route_layer(manage_admins))
+        .route("/api/v1/audit-log", get(get_audit_log).route_layer(view_audit_log))
+        .route("/api/v1/sessions", get(list_own_sessions))
+        .route("/api/v1/sessions/revoke-others", post(revoke_other_sessions))
+        .route("/api/v1/sessions/{session_id}", delete(revoke_own_session))
+        .route("/api/v1/notifications", get(list_notifications))
+        .route("/api/v1/notifications/read-all", post(mark_all_notifications_read))
+        .route(
+            "/api/v1/notifications/preferences",
+            get(get_notification_preferences).put(update_notification_preferences),
+        )
+        .route("/api/v1/notifications/{id}", put(update_notification))
 }
 
-fn setup_routes_client() -> Router<AppState> {
+/// Вход в админку: доступен без сессии
+fn setup_routes_admin_auth() -> Router<AppState> {
+    Router::new()
+        .route("/admin/api/v1/login", post(admin_login_handler))
+        .route("/admin/api/v1/change-password", post(admin_change_password_handler))
+        .route("/admin/api/v1/login/2fa", post(admin_login_2fa_handler))
+        .route("/admin/api/v1/refresh", post(admin_refresh_handler))
+}
+
+fn setup_routes_client(upload_limits: UploadLimits) -> Router<AppState> {
     Router::new()
         .route("/csrf-token", get(get_csrf_token))
-        .route("/contact-submissions", post(accept_form))
+        .route("/pow-challenge", get(get_pow_challenge))
+        .route(
+            "/contact-submissions",
+            post(accept_form).layer(DefaultBodyLimit::max(upload_limits.max_request_bytes())),
+        )
 }
 
 pub async fn setup_app_state(config: &Config) -> Result<AppState, Box<dyn std::error::Error>> {
@@ -87,11 +236,72 @@ pub async fn setup_app_state(config: &Config) -> Result<AppState, Box<dyn std::e
     info!("Running database migrations");
     db_postgres.migrate(&config.admin_login, &config.admin_password).await?;
     info!("Database migrations completed");
+
+    let db_postgres = Arc::new(db_postgres);
+    let mut senders = OutboxSenders { mailer: None, telegram: None };
+    let mail_templates = match &config.mail {
+        Some(mail) => {
+            let templates = MailTemplates::load(&mail.templates_dir).await?;
+            senders.mailer = Some(Mailer::new(mail)?);
+            info!(host = %mail.host, port = mail.port, "Email notifications enabled");
+            Some(Arc::new(templates))
+        }
+        None => {
+            info!("SMTP_HOST is not set, email notifications disabled");
+            None
+        }
+    };
+    let telegram = match &config.telegram {
+        Some(telegram) => {
+            let client = TelegramClient::new(telegram)?;
+            if let Some(url) = &telegram.webhook_url {
+                // Бот работает и без вебхука, кнопки просто не будут обрабатываться
+                match client.set_webhook(url, &telegram.webhook_secret).await {
+                    Ok(()) => info!(url = %url, "Telegram webhook registered"),
+                    Err(e) => warn!(error = %e, "Failed to register Telegram webhook"),
+                }
+            }
+            senders.telegram = Some(client.clone());
+            info!(chat_id = telegram.chat_id, "Telegram notifications enabled");
+            Some(Arc::new(TelegramBot {
+                client,
+                chat_id: telegram.chat_id,
+                webhook_secret: telegram.webhook_secret.clone(),
+            }))
+        }
+        None => {
+            info!("TELEGRAM_BOT_TOKEN is not set, Telegram notifications disabled");
+            None
+        }
+    };
+    spawn_outbox_worker(db_postgres.clone(), senders);
+    spawn_deadline_worker(db_postgres.clone(), mail_templates.clone());
+
+    let db_redis = Arc::new(db_redis);
+    let events = Arc::new(EventHub::default());
+    spawn_event_listener(db_redis.clone(), events.clone());
+
+    let storage = crate::storage::from_config(&config.storage).await?;
+    info!(
+        backend = storage.name(),
+        max_file_mb = config.upload_limits.max_file_bytes / (1024 * 1024),
+        max_files = config.upload_limits.max_files,
+        "Attachment storage configured"
+    );
+    spawn_cleanup_worker(db_postgres.clone(), storage.clone());
     
     let shared_state = AppState { 
-        db_postgres: Arc::new(db_postgres),
-        db_redis: Arc::new(db_redis),
-        jwt_secret
+        db_postgres,
+        db_redis,
+        jwt_secret,
+        trusted_proxies: config.trusted_proxies.clone().into(),
+        spam_filter: Arc::new(SpamFilter::default()),
+        auto_assign: config.auto_assign,
+        mail_templates,
+        telegram,
+        events,
+        storage,
+        upload_limits: config.upload_limits,
     };
     
     Ok(shared_state)
@@ -105,25 +315,44 @@ pub async fn create_app(config: Config) -> Result<Router<>, Box<dyn std::error::
     let static_service_admin = setup_static_service();
     let routes = Router::new();
 
-    let admin_routes = setup_routes_admin();
-    let client_routes = setup_routes_client();
+    let admin_routes = setup_routes_admin(config.upload_limits);
+    let admin_auth_routes = setup_routes_admin_auth();
+    let client_routes = setup_routes_client(config.upload_limits);
+
+    // CSRF проверяется для всех изменяющих запросов API
+    let csrf = from_fn_with_state(shared_state.clone(), csrf_middleware);
 
     let app = routes
         .route("/", get(serve_index))
-        .route("/admin/api/v1/login", post(admin_login_handler))
         .route("/admin/login", get(admin_login))
+        .route("/admin/change-password", get(admin_change_password))
+        // Вебхук проверяет секрет Telegram вместо CSRF и сессии
+        .route("/telegram/webhook", post(telegram_webhook))
+        .merge(admin_auth_routes.route_layer(csrf.clone()))
         .nest_service("/static", static_service)
         .nest_service("/admin/static", static_service_admin)
-        .nest("/admin", admin_routes.route_layer(from_fn_with_state(shared_state.clone(), admin_auth_middleware)))
-        .nest("/api/v1", client_routes)
+        .nest(
+            "/admin",
+            admin_routes
+                .route_layer(csrf.clone())
+                .route_layer(from_fn_with_state(shared_state.clone(), admin_auth_middleware)),
+        )
+        .nest("/api/v1", client_routes.route_layer(csrf))
         .fallback(handle_404)
         .layer(
             ServiceBuilder::new()
+                .layer(from_fn(request_id_middleware))
+                .layer(from_fn_with_state(shared_state.clone(), client_ip_middleware))
                 .layer(
                     TraceLayer::new_for_http()
                         .make_span_with(|req: &Request<Body>| {
+                            let request_id = req
+                                .extensions()
+                                .get::<RequestId>()
+                                .map_or("unknown", |id| id.0.as_str());
                             tracing::info_span!(
                                 "http_request",
+                                request_id = %request_id,
                                 http.method = %req.method(),
                                 http.url = %req.uri(),
                                 http.version = ?req.version(),
@@ -139,11 +368,13 @@ pub async fn create_app(config: Config) -> Result<Router<>, Box<dyn std::error::
                         .on_response(log_response)
                         .on_failure(log_failure),
                 )
-                .layer(TimeoutLayer::new(Duration::from_secs(30)))
                 .layer(CompressionLayer::new())
+                // Внутри сжатия, чтобы читать текст исходного ответа, и снаружи таймаута
+                .layer(from_fn(error_envelope_middleware))
+                .layer(TimeoutLayer::new(Duration::from_secs(30)))
                 .layer(cors)
                 .layer(from_fn(security_headers_middleware))
-                .layer(from_fn(rate_limit_middleware))
+                .layer(from_fn_with_state(shared_state.clone(), rate_limit_middleware))
         )
         .with_state(shared_state);
     
@@ -0,0 +1,256 @@
+use crate::database::postgres::models::SpamAssessment;
+
+/// Сумма баллов, начиная с которой заявка считается спамом
+pub const SPAM_THRESHOLD: u32 = 100;
+/// Человек не успевает заполнить форму быстрее
+const MIN_FILL_SECONDS: i64 = 3;
+const MAX_LINKS: usize = 2;
+const SPAM_KEYWORDS: &[&str] = &[
+    "casino", "viagra", "bitcoin", "forex", "backlinks",
+    "казино", "букмекер", "ставки на спорт", "продвижение сайт", "раскрутка сайт",
+];
+
+/// Сигналы, собранные обработчиком формы до оценки
+pub struct SpamSignals<'a> {
+    pub name: &'a str,
+    pub message: &'a str,
+    pub honeypot: Option<&'a str>,
+    /// Секунд с выдачи CSRF-токена, `None` если время выдачи неизвестно
+    pub fill_seconds: Option<i64>,
+    pub pow_solved: bool,
+    /// Заявки с тем же email и текстом за сутки
+    pub duplicates: i64,
+    /// Заявки с тем же email за последний час
+    pub recent_from_email: i64,
+}
+
+/// Отдельная эвристика. Возвращает штрафные баллы, 0 — признаков спама нет
+pub trait SpamCheck: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn score(&self, signals: &SpamSignals) -> u32;
+}
+
+/// Скрытое поле, которое заполняют только боты
+struct Honeypot;
+
+impl SpamCheck for Honeypot {
+    fn name(&self) -> &'static str {
+        "honeypot"
+    }
+
+    fn score(&self, signals: &SpamSignals) -> u32 {
+        if signals.honeypot.is_some_and(|v| !v.trim().is_empty()) { 100 } else { 0 }
+    }
+}
+
+struct FillTime;
+
+impl SpamCheck for FillTime {
+    fn name(&self) -> &'static str {
+        "too_fast"
+    }
+
+    fn score(&self, signals: &SpamSignals) -> u32 {
+        match signals.fill_seconds {
+            Some(seconds) if seconds < MIN_FILL_SECONDS => 60,
+            Some(_) => 0,
+            None => 30,
+        }
+    }
+}
+
+struct ProofOfWork;
+
+impl SpamCheck for ProofOfWork {
+    fn name(&self) -> &'static str {
+        "proof_of_work"
+    }
+
+    fn score(&self, signals: &SpamSignals) -> u32 {
+        if signals.pow_solved { 0 } else { 60 }
+    }
+}
+
+struct Links;
+
+impl SpamCheck for Links {
+    fn name(&self) -> &'static str {
+        "links"
+    }
+
+    fn score(&self, signals: &SpamSignals) -> u32 {
+        let text = format!("{} {}", signals.name, signals.message).to_lowercase();
+        let links = ["http://", "https://", "www."]
+            .iter()
+            .map(|marker| text.matches(marker).count())
+            .sum::<usize>();
+
+        match links {
+            0 => 0,
+            n if n <= MAX_LINKS => 20,
+            _ => 60,
+        }
+    }
+}
+
+struct Keywords;
+
+impl SpamCheck for Keywords {
+    fn name(&self) -> &'static str {
+        "keywords"
+    }
+
+    fn score(&self, signals: &SpamSignals) -> u32 {
+        let text = format!("{} {}", signals.name, signals.message).to_lowercase();
+        let hits = SPAM_KEYWORDS.iter().filter(|keyword| text.contains(*keyword)).count() as u32;
+        (hits * 30).min(90)
+    }
+}
+
+struct Duplicates;
+
+impl SpamCheck for Duplicates {
+    fn name(&self) -> &'static str {
+        "duplicate"
+    }
+
+    fn score(&self, signals: &SpamSignals) -> u32 {
+        if signals.duplicates > 0 {
+            100
+        } else if signals.recent_from_email >= 3 {
+            40
+        } else {
+            0
+        }
+    }
+}
+
+/// Набор проверок, через который проходят заявки с публичной формы
+pub struct SpamFilter {
+    checks: Vec<Box<dyn SpamCheck>>,
+}
+
+impl Default for SpamFilter {
+    fn default() -> Self {
+        Self {
+            checks: vec![
+                Box::new(Honeypot),
+                Box::new(FillTime),
+                Box::new(ProofOfWork),
+                Box::new(Links),
+                Box::new(Keywords),
+                Box::new(Duplicates),
+            ],
+        }
+    }
+}
+
+impl SpamFilter {
+    pub fn evaluate(&self, signals: &SpamSignals) -> SpamAssessment {
+        let mut score = 0;
+        let mut reasons = Vec::new();
+
+        for check in &self.checks {
+            let points = check.score(signals);
+            if points > 0 {
+                score += points;
+                reasons.push(check.name().to_string());
+            }
+        }
+
+        SpamAssessment {
+            score: score as i32,
+            reasons,
+            is_spam: score >= SPAM_THRESHOLD,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Сигналы обычной заявки, заполненной человеком
+    fn human() -> SpamSignals<'static> {
+        SpamSignals {
+            name: "Иван Петров",
+            message: "Нужна экспертиза проектной документации",
+            honeypot: Some(""),
+            fill_seconds: Some(40),
+            pow_solved: true,
+            duplicates: 0,
+            recent_from_email: 0,
+        }
+    }
+
+    struct Fixed(u32);
+
+    impl SpamCheck for Fixed {
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+
+        fn score(&self, _signals: &SpamSignals) -> u32 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn passes_human_submission() {
+        let assessment = SpamFilter::default().evaluate(&human());
+        assert_eq!(assessment.score, 0);
+        assert!(assessment.reasons.is_empty());
+        assert!(!assessment.is_spam);
+    }
+
+    #[test]
+    fn flags_filled_honeypot() {
+        let assessment = SpamFilter::default().evaluate(&SpamSignals { honeypot: Some("https://spam"), ..human() });
+        assert!(assessment.is_spam);
+        assert_eq!(assessment.reasons, ["honeypot"]);
+
+        let blank = SpamFilter::default().evaluate(&SpamSignals { honeypot: Some("  "), ..human() });
+        assert!(!blank.is_spam);
+    }
+
+    #[test]
+    fn scores_unsolved_proof_of_work() {
+        let assessment = SpamFilter::default().evaluate(&SpamSignals { pow_solved: false, ..human() });
+        assert_eq!(assessment.score, 60);
+        assert_eq!(assessment.reasons, ["proof_of_work"]);
+        assert!(!assessment.is_spam);
+
+        let fast = SpamFilter::default().evaluate(&SpamSignals { pow_solved: false, fill_seconds: Some(1), ..human() });
+        assert!(fast.is_spam);
+    }
+
+    #[test]
+    fn scores_links_by_count() {
+        let few = SpamSignals { message: "Проект на https://example.com и www.example.org", ..human() };
+        assert_eq!(Links.score(&few), 20);
+
+        let many = SpamSignals {
+            message: "http://a.example https://b.example www.c.example",
+            ..human()
+        };
+        assert_eq!(Links.score(&many), 60);
+
+        // Много ссылок само по себе не делает заявку спамом
+        let link_heavy = SpamSignals { fill_seconds: None, ..many };
+        let assessment = SpamFilter::default().evaluate(&link_heavy);
+        assert_eq!(assessment.reasons, ["too_fast", "links"]);
+        assert!(!assessment.is_spam);
+
+        let with_keyword = SpamSignals { message: "casino http://a.example https://b.example www.c.example", ..link_heavy };
+        assert!(SpamFilter::default().evaluate(&with_keyword).is_spam);
+    }
+
+    #[test]
+    fn threshold_is_inclusive() {
+        let filter = |score: u32| SpamFilter { checks: vec![Box::new(Fixed(score))] };
+
+        assert!(!filter(SPAM_THRESHOLD - 1).evaluate(&human()).is_spam);
+        assert!(filter(SPAM_THRESHOLD).evaluate(&human()).is_spam);
+        assert_eq!(filter(0).evaluate(&human()).reasons, Vec::<String>::new());
+    }
+}
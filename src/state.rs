@@ -1,10 +1,29 @@
 use std::sync::Arc;
+use ipnet::IpNet;
 use crate::database::redis::redis_interface::RedisDatabase;
 use crate::database::postgres::postgres_interface::PostgresDatabase;
+use crate::spam::SpamFilter;
+use crate::config::{AssignmentStrategy, UploadLimits};
+use crate::mail::MailTemplates;
+use crate::telegram::TelegramBot;
+use crate::events::EventHub;
+use crate::storage::Storage;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db_postgres: Arc<PostgresDatabase>,
     pub db_redis: Arc<RedisDatabase>,
-    pub jwt_secret: String
+    pub jwt_secret: String,
+    pub trusted_proxies: Arc<[IpNet]>,
+    pub spam_filter: Arc<SpamFilter>,
+    pub auto_assign: AssignmentStrategy,
+    /// `None`, если почтовые уведомления отключены
+    pub mail_templates: Option<Arc<MailTemplates>>,
+    /// `None`, если бот Telegram не настроен
+    pub telegram: Option<Arc<TelegramBot>>,
+    /// События панели из Redis для SSE-потоков
+    pub events: Arc<EventHub>,
+    /// Содержимое вложений заявок
+    pub storage: Arc<dyn Storage>,
+    pub upload_limits: UploadLimits,
 }
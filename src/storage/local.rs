@@ -0,0 +1,86 @@
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use super::{Storage, StorageError, StorageFuture};
+
+/// Файлы в каталоге на диске, `STORAGE_DIR`
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub async fn new(root: impl AsRef<Path>) -> std::io::Result<Self> {
+        tokio::fs::create_dir_all(root.as_ref()).await?;
+        Ok(Self { root: root.as_ref().to_path_buf() })
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+impl Storage for LocalStorage {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    // Запись во временный файл и переименование: читатель не увидит
+    // недописанный файл, а одновременная запись того же ключа безопасна
+    fn put<'a>(&'a self, key: &'a str, data: &'a [u8], _content_type: &'a str) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            let path = self.path(key);
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+
+            let tmp = path.with_extension(format!("{}.tmp", Uuid::new_v4().simple()));
+            if let Err(e) = tokio::fs::write(&tmp, data).await {
+                let _ = tokio::fs::remove_file(&tmp).await;
+                return Err(e.into());
+            }
+            tokio::fs::rename(&tmp, &path).await?;
+            Ok(())
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> StorageFuture<'a, Vec<u8>> {
+        Box::pin(async move {
+            tokio::fs::read(self.path(key)).await.map_err(|e| match e.kind() {
+                ErrorKind::NotFound => StorageError::NotFound(key.to_string()),
+                _ => StorageError::Io(e),
+            })
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            match tokio::fs::remove_file(self.path(key)).await {
+                Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+                _ => Ok(()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn put_get_delete_round_trip() {
+        let root = std::env::temp_dir().join(format!("expertiza-storage-{}", Uuid::new_v4().simple()));
+        let storage = LocalStorage::new(&root).await.unwrap();
+        let key = "sha256/ab/abcdef";
+
+        storage.put(key, b"first", "text/plain").await.unwrap();
+        storage.put(key, b"second", "text/plain").await.unwrap();
+        assert_eq!(storage.get(key).await.unwrap(), b"second");
+
+        storage.delete(key).await.unwrap();
+        assert!(matches!(storage.get(key).await, Err(StorageError::NotFound(_))));
+        storage.delete(key).await.unwrap();
+
+        tokio::fs::remove_dir_all(&root).await.unwrap();
+    }
+}
@@ -0,0 +1,51 @@
+pub mod local;
+pub mod s3;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::config::StorageConfig;
+use local::LocalStorage;
+use s3::S3Storage;
+
+pub type StorageFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, StorageError>> + Send + 'a>>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("Object not found: {0}")]
+    NotFound(String),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("S3 error {status}: {message}")]
+    S3 { status: u16, message: String },
+}
+
+/// Хранилище содержимого вложений. Ключи формирует приложение
+/// (`content_key`), поэтому в них только `[0-9a-z/]`
+pub trait Storage: Send + Sync {
+    /// Название бэкенда для логов
+    fn name(&self) -> &'static str;
+
+    /// Запись объекта. Повторная запись того же ключа перезаписывает объект
+    fn put<'a>(&'a self, key: &'a str, data: &'a [u8], content_type: &'a str) -> StorageFuture<'a, ()>;
+
+    fn get<'a>(&'a self, key: &'a str) -> StorageFuture<'a, Vec<u8>>;
+
+    /// Удаление объекта. Отсутствующий объект ошибкой не считается
+    fn delete<'a>(&'a self, key: &'a str) -> StorageFuture<'a, ()>;
+}
+
+/// Ключ объекта по SHA-256 содержимого: одинаковые файлы хранятся один раз
+pub fn content_key(sha256: &str) -> String {
+    format!("sha256/{}/{}", &sha256[..2], sha256)
+}
+
+pub async fn from_config(config: &StorageConfig) -> Result<Arc<dyn Storage>, StorageError> {
+    Ok(match config {
+        StorageConfig::Local { dir } => Arc::new(LocalStorage::new(dir).await?),
+        StorageConfig::S3(s3) => Arc::new(S3Storage::new(s3)?),
+    })
+}
@@ -0,0 +1,200 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::config::TelegramConfig;
+use crate::database::postgres::models::{CreateSubmissionRequest, OutboxEntry, OutboxRecipient};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+/// Лимит Bot API на длину текста сообщения
+const MAX_MESSAGE_CHARS: usize = 4096;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TelegramError {
+    #[error("Telegram request failed: {0}")]
+    Http(reqwest::Error),
+    #[error("Telegram API error {status}: {description}")]
+    Api { status: u16, description: String },
+}
+
+/// URL запроса содержит токен бота, поэтому в ошибку он не попадает:
+/// текст ошибки пишется в лог и в `outbox.last_error`
+impl From<reqwest::Error> for TelegramError {
+    fn from(e: reqwest::Error) -> Self {
+        TelegramError::Http(e.without_url())
+    }
+}
+
+impl TelegramError {
+    /// Ошибки 4xx, кроме 429, повторная отправка не исправит
+    pub fn is_permanent(&self) -> bool {
+        match self {
+            TelegramError::Http(_) => false,
+            TelegramError::Api { status, .. } => (400..500).contains(status) && *status != 429,
+        }
+    }
+}
+
+/// Действие кнопки под сообщением о заявке. В `callback_data` кнопки
+/// передается как `take:<submission_id>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TelegramAction {
+    /// Назначить заявку на себя и взять в работу
+    Take,
+    Reject,
+    MarkViewed,
+}
+
+impl TelegramAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TelegramAction::Take => "take",
+            TelegramAction::Reject => "reject",
+            TelegramAction::MarkViewed => "viewed",
+        }
+    }
+
+    pub fn callback_data(&self, submission_id: Uuid) -> String {
+        format!("{}:{}", self.as_str(), submission_id)
+    }
+
+    pub fn parse_callback_data(data: &str) -> Option<(TelegramAction, Uuid)> {
+        let (action, submission_id) = data.split_once(':')?;
+        let action = [TelegramAction::Take, TelegramAction::Reject, TelegramAction::MarkViewed]
+            .into_iter()
+            .find(|a| a.as_str() == action)?;
+        Some((action, submission_id.parse().ok()?))
+    }
+}
+
+impl fmt::Display for TelegramAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiResponse {
+    ok: bool,
+    description: Option<String>,
+}
+
+/// Клиент Bot API
+#[derive(Clone)]
+pub struct TelegramClient {
+    http: reqwest::Client,
+    /// `{api_url}/bot{token}`
+    base_url: String,
+}
+
+impl TelegramClient {
+    pub fn new(config: &TelegramConfig) -> Result<Self, TelegramError> {
+        Ok(Self {
+            http: reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build()?,
+            base_url: format!("{}/bot{}", config.api_url.trim_end_matches('/'), config.bot_token),
+        })
+    }
+
+    async fn call(&self, method: &str, payload: &impl Serialize) -> Result<(), TelegramError> {
+        let response = self.http
+            .post(format!("{}/{}", self.base_url, method))
+            .json(payload)
+            .send()
+            .await?;
+
+        let status = response.status().as_u16();
+        let body: ApiResponse = response.json().await?;
+        if !body.ok {
+            return Err(TelegramError::Api {
+                status,
+                description: body.description.unwrap_or_default(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Сообщение о заявке с кнопками действий
+    pub async fn send_submission(&self, chat_id: &str, text: &str, submission_id: Option<Uuid>) -> Result<(), TelegramError> {
+        let mut payload = json!({ "chat_id": chat_id, "text": text });
+        if let Some(submission_id) = submission_id {
+            payload["reply_markup"] = submission_keyboard(submission_id);
+        }
+        self.call("sendMessage", &payload).await
+    }
+
+    /// Всплывающий ответ на нажатие кнопки
+    pub async fn answer_callback(&self, callback_query_id: &str, text: &str) -> Result<(), TelegramError> {
+        self.call("answerCallbackQuery", &json!({
+            "callback_query_id": callback_query_id,
+            "text": text,
+        })).await
+    }
+
+    /// Убирает кнопки, когда заявка уже взята или отклонена
+    pub async fn remove_keyboard(&self, chat_id: i64, message_id: i64) -> Result<(), TelegramError> {
+        self.call("editMessageReplyMarkup", &json!({
+            "chat_id": chat_id,
+            "message_id": message_id,
+            "reply_markup": { "inline_keyboard": [] },
+        })).await
+    }
+
+    pub async fn set_webhook(&self, url: &str, secret_token: &str) -> Result<(), TelegramError> {
+        self.call("setWebhook", &json!({
+            "url": url,
+            "secret_token": secret_token,
+            "allowed_updates": ["callback_query"],
+        })).await
+    }
+}
+
+fn submission_keyboard(submission_id: Uuid) -> Value {
+    let button = |label: &str, action: TelegramAction| json!({
+        "text": label,
+        "callback_data": action.callback_data(submission_id),
+    });
+
+    json!({
+        "inline_keyboard": [[
+            button("Взять", TelegramAction::Take),
+            button("Отклонить", TelegramAction::Reject),
+            button("Просмотрено", TelegramAction::MarkViewed),
+        ]]
+    })
+}
+
+/// Бот панели: клиент API и настройки чата
+pub struct TelegramBot {
+    pub client: TelegramClient,
+    pub chat_id: i64,
+    pub webhook_secret: String,
+}
+
+impl TelegramBot {
+    /// Сообщение о новой заявке для записи в outbox. Текст без разметки,
+    /// чтобы не экранировать данные клиента
+    pub fn submission_post(&self, submission: &CreateSubmissionRequest) -> OutboxEntry {
+        let header = format!(
+            "Новая заявка\n\nИмя: {}\nEmail: {}\nТелефон: {}\n\n",
+            submission.name,
+            submission.email,
+            submission.phone.as_deref().unwrap_or("не указан"),
+        );
+        let footer = format!("\n\nЗаявка {}", submission.submission_id);
+
+        let room = MAX_MESSAGE_CHARS.saturating_sub(header.chars().count() + footer.chars().count());
+        let message: String = if submission.message.chars().count() > room {
+            submission.message.chars().take(room.saturating_sub(1)).chain(['…']).collect()
+        } else {
+            submission.message.clone()
+        };
+
+        OutboxEntry {
+            recipient: OutboxRecipient::TelegramChat(self.chat_id),
+            subject: None,
+            body: format!("{}{}{}", header, message, footer),
+        }
+    }
+}
@@ -0,0 +1,56 @@
+/// Подстановка значений в плейсхолдеры вида `{{key}}` за один проход:
+/// подставленный текст повторно не просматривается, поэтому `{{...}}`
+/// внутри значения остается как есть. Неизвестные плейсхолдеры сохраняются
+pub fn fill(template: &str, mut value: impl FnMut(&str) -> Option<String>) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => {
+                let key = &after[..end];
+                match value(key) {
+                    Some(value) => result.push_str(&value),
+                    None => {
+                        result.push_str("{{");
+                        result.push_str(key);
+                        result.push_str("}}");
+                    }
+                }
+                rest = &after[end + 2..];
+            }
+            None => {
+                result.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    result.push_str(rest);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fill;
+
+    fn lookup(key: &str) -> Option<String> {
+        match key {
+            "name" => Some("{{total}}".to_string()),
+            "total" => Some("100".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn substitutes_each_placeholder_once() {
+        assert_eq!(fill("{{name}}: {{total}}", lookup), "{{total}}: 100");
+    }
+
+    #[test]
+    fn keeps_unknown_and_unterminated_placeholders() {
+        assert_eq!(fill("{{other}} {{total", lookup), "{{other}} {{total");
+    }
+}
@@ -0,0 +1,178 @@
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use rand::{distr::Alphanumeric, rng, Rng, RngCore};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+/// Параметры RFC 6238, совместимые с Google Authenticator и аналогами
+pub const TOTP_STEP_SECONDS: i64 = 30;
+pub const TOTP_DIGITS: u32 = 6;
+/// Допустимое расхождение часов клиента, в шагах
+pub const TOTP_ALLOWED_SKEW: i64 = 1;
+const SECRET_BYTES: usize = 20;
+const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Генерация нового секрета в base32 (без паддинга)
+pub fn generate_secret() -> String {
+    let mut secret = [0u8; SECRET_BYTES];
+    rng().fill_bytes(&mut secret);
+    BASE32_NOPAD.encode(&secret)
+}
+
+/// URI для QR-кода приложения-аутентификатора
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &str) -> String {
+    format!(
+        "otpauth://totp/{issuer}:{account}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={TOTP_DIGITS}&period={TOTP_STEP_SECONDS}",
+        issuer = percent_encode(issuer),
+        account = percent_encode(account),
+    )
+}
+
+/// Номер временного шага для момента `unix_time`
+pub fn time_step(unix_time: i64) -> i64 {
+    unix_time.div_euclid(TOTP_STEP_SECONDS)
+}
+
+/// HOTP-код (RFC 4226) для указанного шага
+pub fn generate_code(secret: &str, step: i64) -> Option<String> {
+    let key = BASE32_NOPAD.decode(secret.as_bytes()).ok()?;
+    let mut mac = Hmac::<Sha1>::new_from_slice(&key).ok()?;
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    let code = binary % 10u32.pow(TOTP_DIGITS);
+
+    Some(format!("{:0width$}", code, width = TOTP_DIGITS as usize))
+}
+
+/// Проверка кода на момент `unix_time` с учетом допустимого расхождения часов.
+/// Шаги до `last_step` включительно уже использованы и не принимаются.
+/// Возвращает шаг, которому соответствует код, чтобы вызывающая сторона
+/// могла зафиксировать его использование.
+pub fn verify_code(secret: &str, code: &str, unix_time: i64, last_step: Option<i64>) -> Option<i64> {
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let current = time_step(unix_time);
+    let first = match last_step {
+        Some(last) => (current - TOTP_ALLOWED_SKEW).max(last + 1),
+        None => current - TOTP_ALLOWED_SKEW,
+    };
+    (first..=current + TOTP_ALLOWED_SKEW).find(|step| {
+        generate_code(secret, *step)
+            .is_some_and(|expected| constant_time_eq(expected.as_bytes(), code.as_bytes()))
+    })
+}
+
+/// Набор одноразовых кодов восстановления вида `xxxxx-xxxxx`
+pub fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let raw: String = rng()
+                .sample_iter(&Alphanumeric)
+                .take(10)
+                .map(|b| char::from(b).to_ascii_lowercase())
+                .collect();
+            format!("{}-{}", &raw[..5], &raw[5..])
+        })
+        .collect()
+}
+
+/// Хэш кода восстановления для хранения в БД. Коды имеют высокую энтропию,
+/// поэтому медленный bcrypt здесь не нужен
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .trim()
+        .to_ascii_lowercase()
+        .chars()
+        .filter(|c| *c != '-' && !c.is_whitespace())
+        .collect();
+    let digest = Sha256::digest(normalized.as_bytes());
+    data_encoding::HEXLOWER.encode(&digest)
+}
+
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Секрет SHA-1 из приложения B RFC 6238: ASCII "12345678901234567890"
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn matches_rfc_6238_sha1_vectors() {
+        // В RFC коды восьмизначные, шестизначный код — их последние шесть цифр
+        let vectors = [
+            (59, "287082"),
+            (1111111109, "081804"),
+            (1111111111, "050471"),
+            (1234567890, "005924"),
+            (2000000000, "279037"),
+            (20000000000, "353130"),
+        ];
+
+        for (unix_time, expected) in vectors {
+            assert_eq!(generate_code(RFC_SECRET, time_step(unix_time)).as_deref(), Some(expected), "T = {}", unix_time);
+            assert_eq!(verify_code(RFC_SECRET, expected, unix_time, None), Some(time_step(unix_time)));
+        }
+    }
+
+    #[test]
+    fn accepts_one_step_of_clock_skew() {
+        let now = 1234567890;
+        let current = time_step(now);
+
+        for offset in [-1, 0, 1] {
+            let code = generate_code(RFC_SECRET, current + offset).unwrap();
+            assert_eq!(verify_code(RFC_SECRET, &code, now, None), Some(current + offset), "offset {}", offset);
+        }
+        for offset in [-2, 2] {
+            let code = generate_code(RFC_SECRET, current + offset).unwrap();
+            assert_eq!(verify_code(RFC_SECRET, &code, now, None), None, "offset {}", offset);
+        }
+    }
+
+    #[test]
+    fn rejects_used_steps() {
+        let now = 1234567890;
+        let current = time_step(now);
+        let code = generate_code(RFC_SECRET, current).unwrap();
+
+        assert_eq!(verify_code(RFC_SECRET, &code, now, Some(current - 1)), Some(current));
+        assert_eq!(verify_code(RFC_SECRET, &code, now, Some(current)), None);
+        // Код предыдущего шага после использования текущего тоже не принимается
+        let previous = generate_code(RFC_SECRET, current - 1).unwrap();
+        assert_eq!(verify_code(RFC_SECRET, &previous, now, Some(current)), None);
+    }
+
+    #[test]
+    fn rejects_malformed_codes() {
+        let now = 59;
+        assert_eq!(verify_code(RFC_SECRET, " 287082 ", now, None), Some(1));
+        for code in ["28708", "2870820", "28708a", ""] {
+            assert_eq!(verify_code(RFC_SECRET, code, now, None), None, "{:?}", code);
+        }
+    }
+}
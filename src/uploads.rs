@@ -0,0 +1,325 @@
+use axum::extract::multipart::{Field, Multipart, MultipartError};
+use axum::http::StatusCode;
+use data_encoding::HEXLOWER;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::config::UploadLimits;
+use crate::database::postgres::models::NewAttachment;
+use crate::database::postgres::postgres_interface::PostgresDatabase;
+use crate::error::AppError;
+use crate::state::AppState;
+use crate::storage::{content_key, Storage};
+
+/// Поле формы с файлами
+pub const FILES_FIELD: &str = "files";
+/// Ограничение длины текстового поля формы
+const MAX_TEXT_FIELD_BYTES: usize = 64 * 1024;
+const MAX_FILE_NAME_CHARS: usize = 255;
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(3600);
+/// Содержимое без вложений хранится сутки после последнего использования
+const CLEANUP_GRACE_SECS: i64 = 24 * 3600;
+const CLEANUP_BATCH_SIZE: i64 = 100;
+
+/// Файл из multipart-запроса. Тип определен по содержимому
+pub struct UploadedFile {
+    pub file_name: String,
+    pub content_type: &'static str,
+    pub data: Vec<u8>,
+    pub sha256: String,
+}
+
+/// Текстовые поля и файлы multipart-формы
+pub struct MultipartForm {
+    pub fields: HashMap<String, String>,
+    pub files: Vec<UploadedFile>,
+}
+
+impl MultipartForm {
+    pub async fn read(mut multipart: Multipart, limits: UploadLimits) -> Result<Self, AppError> {
+        let mut form = MultipartForm { fields: HashMap::new(), files: Vec::new() };
+
+        while let Some(field) = multipart.next_field().await.map_err(multipart_error)? {
+            if is_file_field(&field) {
+                read_file_into(&mut form.files, field, limits).await?;
+            } else {
+                let name = field.name().unwrap_or_default().to_string();
+                let value = read_text(field).await?;
+                form.fields.insert(name, value);
+            }
+        }
+
+        Ok(form)
+    }
+}
+
+pub fn is_file_field(field: &Field<'_>) -> bool {
+    field.name() == Some(FILES_FIELD)
+}
+
+pub async fn read_text(mut field: Field<'_>) -> Result<String, AppError> {
+    let mut data = Vec::new();
+    while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+        if data.len() + chunk.len() > MAX_TEXT_FIELD_BYTES {
+            return Err(AppError::payload_too_large("Form field is too large"));
+        }
+        data.extend_from_slice(&chunk);
+    }
+    String::from_utf8(data).map_err(|_| AppError::bad_request("Form field is not valid UTF-8"))
+}
+
+/// Чтение файла в список с проверкой числа файлов. Пустое поле выбора
+/// файла браузер отправляет без имени и содержимого, оно пропускается
+pub async fn read_file_into(
+    files: &mut Vec<UploadedFile>,
+    field: Field<'_>,
+    limits: UploadLimits,
+) -> Result<(), AppError> {
+    if files.len() == limits.max_files {
+        return Err(AppError::payload_too_large(format!(
+            "No more than {} files per request",
+            limits.max_files
+        )));
+    }
+    if let Some(file) = read_file(field, limits).await? {
+        files.push(file);
+    }
+    Ok(())
+}
+
+/// Файл читается по частям, превышение лимита прерывает чтение сразу
+async fn read_file(mut field: Field<'_>, limits: UploadLimits) -> Result<Option<UploadedFile>, AppError> {
+    let file_name = sanitize_file_name(field.file_name().unwrap_or_default());
+
+    let mut data = Vec::new();
+    while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+        if data.len() + chunk.len() > limits.max_file_bytes {
+            return Err(AppError::payload_too_large(format!(
+                "File \"{}\" exceeds {} MB",
+                file_name,
+                limits.max_file_bytes / (1024 * 1024)
+            )));
+        }
+        data.extend_from_slice(&chunk);
+    }
+    if data.is_empty() {
+        return Ok(None);
+    }
+
+    let content_type = sniff_content_type(&data, &file_name).ok_or_else(|| {
+        AppError::unsupported_media_type(format!("File type of \"{}\" is not allowed", file_name))
+    })?;
+
+    Ok(Some(UploadedFile {
+        file_name,
+        content_type,
+        sha256: HEXLOWER.encode(&Sha256::digest(&data)),
+        data,
+    }))
+}
+
+pub fn multipart_error(e: MultipartError) -> AppError {
+    match e.status() {
+        StatusCode::PAYLOAD_TOO_LARGE => AppError::payload_too_large(e.body_text()),
+        _ => AppError::bad_request(e.body_text()),
+    }
+}
+
+/// Имя для Content-Disposition и списка вложений: без пути и управляющих символов
+fn sanitize_file_name(name: &str) -> String {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let cleaned: String = base
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_FILE_NAME_CHARS)
+        .collect();
+    let cleaned = cleaned.trim().trim_start_matches('.');
+
+    if cleaned.is_empty() { "file".to_string() } else { cleaned.to_string() }
+}
+
+/// Тип файла по сигнатуре содержимого. Расширение учитывается только там,
+/// где сигнатура общая (ZIP-контейнеры Office, составные документы OLE).
+/// `None` — тип не входит в список разрешенных
+fn sniff_content_type(data: &[u8], file_name: &str) -> Option<&'static str> {
+    let extension = file_name.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase()).unwrap_or_default();
+
+    if data.starts_with(b"%PDF-") {
+        return Some("application/pdf");
+    }
+    if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return Some("image/jpeg");
+    }
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some("image/png");
+    }
+    if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        return Some("image/gif");
+    }
+    if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
+    if data.starts_with(b"II*\0") || data.starts_with(b"MM\0*") {
+        return Some("image/tiff");
+    }
+    if data.len() >= 12 && &data[4..8] == b"ftyp" && matches!(&data[8..12], b"heic" | b"heix" | b"mif1") {
+        return Some("image/heic");
+    }
+    if data.starts_with(b"{\\rtf") {
+        return Some("application/rtf");
+    }
+    if data.starts_with(b"PK\x03\x04") {
+        return Some(match extension.as_str() {
+            "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+            "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+            "odt" => "application/vnd.oasis.opendocument.text",
+            "ods" => "application/vnd.oasis.opendocument.spreadsheet",
+            _ => "application/zip",
+        });
+    }
+    if data.starts_with(&[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1]) {
+        return match extension.as_str() {
+            "doc" => Some("application/msword"),
+            "xls" => Some("application/vnd.ms-excel"),
+            "ppt" => Some("application/vnd.ms-powerpoint"),
+            _ => None,
+        };
+    }
+    // Текст без сигнатуры: только UTF-8 без нулевых байтов
+    if matches!(extension.as_str(), "txt" | "csv") && !data.contains(&0) && std::str::from_utf8(data).is_ok() {
+        return Some(if extension == "csv" { "text/csv" } else { "text/plain" });
+    }
+
+    None
+}
+
+/// Запись файлов в хранилище. Содержимое, которое уже есть в хранилище,
+/// повторно не загружается, одинаковые файлы запроса сохраняются один раз
+pub async fn store(state: &AppState, files: &[UploadedFile]) -> Result<Vec<NewAttachment>, AppError> {
+    let mut seen = HashSet::new();
+    let mut attachments = Vec::with_capacity(files.len());
+
+    for file in files {
+        if !seen.insert(file.sha256.as_str()) {
+            continue;
+        }
+
+        let storage_key = content_key(&file.sha256);
+        if !state.db_postgres.touch_stored_file(&file.sha256).await? {
+            state.storage.put(&storage_key, &file.data, file.content_type).await?;
+        }
+
+        attachments.push(NewAttachment {
+            sha256: file.sha256.clone(),
+            size_bytes: file.data.len() as i64,
+            content_type: file.content_type,
+            storage_key,
+            file_name: file.file_name.clone(),
+        });
+    }
+
+    Ok(attachments)
+}
+
+/// Фоновая очистка содержимого, на которое не ссылается ни одно вложение
+/// (например, после удаления заявки или сбоя при ее сохранении)
+pub fn spawn_cleanup_worker(db: Arc<PostgresDatabase>, storage: Arc<dyn Storage>) {
+    tokio::spawn(async move {
+        loop {
+            let result = db
+                .delete_unreferenced_files(CLEANUP_GRACE_SECS, CLEANUP_BATCH_SIZE, |key| {
+                    let storage = storage.clone();
+                    async move {
+                        match storage.delete(&key).await {
+                            Ok(()) => true,
+                            Err(e) => {
+                                warn!(key = %key, error = %e, "Failed to delete unreferenced file");
+                                false
+                            }
+                        }
+                    }
+                })
+                .await;
+
+            match result {
+                Ok(0) => {}
+                Ok(count) => {
+                    info!(count, "Unreferenced files deleted");
+                    // Полная пачка: возможно, есть еще файлы
+                    if count as i64 == CLEANUP_BATCH_SIZE {
+                        continue;
+                    }
+                }
+                Err(e) => warn!(error = %e, "Failed to clean up unreferenced files"),
+            }
+            tokio::time::sleep(CLEANUP_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLE_HEADER: &[u8] = &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1, 0, 0];
+
+    #[test]
+    fn sniffs_allowed_and_rejects_other_types() {
+        let cases: &[(&[u8], &str, Option<&str>)] = &[
+            (b"%PDF-1.7\n", "scan.jpg", Some("application/pdf")),
+            (&[0xFF, 0xD8, 0xFF, 0xE0], "photo", Some("image/jpeg")),
+            (b"\x89PNG\r\n\x1a\n\0\0", "image.png", Some("image/png")),
+            (b"GIF89a\x01\0", "a.gif", Some("image/gif")),
+            (b"RIFF\0\0\0\0WEBPVP8 ", "a.webp", Some("image/webp")),
+            (b"II*\0\x08\0", "a.tif", Some("image/tiff")),
+            (b"\0\0\0\x18ftypheic", "a.heic", Some("image/heic")),
+            (b"{\\rtf1\\ansi", "a.rtf", Some("application/rtf")),
+            (b"PK\x03\x04\x14\0", "report.DOCX", Some("application/vnd.openxmlformats-officedocument.wordprocessingml.document")),
+            (b"PK\x03\x04\x14\0", "table.ods", Some("application/vnd.oasis.opendocument.spreadsheet")),
+            (b"PK\x03\x04\x14\0", "archive", Some("application/zip")),
+            (OLE_HEADER, "old.doc", Some("application/msword")),
+            (OLE_HEADER, "old.xls", Some("application/vnd.ms-excel")),
+            // Составной документ без расширения может быть чем угодно, например .msi
+            (OLE_HEADER, "setup", None),
+            (OLE_HEADER, "setup.msi", None),
+            ("Привет, мир".as_bytes(), "note.txt", Some("text/plain")),
+            (b"a;b\n1;2\n", "data.csv", Some("text/csv")),
+            (b"text\0with nul", "note.txt", None),
+            (&[0xC3, 0x28], "broken.txt", None),
+            (b"plain text", "note", None),
+            (b"MZ\x90\0", "program.exe", None),
+            (b"MZ\x90\0", "program.pdf", None),
+            (b"<html><script>", "page.html", None),
+        ];
+
+        for (data, file_name, expected) in cases {
+            assert_eq!(sniff_content_type(data, file_name), *expected, "{}", file_name);
+        }
+    }
+
+    #[test]
+    fn sanitizes_file_names() {
+        let cases = [
+            ("report.pdf", "report.pdf"),
+            ("../../etc/passwd", "passwd"),
+            ("..\\..\\windows\\system.ini", "system.ini"),
+            ("C:\\Users\\ivan\\Договор.docx", "Договор.docx"),
+            ("dir/", "file"),
+            ("..", "file"),
+            (".env", "env"),
+            ("na\u{0}me\r\n.pdf", "name.pdf"),
+            ("\u{1b}[31mred.txt", "[31mred.txt"),
+            ("  spaced.txt  ", "spaced.txt"),
+            ("", "file"),
+        ];
+
+        for (name, expected) in cases {
+            assert_eq!(sanitize_file_name(name), expected, "{:?}", name);
+        }
+        assert_eq!(sanitize_file_name(&"a".repeat(1000)).chars().count(), MAX_FILE_NAME_CHARS);
+    }
+}
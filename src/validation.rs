@@ -0,0 +1,153 @@
+use serde::Serialize;
+
+use crate::error::AppError;
+
+pub const NAME_MIN_CHARS: usize = 2;
+pub const NAME_MAX_CHARS: usize = 255;
+pub const MESSAGE_MIN_CHARS: usize = 10;
+pub const MESSAGE_MAX_CHARS: usize = 1000;
+pub const EMAIL_MAX_CHARS: usize = 254;
+
+/// Ошибка конкретного поля формы, клиент выводит ее рядом с полем
+#[derive(Debug, Serialize)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &'static str, code: &'static str, message: impl Into<String>) -> Self {
+        Self { field, code, message: message.into() }
+    }
+}
+
+/// Нормализованные данные заявки, прошедшие проверку
+#[derive(Debug)]
+pub struct ValidatedContact {
+    pub name: String,
+    pub email: String,
+    /// Телефон в формате E.164 (`+7XXXXXXXXXX`)
+    pub phone: Option<String>,
+    pub message: String,
+}
+
+/// Общая проверка заявки для публичной формы и админки.
+/// Возвращает все ошибки сразу, а не первую найденную
+pub fn validate_contact(
+    name: &str,
+    email: &str,
+    phone: Option<&str>,
+    message: &str,
+) -> Result<ValidatedContact, AppError> {
+    let mut errors = Vec::new();
+
+    let name = name.trim();
+    check_length(&mut errors, "name", name, NAME_MIN_CHARS, NAME_MAX_CHARS,
+        "Имя должно содержать 2-255 символов");
+
+    let message = message.trim();
+    check_length(&mut errors, "message", message, MESSAGE_MIN_CHARS, MESSAGE_MAX_CHARS,
+        "Сообщение должно содержать 10-1000 символов");
+
+    let email = email.trim().to_lowercase();
+    if email.is_empty() {
+        errors.push(FieldError::new("email", "required", "Укажите email адрес"));
+    } else if email.chars().count() > EMAIL_MAX_CHARS {
+        errors.push(FieldError::new("email", "too_long", "Email адрес не должен превышать 254 символа"));
+    } else if !is_valid_email(&email) {
+        errors.push(FieldError::new("email", "invalid_format", "Некорректный email адрес"));
+    }
+
+    let phone = match phone.map(str::trim).filter(|p| !p.is_empty()) {
+        None => None,
+        Some(raw) => {
+            let normalized = normalize_ru_phone(raw);
+            if normalized.is_none() {
+                errors.push(FieldError::new("phone", "invalid_format",
+                    "Некорректный номер телефона, ожидается российский номер"));
+            }
+            normalized
+        }
+    };
+
+    if !errors.is_empty() {
+        return Err(AppError::Validation(errors));
+    }
+
+    Ok(ValidatedContact {
+        name: name.to_string(),
+        email,
+        phone,
+        message: message.to_string(),
+    })
+}
+
+/// Длина считается в символах, а не в байтах: кириллица занимает 2 байта
+fn check_length(
+    errors: &mut Vec<FieldError>,
+    field: &'static str,
+    value: &str,
+    min: usize,
+    max: usize,
+    message: &str,
+) {
+    let chars = value.chars().count();
+    if chars == 0 {
+        errors.push(FieldError::new(field, "required", "Поле обязательно для заполнения"));
+    } else if chars < min {
+        errors.push(FieldError::new(field, "too_short", message));
+    } else if chars > max {
+        errors.push(FieldError::new(field, "too_long", message));
+    }
+}
+
+/// Повторяет ограничение `check_email_format` из миграции:
+/// `^[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}$`
+pub fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+
+    let local_ok = !local.is_empty()
+        && local.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '%' | '+' | '-'));
+
+    let domain_ok = match domain.rsplit_once('.') {
+        Some((host, tld)) => {
+            !host.is_empty()
+                && host.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-'))
+                && tld.len() >= 2
+                && tld.chars().all(|c| c.is_ascii_alphabetic())
+        }
+        None => false,
+    };
+
+    local_ok && domain_ok
+}
+
+/// Приведение российского номера к E.164. Допускаются `8XXXXXXXXXX`, `+7XXXXXXXXXX`
+/// и 10 цифр без кода страны, разделители игнорируются
+pub fn normalize_ru_phone(raw: &str) -> Option<String> {
+    if raw.chars().any(|c| !(c.is_ascii_digit() || matches!(c, '+' | ' ' | '-' | '(' | ')'))) {
+        return None;
+    }
+    // '+' допустим только в начале
+    if raw.char_indices().any(|(i, c)| c == '+' && i != 0) {
+        return None;
+    }
+
+    let digits: String = raw.chars().filter(char::is_ascii_digit).collect();
+    let national = match digits.len() {
+        10 if !raw.starts_with('+') => &digits[..],
+        11 if digits.starts_with('7') => &digits[1..],
+        11 if digits.starts_with('8') && !raw.starts_with('+') => &digits[1..],
+        _ => return None,
+    };
+
+    // Коды зон: 3xx, 4xx, 8xx — географические и бесплатные, 9xx — мобильные
+    if !matches!(national.as_bytes()[0], b'3' | b'4' | b'8' | b'9') {
+        return None;
+    }
+
+    Some(format!("+7{}", national))
+}
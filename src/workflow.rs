@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::roles::AdminRole;
+
+/// Статус заявки (колонка `submissions.status`, ограничение `check_status_valid`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubmissionStatus {
+    New,
+    Viewed,
+    InProgress,
+    Completed,
+    Rejected,
+    Spam,
+}
+
+impl SubmissionStatus {
+    pub const ALL: [SubmissionStatus; 6] = [
+        SubmissionStatus::New,
+        SubmissionStatus::Viewed,
+        SubmissionStatus::InProgress,
+        SubmissionStatus::Completed,
+        SubmissionStatus::Rejected,
+        SubmissionStatus::Spam,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubmissionStatus::New => "new",
+            SubmissionStatus::Viewed => "viewed",
+            SubmissionStatus::InProgress => "in_progress",
+            SubmissionStatus::Completed => "completed",
+            SubmissionStatus::Rejected => "rejected",
+            SubmissionStatus::Spam => "spam",
+        }
+    }
+
+    /// Название статуса в панели и уведомлениях
+    pub fn label(&self) -> &'static str {
+        match self {
+            SubmissionStatus::New => "Новая",
+            SubmissionStatus::Viewed => "Просмотрено",
+            SubmissionStatus::InProgress => "В работе",
+            SubmissionStatus::Completed => "Завершено",
+            SubmissionStatus::Rejected => "Отклонено",
+            SubmissionStatus::Spam => "Спам",
+        }
+    }
+
+    /// Переходы, доступные при обычной работе с заявкой
+    pub fn next_statuses(&self) -> &'static [SubmissionStatus] {
+        use SubmissionStatus::*;
+        match self {
+            New => &[Viewed, InProgress, Rejected, Spam],
+            Viewed => &[InProgress, Rejected, Spam],
+            InProgress => &[Completed, Rejected],
+            // Ошибочно помеченная как спам заявка возвращается в работу
+            Spam => &[New],
+            Completed | Rejected => &[],
+        }
+    }
+
+    /// Проверка перехода. Владелец может вернуть заявку в любой статус,
+    /// например переоткрыть завершенную
+    pub fn can_transition_to(&self, to: SubmissionStatus, role: AdminRole) -> bool {
+        self.next_statuses().contains(&to) || (role == AdminRole::Owner && *self != to)
+    }
+}
+
+impl fmt::Display for SubmissionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for SubmissionStatus {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        SubmissionStatus::ALL
+            .into_iter()
+            .find(|status| status.as_str() == value)
+            .ok_or_else(|| format!("Unknown submission status: {}", value))
+    }
+}
+
+impl TryFrom<String> for SubmissionStatus {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use SubmissionStatus::*;
+
+    /// Все разрешенные переходы, остальные пары запрещены
+    const ALLOWED: &[(SubmissionStatus, SubmissionStatus)] = &[
+        (New, Viewed),
+        (New, InProgress),
+        (New, Rejected),
+        (New, Spam),
+        (Viewed, InProgress),
+        (Viewed, Rejected),
+        (Viewed, Spam),
+        (InProgress, Completed),
+        (InProgress, Rejected),
+        (Spam, New),
+    ];
+
+    #[test]
+    fn allows_only_listed_transitions() {
+        for role in [AdminRole::Manager, AdminRole::Expert, AdminRole::ReadOnly] {
+            for from in SubmissionStatus::ALL {
+                for to in SubmissionStatus::ALL {
+                    assert_eq!(
+                        from.can_transition_to(to, role),
+                        ALLOWED.contains(&(from, to)),
+                        "{} -> {} for {:?}",
+                        from,
+                        to,
+                        role,
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn terminal_statuses_stay_terminal() {
+        for from in [Completed, Rejected] {
+            assert!(from.next_statuses().is_empty());
+            for to in SubmissionStatus::ALL {
+                assert!(!from.can_transition_to(to, AdminRole::Manager), "{} -> {}", from, to);
+            }
+        }
+    }
+
+    #[test]
+    fn owner_can_move_to_any_other_status() {
+        for from in SubmissionStatus::ALL {
+            for to in SubmissionStatus::ALL {
+                assert_eq!(from.can_transition_to(to, AdminRole::Owner), from != to, "{} -> {}", from, to);
+            }
+        }
+    }
+
+    #[test]
+    fn parses_status_names() {
+        for status in SubmissionStatus::ALL {
+            assert_eq!(status.as_str().parse::<SubmissionStatus>(), Ok(status));
+        }
+        assert!("done".parse::<SubmissionStatus>().is_err());
+    }
+}
@@ -204,6 +204,14 @@ export class AddClientModal {
   }
 
   onSaveError(error) {
+    // Ошибки валидации приходят списком по полям
+    const fieldErrors = error?.body?.details;
+    if (Array.isArray(fieldErrors) && fieldErrors.length > 0) {
+      fieldErrors.forEach(fieldError => notificationService.error(fieldError.message));
+      this.focusField(fieldErrors[0].field === 'message' ? 'comment' : fieldErrors[0].field);
+      return;
+    }
+
     notificationService.error(error?.message || 'Ошибка при сохранении клиента');
   }
 
@@ -0,0 +1,200 @@
+// components/AdminUsersPanel.js
+import { apiService } from '../services/apiService.js';
+import { notificationService } from '../services/notificationService.js';
+import { DateUtils, DomUtils } from '../utils/index.js';
+import { ROLE_LABELS } from '../config/constants.js';
+
+export class AdminUsersPanel {
+  constructor() {
+    this.card = document.getElementById('adminUsersCard');
+    this.tableBody = document.getElementById('adminUsersTable');
+    this.form = document.getElementById('createAdminForm');
+    this.navItems = document.querySelectorAll('.nav-item[data-section]');
+    this.admins = [];
+
+    if (!this.card || !this.tableBody || !this.form) {
+      throw new Error('Admin users panel elements not found');
+    }
+
+    this.bindEvents();
+  }
+
+  bindEvents() {
+    this.navItems.forEach(item => {
+      item.addEventListener('click', () => this.showSection(item.dataset.section));
+    });
+    this.form.addEventListener('submit', this.handleCreate.bind(this));
+    this.tableBody.addEventListener('click', this.handleTableClick.bind(this));
+    this.tableBody.addEventListener('change', this.handleRoleChange.bind(this));
+  }
+
+  // Карточки разделов помечены data-section, все остальные относятся к заявкам
+  showSection(section) {
+    this.navItems.forEach(item => {
+      item.classList.toggle('active', item.dataset.section === section);
+    });
+    document.querySelectorAll('.stats-container, .submissions-card').forEach(el => {
+      el.style.display = (el.dataset.section || 'submissions') === section ? '' : 'none';
+    });
+
+    if (section === 'admins') {
+      this.load();
+    }
+  }
+
+  async load() {
+    try {
+      this.admins = await apiService.fetchAdmins();
+      this.render();
+    } catch (error) {
+      console.error('Error loading admins:', error);
+      notificationService.error('Не удалось загрузить список администраторов');
+    }
+  }
+
+  render() {
+    DomUtils.removeAllChildren(this.tableBody);
+
+    if (!this.admins || this.admins.length === 0) {
+      const row = DomUtils.createElement('tr');
+      row.innerHTML = '<td colspan="6" style="text-align: center; padding: 2rem;">Нет данных для отображения</td>';
+      this.tableBody.appendChild(row);
+      return;
+    }
+
+    this.admins.forEach(admin => this.tableBody.appendChild(this.createRow(admin)));
+  }
+
+  createRow(admin) {
+    const row = DomUtils.createElement('tr');
+    row.dataset.id = admin.id;
+
+    const roleOptions = Object.entries(ROLE_LABELS)
+      .map(([value, label]) => `<option value="${value}" ${value === admin.role ? 'selected' : ''}>${label}</option>`)
+      .join('');
+
+    row.innerHTML = `
+      <td></td>
+      <td><select class="status-select role-select" data-id="${admin.id}">${roleOptions}</select></td>
+      <td>${admin.is_active ? 'Активен' : 'Отключен'}</td>
+      <td>
+        <span class="admin-email"></span>
+        <label title="Получать письма о новых заявках">
+          <input type="checkbox" class="on-duty-toggle" data-id="${admin.id}" ${admin.on_duty ? 'checked' : ''}> дежурный
+        </label>
+      </td>
+      <td>${admin.last_login_at ? DateUtils.formatDate(admin.last_login_at) : '—'}</td>
+      <td>
+        <button class="btn btn-outline toggle-active-btn" data-id="${admin.id}" data-active="${admin.is_active}">
+          <i class="fas ${admin.is_active ? 'fa-user-slash' : 'fa-user-check'}"></i>
+        </button>
+        <button class="btn btn-outline reset-password-btn" data-id="${admin.id}"><i class="fas fa-key"></i></button>
+        <button class="btn btn-outline edit-email-btn" data-id="${admin.id}"><i class="fas fa-envelope"></i></button>
+        <button class="btn btn-outline edit-telegram-btn" data-id="${admin.id}" title="${admin.telegram_user_id ? `Telegram: ${admin.telegram_user_id}` : 'Telegram не привязан'}"><i class="fab fa-telegram-plane"></i></button>
+        <button class="btn btn-outline delete-admin-btn" data-id="${admin.id}"><i class="fas fa-trash"></i></button>
+      </td>
+    `;
+    // Логин выводится как текст, чтобы исключить XSS
+    row.firstElementChild.textContent = admin.username;
+    row.querySelector('.admin-email').textContent = admin.email || 'почта не указана';
+    return row;
+  }
+
+  async handleCreate(event) {
+    event.preventDefault();
+    const formData = new FormData(this.form);
+
+    try {
+      await apiService.createAdmin({
+        username: formData.get('username')?.trim() || '',
+        password: formData.get('password') || '',
+        role: formData.get('role')
+      });
+      this.form.reset();
+      notificationService.success('Администратор добавлен');
+      await this.load();
+    } catch (error) {
+      this.showError(error, 'Не удалось добавить администратора');
+    }
+  }
+
+  async handleTableClick(event) {
+    const button = event.target.closest('button');
+    if (!button) return;
+    const adminId = button.dataset.id;
+
+    try {
+      if (button.classList.contains('toggle-active-btn')) {
+        await apiService.setAdminActive(adminId, button.dataset.active !== 'true');
+        notificationService.success('Статус администратора обновлен');
+      } else if (button.classList.contains('reset-password-btn')) {
+        const password = window.prompt('Временный пароль (минимум 12 символов):');
+        if (!password) return;
+        await apiService.resetAdminPassword(adminId, password);
+        notificationService.success('Пароль изменен');
+      } else if (button.classList.contains('edit-email-btn')) {
+        const admin = this.admins.find(a => a.id === adminId);
+        const email = window.prompt('Email для уведомлений (пусто — удалить):', admin?.email || '');
+        if (email === null) return;
+        await apiService.setAdminMail(adminId, { email: email.trim() || null, onDuty: Boolean(admin?.on_duty) && Boolean(email.trim()) });
+        notificationService.success('Почта обновлена');
+      } else if (button.classList.contains('edit-telegram-btn')) {
+        const admin = this.admins.find(a => a.id === adminId);
+        const value = window.prompt('Числовой id пользователя Telegram (пусто — отвязать):', admin?.telegram_user_id ?? '');
+        if (value === null) return;
+        const telegramUserId = value.trim() ? Number(value.trim()) : null;
+        if (telegramUserId !== null && !Number.isSafeInteger(telegramUserId)) {
+          notificationService.error('Некорректный id пользователя Telegram');
+          return;
+        }
+        await apiService.setAdminTelegram(adminId, telegramUserId);
+        notificationService.success('Telegram обновлен');
+      } else if (button.classList.contains('delete-admin-btn')) {
+        if (!window.confirm('Удалить администратора?')) return;
+        await apiService.deleteAdmin(adminId);
+        notificationService.success('Администратор удален');
+      } else {
+        return;
+      }
+      await this.load();
+    } catch (error) {
+      this.showError(error, 'Операция не выполнена');
+    }
+  }
+
+  async handleRoleChange(event) {
+    const toggle = event.target.closest('.on-duty-toggle');
+    if (toggle) {
+      await this.handleOnDutyChange(toggle);
+      return;
+    }
+
+    const select = event.target.closest('.role-select');
+    if (!select) return;
+
+    try {
+      await apiService.setAdminRole(select.dataset.id, select.value);
+      notificationService.success('Роль обновлена');
+    } catch (error) {
+      this.showError(error, 'Не удалось изменить роль');
+    }
+    await this.load();
+  }
+
+  async handleOnDutyChange(toggle) {
+    const admin = this.admins.find(a => a.id === toggle.dataset.id);
+
+    try {
+      await apiService.setAdminMail(toggle.dataset.id, { email: admin?.email || null, onDuty: toggle.checked });
+      notificationService.success(toggle.checked ? 'Дежурство включено' : 'Дежурство выключено');
+    } catch (error) {
+      this.showError(error, 'Не удалось изменить дежурство');
+    }
+    await this.load();
+  }
+
+  showError(error, fallback) {
+    console.error(fallback, error);
+    notificationService.error(error?.body?.message || fallback);
+  }
+}
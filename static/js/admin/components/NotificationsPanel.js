@@ -0,0 +1,230 @@
+// components/NotificationsPanel.js
+import { apiService } from '../services/apiService.js';
+import { notificationService } from '../services/notificationService.js';
+import { EventBus } from '../utils/eventBus.js';
+import { DateUtils, DomUtils } from '../utils/index.js';
+import { NOTIFICATION_KIND_LABELS } from '../config/constants.js';
+
+export class NotificationsPanel {
+  constructor() {
+    this.card = document.getElementById('notificationsCard');
+    this.list = document.getElementById('notificationsList');
+    this.badge = document.getElementById('notificationsBadge');
+    this.unreadToggle = document.getElementById('unreadOnlyToggle');
+    this.markAllBtn = document.getElementById('markAllReadBtn');
+    this.moreBtn = document.getElementById('notificationsMoreBtn');
+    this.preferencesTable = document.getElementById('notificationPreferencesTable');
+    this.navItem = document.querySelector('.nav-item[data-section="notifications"]');
+
+    this.notifications = [];
+    this.page = 1;
+    this.hasNext = false;
+    this.unreadOnly = false;
+
+    if (!this.card || !this.list || !this.preferencesTable) {
+      throw new Error('Notifications panel elements not found');
+    }
+
+    this._boundListClick = this.handleListClick.bind(this);
+    this._boundPreferenceChange = this.handlePreferenceChange.bind(this);
+
+    this.bindEvents();
+  }
+
+  bindEvents() {
+    if (this.navItem) {
+      this.navItem.addEventListener('click', () => this.load());
+    }
+    this.list.addEventListener('click', this._boundListClick);
+    this.preferencesTable.addEventListener('change', this._boundPreferenceChange);
+    this.unreadToggle?.addEventListener('change', () => {
+      this.unreadOnly = this.unreadToggle.checked;
+      this.load();
+    });
+    this.markAllBtn?.addEventListener('click', () => this.markAllRead());
+    this.moreBtn?.addEventListener('click', () => this.loadPage(this.page + 1));
+  }
+
+  isVisible() {
+    return this.card.style.display !== 'none';
+  }
+
+  async load() {
+    await Promise.all([this.loadPage(1), this.loadPreferences()]);
+  }
+
+  async loadPage(page) {
+    try {
+      const result = await apiService.fetchNotifications(page, { unreadOnly: this.unreadOnly });
+      const data = Array.isArray(result?.data) ? result.data : [];
+
+      this.notifications = page === 1 ? data : this.notifications.concat(data);
+      this.page = page;
+      this.hasNext = Boolean(result?.has_next);
+      this.setUnreadCount(result?.unread_count ?? 0);
+      this.render();
+    } catch (error) {
+      console.error('Error loading notifications:', error);
+      notificationService.error('Не удалось загрузить уведомления');
+    }
+  }
+
+  // Счетчик на пункте меню. Открытый список перечитывается целиком
+  async refreshUnreadCount() {
+    if (this.isVisible()) {
+      await this.loadPage(1);
+      return;
+    }
+    try {
+      const result = await apiService.fetchNotifications(1, { unreadOnly: true });
+      this.setUnreadCount(result?.unread_count ?? 0);
+    } catch (error) {
+      console.error('Error loading unread notifications count:', error);
+    }
+  }
+
+  setUnreadCount(count) {
+    if (!this.badge) return;
+    this.badge.textContent = count > 99 ? '99+' : String(count);
+    this.badge.hidden = count === 0;
+  }
+
+  render() {
+    DomUtils.removeAllChildren(this.list);
+    if (this.moreBtn) this.moreBtn.hidden = !this.hasNext;
+
+    if (this.notifications.length === 0) {
+      const empty = DomUtils.createElement('div', 'notification-empty');
+      empty.textContent = this.unreadOnly ? 'Непрочитанных уведомлений нет' : 'Уведомлений пока нет';
+      this.list.appendChild(empty);
+      return;
+    }
+
+    this.notifications.forEach(notification => this.list.appendChild(this.createItem(notification)));
+  }
+
+  createItem(notification) {
+    const item = DomUtils.createElement('div', 'notification-item');
+    item.dataset.id = notification.id;
+    item.classList.toggle('unread', !notification.read_at);
+
+    const header = DomUtils.createElement('div', 'admin-comment-header');
+    const title = DomUtils.createElement('span', 'admin-comment-author');
+    title.textContent = NOTIFICATION_KIND_LABELS[notification.kind] || notification.kind;
+    const date = DomUtils.createElement('span', 'admin-comment-date');
+    date.textContent = DateUtils.formatDate(notification.created_at);
+    header.appendChild(title);
+    header.appendChild(date);
+
+    // Текст выводится через textContent, чтобы исключить XSS
+    const body = DomUtils.createElement('div', 'admin-comment-text');
+    body.textContent = notification.message || '';
+
+    const footer = DomUtils.createElement('div', 'admin-comment-footer');
+    if (notification.submission_id) {
+      const open = DomUtils.createElement('button', 'comment-action');
+      open.dataset.action = 'open';
+      open.dataset.submissionId = notification.submission_id;
+      open.textContent = `Заявка: ${notification.submission_name || notification.submission_id}`;
+      footer.appendChild(open);
+    }
+    const toggle = DomUtils.createElement('button', 'comment-action');
+    toggle.dataset.action = notification.read_at ? 'unread' : 'read';
+    toggle.textContent = notification.read_at ? 'Отметить непрочитанным' : 'Отметить прочитанным';
+    footer.appendChild(toggle);
+
+    item.appendChild(header);
+    item.appendChild(body);
+    item.appendChild(footer);
+    return item;
+  }
+
+  async handleListClick(event) {
+    const button = event.target.closest('.comment-action');
+    if (!button) return;
+    const id = button.closest('.notification-item')?.dataset.id;
+    const notification = this.notifications.find(n => n.id === id);
+    if (!notification) return;
+
+    switch (button.dataset.action) {
+      case 'open':
+        EventBus.emit('submission:view', button.dataset.submissionId);
+        if (!notification.read_at) await this.setRead(notification, true);
+        break;
+      case 'read':
+        await this.setRead(notification, true);
+        break;
+      case 'unread':
+        await this.setRead(notification, false);
+        break;
+    }
+  }
+
+  async setRead(notification, read) {
+    try {
+      await apiService.setNotificationRead(notification.id, read);
+      await this.loadPage(1);
+    } catch (error) {
+      console.error('Error updating notification:', error);
+      notificationService.error('Не удалось обновить уведомление');
+    }
+  }
+
+  async markAllRead() {
+    try {
+      await apiService.markAllNotificationsRead();
+      await this.loadPage(1);
+    } catch (error) {
+      console.error('Error marking notifications read:', error);
+      notificationService.error('Не удалось отметить уведомления');
+    }
+  }
+
+  async loadPreferences() {
+    try {
+      this.renderPreferences(await apiService.fetchNotificationPreferences());
+    } catch (error) {
+      console.error('Error loading notification preferences:', error);
+    }
+  }
+
+  renderPreferences(preferences) {
+    DomUtils.removeAllChildren(this.preferencesTable);
+
+    (Array.isArray(preferences) ? preferences : []).forEach(preference => {
+      const row = DomUtils.createElement('tr');
+      row.dataset.kind = preference.kind;
+      row.innerHTML = `
+        <td></td>
+        <td><input type="checkbox" data-channel="inbox" ${preference.inbox ? 'checked' : ''}></td>
+        <td><input type="checkbox" data-channel="email" ${preference.email ? 'checked' : ''}></td>
+      `;
+      row.firstElementChild.textContent = NOTIFICATION_KIND_LABELS[preference.kind] || preference.kind;
+      this.preferencesTable.appendChild(row);
+    });
+  }
+
+  async handlePreferenceChange(event) {
+    const row = event.target.closest('tr[data-kind]');
+    if (!row) return;
+
+    const preference = {
+      kind: row.dataset.kind,
+      inbox: row.querySelector('[data-channel="inbox"]').checked,
+      email: row.querySelector('[data-channel="email"]').checked
+    };
+    try {
+      this.renderPreferences(await apiService.updateNotificationPreferences([preference]));
+      notificationService.success('Настройки уведомлений сохранены');
+    } catch (error) {
+      console.error('Error saving notification preferences:', error);
+      notificationService.error('Не удалось сохранить настройки');
+      await this.loadPreferences();
+    }
+  }
+
+  destroy() {
+    this.list.removeEventListener('click', this._boundListClick);
+    this.preferencesTable.removeEventListener('change', this._boundPreferenceChange);
+  }
+}
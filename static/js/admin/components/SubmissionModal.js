@@ -1,5 +1,5 @@
 // static/js/admin/components/SubmissionModal.js
-import { DateUtils, ClipboardUtils } from '../utils/index.js';
+import { DateUtils, ClipboardUtils, DomUtils, PriceUtils } from '../utils/index.js';
 import { EventBus } from '../utils/eventBus.js';
 import { notificationService } from '../services/notificationService.js';
 import { apiService } from '../services/apiService.js';
@@ -9,6 +9,9 @@ export class SubmissionModal {
     this.modal = document.getElementById('messageModal');
     this.currentSubmission = null;
     this.comments = [];
+    this.commentsPage = 1;
+    this.commentsHasNext = false;
+    this.replyTo = null;
 
     this.elements = {
       name: document.getElementById('modal-name'),
@@ -23,7 +26,27 @@ export class SubmissionModal {
       // блок комментариев
       adminInput: document.getElementById('adminCommentInput'),
       saveBtn: document.getElementById('saveAdminCommentBtn'),
-      commentsList: document.getElementById('adminCommentsList')
+      commentsList: document.getElementById('adminCommentsList'),
+      replyTarget: document.getElementById('commentReplyTarget'),
+
+      // оценка стоимости
+      quoteCurrent: document.getElementById('quoteCurrent'),
+      quotePrice: document.getElementById('quotePriceInput'),
+      quoteCurrency: document.getElementById('quoteCurrencySelect'),
+      quoteNote: document.getElementById('quoteNoteInput'),
+      quoteSaveBtn: document.getElementById('saveQuoteBtn'),
+      quoteDocumentLink: document.getElementById('quoteDocumentLink'),
+      quoteHistory: document.getElementById('quoteHistoryList'),
+
+      // срок выполнения
+      dueDate: document.getElementById('dueDateInput'),
+      dueDateSaveBtn: document.getElementById('saveDueDateBtn'),
+      dueDateClearBtn: document.getElementById('clearDueDateBtn'),
+
+      // вложения
+      attachmentsList: document.getElementById('attachmentsList'),
+      attachmentInput: document.getElementById('attachmentInput'),
+      attachmentUploadBtn: document.getElementById('uploadAttachmentsBtn')
     };
 
     if (!this.modal) {
@@ -37,6 +60,11 @@ export class SubmissionModal {
     this._boundStatusChange = this.handleStatusChange.bind(this);
     this._boundSave = this.saveAdminComment.bind(this);
     this._boundKeydown = this._onAdminInputKeydown.bind(this);
+    this._boundCommentAction = this.handleCommentAction.bind(this);
+    this._boundSaveQuote = this.saveQuote.bind(this);
+    this._boundSaveDueDate = () => this.saveDueDate(this.elements.dueDate?.value || null);
+    this._boundClearDueDate = () => this.saveDueDate(null);
+    this._boundUploadAttachments = this.uploadAttachments.bind(this);
 
     this.init();
   }
@@ -71,6 +99,24 @@ export class SubmissionModal {
     if (this.elements.adminInput) {
       this.elements.adminInput.addEventListener('keydown', this._boundKeydown);
     }
+    if (this.elements.commentsList) {
+      this.elements.commentsList.addEventListener('click', this._boundCommentAction);
+    }
+
+    if (this.elements.quoteSaveBtn) {
+      this.elements.quoteSaveBtn.addEventListener('click', this._boundSaveQuote);
+    }
+
+    if (this.elements.dueDateSaveBtn) {
+      this.elements.dueDateSaveBtn.addEventListener('click', this._boundSaveDueDate);
+    }
+    if (this.elements.dueDateClearBtn) {
+      this.elements.dueDateClearBtn.addEventListener('click', this._boundClearDueDate);
+    }
+
+    if (this.elements.attachmentUploadBtn) {
+      this.elements.attachmentUploadBtn.addEventListener('click', this._boundUploadAttachments);
+    }
   }
 
   handleOutsideClick(event) {
@@ -113,49 +159,45 @@ export class SubmissionModal {
 
   /**
    * Нормализуем серверный формат:
-   * { comment_id, comment, created_at, admin_name } => { id, text, author, created_at }
+   * { comment_id, parent_id, comment, admin_name, is_own, ... } => { id, parentId, text, author, ... }
    */
   _normalizeComment(raw) {
     if (!raw) return null;
     return {
-      id: raw.comment_id || raw.id || `local-${Date.now()}`,
-      text: raw.comment ?? raw.comment_text ?? raw.text ?? '',
-      author: raw.admin_name ?? raw.author ?? 'admin',
-      created_at: raw.created_at ?? raw.createdAt ?? new Date().toISOString()
+      id: raw.comment_id,
+      parentId: raw.parent_id ?? null,
+      text: raw.comment ?? '',
+      author: raw.admin_name ?? 'admin',
+      isOwn: Boolean(raw.is_own),
+      created_at: raw.created_at,
+      edited_at: raw.edited_at ?? null,
+      deleted: Boolean(raw.deleted_at),
+      replies: Array.isArray(raw.replies) ? raw.replies.map(r => this._normalizeComment(r)) : []
     };
   }
 
-  // Загрузка комментариев с сервера (сервер всегда возвращает { data: [...] })
-  async loadComments() {
+  // Загрузка веток комментариев. Следующие страницы дописываются в конец списка
+  async loadComments(page = 1) {
     if (!this.currentSubmission) return;
 
     const submissionId = this.currentSubmission.submission_id;
-    this.renderCommentsLoading();
+    if (page === 1) this.renderCommentsLoading();
 
     try {
-      const res = await apiService.fetchAdminComments(submissionId);
-      // Для дебага оставим в консоли raw ответ
-      console.debug('[SubmissionModal] raw comments response', res);
-
-      let rawList = [];
-      if (!res) {
-        rawList = [];
-      } else if (Array.isArray(res)) {
-        rawList = res;
-      } else if (res.data && Array.isArray(res.data)) {
-        rawList = res.data;
-      } else if (res.comments && Array.isArray(res.comments)) {
-        rawList = res.comments;
-      } else {
-        rawList = [];
-      }
+      const res = await apiService.fetchAdminComments(submissionId, page);
+      if (!this.currentSubmission || this.currentSubmission.submission_id !== submissionId) return;
 
-      this.comments = rawList.map(r => this._normalizeComment(r)).filter(Boolean);
+      const threads = (res?.data || []).map(r => this._normalizeComment(r)).filter(Boolean);
+      this.comments = page === 1 ? threads : this.comments.concat(threads);
+      this.commentsPage = res?.page || page;
+      this.commentsHasNext = Boolean(res?.has_next);
       this.renderComments();
     } catch (err) {
       console.error('Failed to load comments', err);
-      this.comments = [];
-      this.renderCommentsError();
+      if (page === 1) {
+        this.comments = [];
+        this.renderCommentsError();
+      }
       notificationService.error('Не удалось загрузить комментарии');
     }
   }
@@ -179,47 +221,24 @@ export class SubmissionModal {
     saveBtn.textContent = 'Сохранение...';
 
     try {
-      // Сервер извлекает admin_id из cookie, отправляем только submissions_id и text
-      const res = await apiService.addAdminComment(this.currentSubmission.submission_id, text);
-
-      console.debug('[SubmissionModal] raw add comment response', res);
-
-      // Сервер может вернуть { data: [...] } или null (201 created without body)
-      let createdRaw = null;
-      if (!res) {
-        createdRaw = null;
-      } else if (res.data && Array.isArray(res.data) && res.data.length > 0) {
-        // иногда create возвращает массив data
-        createdRaw = res.data[0];
-      } else if (res.data && typeof res.data === 'object' && res.data.comment_id) {
-        createdRaw = res.data;
-      } else if (res.comment) {
-        createdRaw = res.comment;
-      } else if (res.id || res.comment_id) {
-        createdRaw = res;
-      }
-
-      const newComment = createdRaw ? this._normalizeComment(createdRaw) : {
-        id: `local-${Date.now()}`,
-        text,
-        author: 'admin',
-        created_at: new Date().toISOString()
-      };
-
-      // Добавляем в локальный список и рендерим
-      this.comments.unshift(newComment);
-      this.renderComments();
+      const submissionId = this.currentSubmission.submission_id;
+      const res = await apiService.addAdminComment(submissionId, text, this.replyTo?.id ?? null);
 
       inputEl.value = '';
-      notificationService.success('Комментарий сохранён');
+      this.setReplyTarget(null);
+      const mentioned = res?.mentioned || [];
+      notificationService.success(mentioned.length
+        ? `Комментарий сохранён, уведомлены: ${mentioned.join(', ')}`
+        : 'Комментарий сохранён');
 
       EventBus.emit('submission:comment-added', {
-        submissionId: this.currentSubmission.submission_id,
-        comment: newComment
+        submissionId,
+        commentId: res?.comment_id
       });
+      await this.loadComments();
     } catch (err) {
       console.error('saveAdminComment error', err);
-      notificationService.error('Ошибка при сохранении комментария');
+      notificationService.error(err.body?.message || 'Ошибка при сохранении комментария');
     } finally {
       // Восстанавливаем кнопку
       saveBtn.disabled = false;
@@ -228,13 +247,359 @@ export class SubmissionModal {
     }
   }
 
-  // Отрисовка списка комментариев
+  _findComment(id) {
+    for (const thread of this.comments) {
+      if (thread.id === id) return thread;
+      const reply = thread.replies.find(r => r.id === id);
+      if (reply) return reply;
+    }
+    return null;
+  }
+
+  // Действия с комментарием через делегирование: data-action на кнопках
+  async handleCommentAction(event) {
+    const button = event.target.closest('[data-action]');
+    if (!button || !this.elements.commentsList.contains(button)) return;
+
+    const { action, commentId } = button.dataset;
+    if (action === 'more') {
+      button.disabled = true;
+      await this.loadComments(this.commentsPage + 1);
+      return;
+    }
+
+    const comment = this._findComment(commentId);
+    if (!comment) return;
+
+    switch (action) {
+      case 'reply':
+        this.setReplyTarget(comment);
+        this.elements.adminInput?.focus();
+        break;
+      case 'edit':
+        this.startCommentEdit(button.closest('.admin-comment-item'), comment);
+        break;
+      case 'delete':
+        await this.deleteComment(comment);
+        break;
+      case 'edits':
+        await this.toggleCommentEdits(button.closest('.admin-comment-item'), comment);
+        break;
+    }
+  }
+
+  setReplyTarget(comment) {
+    this.replyTo = comment;
+    const target = this.elements.replyTarget;
+    if (!target) return;
+
+    DomUtils.removeAllChildren(target);
+    target.hidden = !comment;
+    if (!comment) return;
+
+    target.appendChild(document.createTextNode(`Ответ для ${comment.author} `));
+    const cancel = document.createElement('button');
+    cancel.type = 'button';
+    cancel.className = 'comment-action';
+    cancel.textContent = 'отменить';
+    cancel.addEventListener('click', () => this.setReplyTarget(null));
+    target.appendChild(cancel);
+  }
+
+  startCommentEdit(item, comment) {
+    const body = item?.querySelector(':scope > .admin-comment-body');
+    if (!body || item.querySelector(':scope > .admin-comment-edit')) return;
+
+    const form = document.createElement('div');
+    form.className = 'admin-comment-edit';
+
+    const input = document.createElement('textarea');
+    input.className = 'admin-comment-input';
+    input.value = comment.text;
+
+    const save = document.createElement('button');
+    save.className = 'btn btn-primary';
+    save.textContent = 'Сохранить';
+
+    const cancel = document.createElement('button');
+    cancel.className = 'btn btn-outline';
+    cancel.textContent = 'Отмена';
+
+    const restore = () => {
+      form.remove();
+      body.hidden = false;
+    };
+    cancel.addEventListener('click', restore);
+    save.addEventListener('click', async () => {
+      const text = input.value.trim();
+      if (!text) {
+        notificationService.error('Комментарий не может быть пустым');
+        return;
+      }
+      save.disabled = true;
+      try {
+        await apiService.updateAdminComment(comment.id, text);
+        comment.text = text;
+        comment.edited_at = new Date().toISOString();
+        this.renderComments();
+        notificationService.success('Комментарий изменён');
+      } catch (err) {
+        console.error('updateAdminComment error', err);
+        notificationService.error(err.body?.message || 'Ошибка при изменении комментария');
+        save.disabled = false;
+      }
+    });
+
+    const actions = document.createElement('div');
+    actions.className = 'admin-comment-actions';
+    actions.appendChild(cancel);
+    actions.appendChild(save);
+
+    form.appendChild(input);
+    form.appendChild(actions);
+    body.hidden = true;
+    body.after(form);
+    input.focus();
+  }
+
+  async deleteComment(comment) {
+    if (!window.confirm('Удалить комментарий?')) return;
+
+    try {
+      await apiService.deleteAdminComment(comment.id);
+      comment.deleted = true;
+      comment.text = '';
+      if (this.replyTo?.id === comment.id) this.setReplyTarget(null);
+      this.renderComments();
+      notificationService.success('Комментарий удалён');
+    } catch (err) {
+      console.error('deleteAdminComment error', err);
+      notificationService.error(err.body?.message || 'Ошибка при удалении комментария');
+    }
+  }
+
+  // Предыдущие версии комментария под его текстом, повторный клик скрывает
+  async toggleCommentEdits(item, comment) {
+    const existing = item?.querySelector(':scope > .admin-comment-edits');
+    if (existing) {
+      existing.remove();
+      return;
+    }
+
+    try {
+      const edits = await apiService.fetchCommentEdits(comment.id);
+      const list = document.createElement('div');
+      list.className = 'admin-comment-edits';
+      (Array.isArray(edits) ? edits : []).forEach(edit => {
+        const entry = document.createElement('div');
+        entry.textContent = `${DateUtils.formatDate(edit.edited_at)}: ${edit.previous_comment}`;
+        list.appendChild(entry);
+      });
+      item.querySelector(':scope > .admin-comment-body')?.after(list);
+    } catch (err) {
+      console.error('fetchCommentEdits error', err);
+      notificationService.error('Не удалось загрузить историю правок');
+    }
+  }
+
+  async saveQuote() {
+    if (!this.currentSubmission) return;
+
+    const price = PriceUtils.toMinorUnits(this.elements.quotePrice?.value ?? '');
+    if (!Number.isFinite(price) || price < 0) {
+      notificationService.error('Укажите корректную сумму');
+      return;
+    }
+    const currency = this.elements.quoteCurrency?.value || 'RUB';
+    const note = (this.elements.quoteNote?.value || '').trim() || null;
+
+    const saveBtn = this.elements.quoteSaveBtn;
+    saveBtn.disabled = true;
+
+    try {
+      const submissionId = this.currentSubmission.submission_id;
+      await apiService.updateSubmissionQuote(submissionId, { price, currency, note });
+
+      const quote = { price, currency, quoted_at: new Date().toISOString() };
+      Object.assign(this.currentSubmission, quote);
+      this.renderQuote(this.currentSubmission);
+      if (this.elements.quoteNote) this.elements.quoteNote.value = '';
+      notificationService.success('Оценка сохранена');
+
+      EventBus.emit('submission:quote-updated', { submissionId, quote });
+      await this.loadQuoteHistory();
+    } catch (err) {
+      console.error('saveQuote error', err);
+      notificationService.error(err.body?.message || 'Ошибка при сохранении оценки');
+    } finally {
+      saveBtn.disabled = false;
+    }
+  }
+
+  // value — значение поля datetime-local в местном времени, null снимает срок
+  async saveDueDate(value) {
+    if (!this.currentSubmission) return;
+
+    const dueAt = value ? new Date(value) : null;
+    if (dueAt && Number.isNaN(dueAt.getTime())) {
+      notificationService.error('Укажите корректную дату');
+      return;
+    }
+
+    try {
+      const submissionId = this.currentSubmission.submission_id;
+      await apiService.setSubmissionDueDate(submissionId, dueAt ? dueAt.toISOString() : null);
+
+      this.currentSubmission.due_at = dueAt ? dueAt.toISOString() : null;
+      this.renderDueDate(this.currentSubmission);
+      notificationService.success(dueAt ? 'Срок сохранен' : 'Срок снят');
+    } catch (err) {
+      console.error('saveDueDate error', err);
+      notificationService.error(err.body?.message || 'Ошибка при сохранении срока');
+    }
+  }
+
+  renderDueDate(submission) {
+    if (!this.elements.dueDate) return;
+    if (!submission.due_at) {
+      this.elements.dueDate.value = '';
+      return;
+    }
+    // Поле datetime-local ждет местное время без часового пояса
+    const date = new Date(submission.due_at);
+    const local = new Date(date.getTime() - date.getTimezoneOffset() * 60000);
+    this.elements.dueDate.value = local.toISOString().slice(0, 16);
+  }
+
+  async loadAttachments() {
+    if (!this.currentSubmission || !this.elements.attachmentsList) return;
+
+    const submissionId = this.currentSubmission.submission_id;
+    try {
+      const attachments = await apiService.fetchAttachments(submissionId);
+      // Окно могли закрыть или открыть другую заявку, пока шел запрос
+      if (this.currentSubmission?.submission_id !== submissionId) return;
+      this.renderAttachments(Array.isArray(attachments) ? attachments : []);
+    } catch (err) {
+      console.error('Failed to load attachments', err);
+    }
+  }
+
+  async uploadAttachments() {
+    const input = this.elements.attachmentInput;
+    if (!this.currentSubmission || !input || input.files.length === 0) {
+      notificationService.error('Выберите файлы');
+      return;
+    }
+
+    const uploadBtn = this.elements.attachmentUploadBtn;
+    uploadBtn.disabled = true;
+
+    try {
+      const attachments = await apiService.uploadAttachments(this.currentSubmission.submission_id, input.files);
+      input.value = '';
+      this.renderAttachments(Array.isArray(attachments) ? attachments : []);
+      notificationService.success('Файлы загружены');
+    } catch (err) {
+      console.error('uploadAttachments error', err);
+      notificationService.error(err.body?.message || 'Ошибка при загрузке файлов');
+    } finally {
+      uploadBtn.disabled = false;
+    }
+  }
+
+  renderAttachments(attachments) {
+    const container = this.elements.attachmentsList;
+    DomUtils.removeAllChildren(container);
+
+    if (attachments.length === 0) {
+      const empty = document.createElement('div');
+      empty.className = 'admin-comments-empty';
+      empty.textContent = 'Файлов нет';
+      container.appendChild(empty);
+      return;
+    }
+
+    attachments.forEach(attachment => {
+      const item = document.createElement('div');
+      item.className = 'attachment-item';
+
+      // Имя файла задает клиент, поэтому только textContent
+      const link = document.createElement('a');
+      link.href = apiService.attachmentUrl(attachment.id);
+      link.textContent = attachment.file_name;
+      link.download = attachment.file_name;
+
+      const meta = document.createElement('span');
+      meta.className = 'attachment-meta';
+      const source = attachment.source === 'client' ? 'клиент' : (attachment.uploaded_by_name || 'администратор');
+      meta.textContent = `${formatFileSize(attachment.size_bytes)} · ${source} · ${DateUtils.formatDate(attachment.created_at)}`;
+
+      item.appendChild(link);
+      item.appendChild(meta);
+      container.appendChild(item);
+    });
+  }
+
+  async loadQuoteHistory() {
+    const container = this.elements.quoteHistory;
+    if (!this.currentSubmission || !container) return;
+
+    try {
+      const history = await apiService.fetchQuoteHistory(this.currentSubmission.submission_id);
+      DomUtils.removeAllChildren(container);
+
+      (Array.isArray(history) ? history : []).forEach(entry => {
+        const item = document.createElement('div');
+        item.className = 'admin-comment-item';
+
+        const header = document.createElement('div');
+        header.className = 'admin-comment-header';
+        header.textContent = `${entry.admin_name || 'удаленный администратор'} · ${DateUtils.formatDate(entry.created_at)}`;
+
+        const body = document.createElement('div');
+        body.className = 'admin-comment-body';
+        const previous = entry.previous_price !== null
+          ? `${PriceUtils.format(entry.previous_price, entry.previous_currency)} → `
+          : '';
+        body.textContent = previous + PriceUtils.format(entry.price, entry.currency)
+          + (entry.note ? ` — ${entry.note}` : '');
+
+        item.appendChild(header);
+        item.appendChild(body);
+        container.appendChild(item);
+      });
+    } catch (err) {
+      console.error('Failed to load quote history', err);
+    }
+  }
+
+  renderQuote(submission) {
+    const quoted = Boolean(submission.quoted_at);
+
+    if (this.elements.quoteCurrent) {
+      this.elements.quoteCurrent.textContent = quoted
+        ? PriceUtils.format(submission.price, submission.currency)
+        : 'Без оценки';
+    }
+    if (this.elements.quotePrice) {
+      this.elements.quotePrice.value = quoted ? (submission.price / 100).toFixed(2).replace('.', ',') : '';
+    }
+    if (this.elements.quoteCurrency) {
+      this.elements.quoteCurrency.value = submission.currency || 'RUB';
+    }
+    if (this.elements.quoteDocumentLink) {
+      this.elements.quoteDocumentLink.href = apiService.quoteDocumentUrl(submission.submission_id);
+      this.elements.quoteDocumentLink.style.display = quoted ? '' : 'none';
+    }
+  }
+
+  // Отрисовка веток комментариев: ответы под корневым комментарием
   renderComments() {
     const container = this.elements.commentsList;
     if (!container) return;
 
-    // Очистка контейнера
-    while (container.firstChild) container.removeChild(container.firstChild);
+    DomUtils.removeAllChildren(container);
 
     if (!this.comments || this.comments.length === 0) {
       const empty = document.createElement('div');
@@ -244,36 +609,81 @@ export class SubmissionModal {
       return;
     }
 
-    this.comments.forEach(comment => {
-      const item = document.createElement('div');
-      item.className = 'admin-comment-item';
+    this.comments.forEach(thread => {
+      const item = this._renderComment(thread);
+      thread.replies.forEach(reply => {
+        const replyItem = this._renderComment(reply);
+        replyItem.classList.add('admin-comment-reply');
+        item.appendChild(replyItem);
+      });
+      container.appendChild(item);
+    });
 
-      const header = document.createElement('div');
-      header.className = 'admin-comment-header';
+    if (this.commentsHasNext) {
+      const more = document.createElement('button');
+      more.className = 'btn btn-outline admin-comments-more';
+      more.dataset.action = 'more';
+      more.textContent = 'Показать ещё';
+      container.appendChild(more);
+    }
+  }
 
-      const author = document.createElement('span');
-      author.className = 'admin-comment-author';
-      author.textContent = comment.author || 'admin';
+  _renderComment(comment) {
+    const item = document.createElement('div');
+    item.className = 'admin-comment-item';
 
-      const sep = document.createTextNode(' · ');
+    const header = document.createElement('div');
+    header.className = 'admin-comment-header';
 
-      const date = document.createElement('span');
-      date.className = 'admin-comment-date';
-      date.textContent = comment.created_at ? DateUtils.formatDate(comment.created_at) : '';
+    const author = document.createElement('span');
+    author.className = 'admin-comment-author';
+    author.textContent = comment.author;
 
-      header.appendChild(author);
-      header.appendChild(sep);
-      header.appendChild(date);
+    const date = document.createElement('span');
+    date.className = 'admin-comment-date';
+    date.textContent = comment.created_at ? DateUtils.formatDate(comment.created_at) : '';
 
-      const body = document.createElement('div');
-      body.className = 'admin-comment-body';
-      body.textContent = comment.text || '';
+    header.appendChild(author);
+    header.appendChild(document.createTextNode(' · '));
+    header.appendChild(date);
 
-      item.appendChild(header);
-      item.appendChild(body);
+    const body = document.createElement('div');
+    body.className = 'admin-comment-body';
+    if (comment.deleted) {
+      body.classList.add('admin-comment-deleted');
+      body.textContent = 'Комментарий удалён';
+    } else {
+      body.textContent = comment.text;
+    }
 
-      container.appendChild(item);
-    });
+    item.appendChild(header);
+    item.appendChild(body);
+
+    if (!comment.deleted) {
+      const actions = document.createElement('div');
+      actions.className = 'admin-comment-footer';
+      const addAction = (action, label) => {
+        const btn = document.createElement('button');
+        btn.type = 'button';
+        btn.className = 'comment-action';
+        btn.dataset.action = action;
+        btn.dataset.commentId = comment.id;
+        btn.textContent = label;
+        actions.appendChild(btn);
+      };
+
+      addAction('reply', 'Ответить');
+      if (comment.isOwn) {
+        addAction('edit', 'Изменить');
+        addAction('delete', 'Удалить');
+      }
+      if (comment.edited_at) {
+        addAction('edits', `изменён ${DateUtils.formatDate(comment.edited_at)}`);
+      }
+      item.appendChild(actions);
+    }
+
+    return item;
   }
 
   renderCommentsLoading() {
@@ -314,10 +724,14 @@ export class SubmissionModal {
     this.fillData(submission);
     this.show();
 
-    // Загружаем комментарии, не блокируя UI
+    // Загружаем комментарии и историю оценок, не блокируя UI
     this.loadComments().catch(() => {
       // ошибки уже обработаны в loadComments
     });
+    if (this.elements.quoteHistory) DomUtils.removeAllChildren(this.elements.quoteHistory);
+    this.loadQuoteHistory();
+    if (this.elements.attachmentsList) DomUtils.removeAllChildren(this.elements.attachmentsList);
+    this.loadAttachments();
   }
 
   fillData(submission) {
@@ -330,10 +744,13 @@ export class SubmissionModal {
     if (this.elements.uuid) this.elements.uuid.textContent = submission.submission_id;
     if (this.elements.message) this.elements.message.textContent = submission.message;
     if (this.elements.status) this.elements.status.value = submission.status;
+    this.renderQuote(submission);
+    this.renderDueDate(submission);
 
     if (this.elements.adminInput) this.elements.adminInput.value = '';
     if (this.elements.commentsList) this.elements.commentsList.innerHTML = '';
     this.comments = [];
+    this.setReplyTarget(null);
   }
 
   show() {
@@ -351,6 +768,23 @@ export class SubmissionModal {
     this.currentSubmission = null;
     this.modal.dataset.currentId = '';
     this.comments = [];
+    this.setReplyTarget(null);
+  }
+
+  // Новый комментарий другого администратора. Во время правки список
+  // не перерисовывается, чтобы не потерять введенный текст
+  refreshComments(submissionId) {
+    if (!this.currentSubmission || this.currentSubmission.submission_id !== submissionId) return;
+    if (this.elements.commentsList?.querySelector('.admin-comment-edit')) return;
+    this.loadComments();
+  }
+
+  // Смена статуса без перерисовки остальных блоков окна
+  setStatus(submissionId, status) {
+    if (this.currentSubmission && this.currentSubmission.submission_id === submissionId) {
+      this.currentSubmission.status = status;
+      if (this.elements.status) this.elements.status.value = status;
+    }
   }
 
   updateSubmission(submission) {
@@ -384,5 +818,28 @@ export class SubmissionModal {
     if (this.elements.adminInput) {
       this.elements.adminInput.removeEventListener('keydown', this._boundKeydown);
     }
+
+    if (this.elements.commentsList) {
+      this.elements.commentsList.removeEventListener('click', this._boundCommentAction);
+    }
+
+    if (this.elements.quoteSaveBtn) {
+      this.elements.quoteSaveBtn.removeEventListener('click', this._boundSaveQuote);
+    }
+    if (this.elements.dueDateSaveBtn) {
+      this.elements.dueDateSaveBtn.removeEventListener('click', this._boundSaveDueDate);
+    }
+    if (this.elements.dueDateClearBtn) {
+      this.elements.dueDateClearBtn.removeEventListener('click', this._boundClearDueDate);
+    }
+    if (this.elements.attachmentUploadBtn) {
+      this.elements.attachmentUploadBtn.removeEventListener('click', this._boundUploadAttachments);
+    }
   }
 }
+
+function formatFileSize(bytes) {
+  if (bytes < 1024) return `${bytes} Б`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} КБ`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} МБ`;
+}
@@ -1,4 +1,4 @@
-import { DateUtils, UserUtils, UuidUtils, ClipboardUtils, DomUtils } from '../utils/index.js';
+import { DateUtils, UserUtils, UuidUtils, ClipboardUtils, DomUtils, PriceUtils } from '../utils/index.js';
 import { STATUS_LABELS } from '../config/constants.js';
 import { EventBus } from '../utils/eventBus.js';
 import { notificationService } from '../services/notificationService.js';
@@ -98,6 +98,10 @@ export class SubmissionTable {
     const shortUuid = UuidUtils.shorten(submission.submission_id);
     const formattedDate = DateUtils.formatDate(submission.created_at);
     const statusText = STATUS_LABELS[submission.status] || submission.status;
+    const assigneeText = submission.assignee_name || 'Не назначена';
+    const priceText = submission.quoted_at
+      ? PriceUtils.format(submission.price, submission.currency)
+      : 'Без оценки';
 
     row.innerHTML = `
       <td>
@@ -120,6 +124,12 @@ export class SubmissionTable {
         <span class="status-badge status-${submission.status}">
           ${statusText}
         </span>
+        <div style="font-size: 13px; color: var(--secondary);">
+          <i class="fas fa-user-check"></i> ${assigneeText}
+        </div>
+        <div style="font-size: 13px; color: var(--secondary);">
+          <i class="fas fa-ruble-sign"></i> ${priceText}
+        </div>
       </td>
       <td>
         <button class="action-btn view-btn" data-id="${submission.submission_id}">
@@ -1,12 +1,18 @@
 export const CONFIG = {
   API: {
     BASE_URL: '/admin/api/v1',
+    CSRF_TOKEN_URL: '/api/v1/csrf-token',
+    LOGOUT_URL: '/admin/logout',
     ENDPOINTS: {
       SUBMISSIONS: '/dashboard-page',
       UPDATE_STATUS: '/update-submission-status',
       ADD_SUBMISSION: '/add-submissions',
-      ADD_COMMENTS: '/create-submissions-comment',
-      GET_COMMENTS: '/get-submissions-comment'
+      SUBMISSION: '/submissions',
+      COMMENTS: '/comments',
+      ATTACHMENTS: '/attachments',
+      EVENTS: '/events',
+      NOTIFICATIONS: '/notifications',
+      ADMINS: '/admins'
     }
   },
   PAGINATION: {
@@ -24,7 +30,8 @@ export const SUBMISSION_STATUSES = {
   VIEWED: 'viewed',
   IN_PROGRESS: 'in_progress',
   COMPLETED: 'completed',
-  REJECTED: 'rejected'
+  REJECTED: 'rejected',
+  SPAM: 'spam'
 };
 
 export const STATUS_LABELS = {
@@ -32,7 +39,29 @@ export const STATUS_LABELS = {
   [SUBMISSION_STATUSES.VIEWED]: 'Просмотрено',
   [SUBMISSION_STATUSES.IN_PROGRESS]: 'В работе',
   [SUBMISSION_STATUSES.COMPLETED]: 'Завершено',
-  [SUBMISSION_STATUSES.REJECTED]: 'Отклонено'
+  [SUBMISSION_STATUSES.REJECTED]: 'Отклонено',
+  [SUBMISSION_STATUSES.SPAM]: 'Спам'
+};
+
+export const ADMIN_ROLES = {
+  OWNER: 'owner',
+  MANAGER: 'manager',
+  EXPERT: 'expert',
+  READ_ONLY: 'read_only'
+};
+
+export const ROLE_LABELS = {
+  [ADMIN_ROLES.OWNER]: 'Владелец',
+  [ADMIN_ROLES.MANAGER]: 'Менеджер',
+  [ADMIN_ROLES.EXPERT]: 'Эксперт',
+  [ADMIN_ROLES.READ_ONLY]: 'Только просмотр'
+};
+
+export const NOTIFICATION_KIND_LABELS = {
+  assignment: 'Назначение заявки',
+  mention: 'Упоминание в комментарии',
+  deadline: 'Приближается срок',
+  status_change: 'Смена статуса моей заявки'
 };
 
 export const NOTIFICATION_TYPES = {
@@ -7,14 +7,21 @@ import { SubmissionModal } from '../components/SubmissionModal.js';
 import { AddClientModal } from '../components/AddClientModal.js';
 import { SearchBox } from '../components/SearchBox.js';
 import { StatsPanel } from '../components/StatsPanel.js';
+import { AdminUsersPanel } from '../components/AdminUsersPanel.js';
+import { NotificationsPanel } from '../components/NotificationsPanel.js';
+import { eventStream } from '../services/eventStream.js';
 import { EventBus } from '../utils/eventBus.js';
 import { CONFIG } from '../config/constants.js';
+import { debounce } from '../utils/index.js';
 
 export class AdminDashboardController {
   constructor() {
     this.isInitialized = false;
     this.components = {};
     this.currentSort = { sortBy: null, order: null };
+    this.includeSpam = false;
+    this.assignedToMe = false;
+    this.filters = {};
 
     this._logoutBtn = null;
     this._boundHandleLogout = this.handleLogout.bind(this);
@@ -45,6 +52,8 @@ export class AdminDashboardController {
       this.components.addClientModal = new AddClientModal();
       this.components.searchBox = new SearchBox();
       this.components.statsPanel = new StatsPanel();
+      this.components.adminUsersPanel = new AdminUsersPanel();
+      this.components.notificationsPanel = new NotificationsPanel();
       this.currentSort = { sortBy: null, order: null };
 
       console.log('All components initialized');
@@ -60,17 +69,50 @@ export class AdminDashboardController {
     submissionStore.on('loading:changed', this.handleLoadingChange.bind(this));
     submissionStore.on('submission:added', this.handleSubmissionAdded.bind(this));
     submissionStore.on('submission:updated', this.handleSubmissionUpdated.bind(this));
-    submissionStore.on('submissions:filtered', this.handleSubmissionsFiltered.bind(this));
 
     EventBus.on('pagination:prev', this.handlePageChange.bind(this));
     EventBus.on('pagination:next', this.handlePageChange.bind(this));
     EventBus.on('submission:view', this.handleSubmissionView.bind(this));
     EventBus.on('submission:status-change', this.handleStatusChange.bind(this));
+    EventBus.on('submission:quote-updated', this.handleQuoteUpdated.bind(this));
     EventBus.on('search:query', this.handleSearch.bind(this));
     EventBus.on('client:save', this.handleClientSave.bind(this));
     EventBus.on('table:sort', this.handleTableSort.bind(this));
     EventBus.on('api:error', this.handleApiError.bind(this));
 
+    // Изменения других администраторов и новые заявки с сайта
+    EventBus.on('live:submission_created', this.handleLiveSubmissionCreated.bind(this));
+    EventBus.on('live:status_changed', this.handleLiveStatusChanged.bind(this));
+    EventBus.on('live:assignment_changed', this.handleLiveAssignmentChanged.bind(this));
+    EventBus.on('live:comment_added', this.handleLiveCommentChanged.bind(this));
+    EventBus.on('live:comment_updated', this.handleLiveCommentChanged.bind(this));
+    EventBus.on('live:comment_deleted', this.handleLiveCommentChanged.bind(this));
+    EventBus.on('live:resync', this.refresh.bind(this));
+
+    // Уведомления создаются теми же действиями, что и события панели,
+    // поэтому после события перечитывается счетчик непрочитанных
+    const refreshUnread = debounce(() => this.components.notificationsPanel.refreshUnreadCount(), 1000);
+    ['live:submission_created', 'live:status_changed', 'live:assignment_changed', 'live:comment_added', 'live:comment_updated', 'live:resync']
+      .forEach(event => EventBus.on(event, refreshUnread));
+
+    // Спам скрыт из списка, пока не включен переключатель
+    const spamToggle = document.getElementById('showSpamToggle');
+    if (spamToggle) {
+      spamToggle.addEventListener('change', () => {
+        this.includeSpam = spamToggle.checked;
+        this.loadData(CONFIG.PAGINATION.DEFAULT_PAGE);
+      });
+    }
+
+    // «Мои заявки» — только назначенные текущему администратору
+    const assignedToggle = document.getElementById('assignedToMeToggle');
+    if (assignedToggle) {
+      assignedToggle.addEventListener('change', () => {
+        this.assignedToMe = assignedToggle.checked;
+        this.loadData(CONFIG.PAGINATION.DEFAULT_PAGE);
+      });
+    }
+
     const logoutBtn = document.querySelector('.logout-item');
     if (logoutBtn) {
       logoutBtn.addEventListener('click', this._boundHandleLogout);
@@ -80,6 +122,8 @@ export class AdminDashboardController {
 
   async loadInitialData() {
     await this.loadData(CONFIG.PAGINATION.DEFAULT_PAGE);
+    this.components.notificationsPanel.refreshUnreadCount();
+    eventStream.connect();
   }
 
   async loadData(page, sort = {}) {
@@ -90,7 +134,12 @@ export class AdminDashboardController {
       const sortBy = (sort && typeof sort.sortBy !== 'undefined') ? sort.sortBy : this.currentSort.sortBy;
       const order  = (sort && typeof sort.order !== 'undefined') ? sort.order : this.currentSort.order;
 
-      const result = await apiService.fetchSubmissions(page, CONFIG.PAGINATION.PAGE_SIZE, { sortBy, order });
+      const result = await apiService.fetchSubmissions(page, CONFIG.PAGINATION.PAGE_SIZE, {
+        sortBy,
+        order,
+        includeSpam: this.includeSpam,
+        filters: { ...this.filters, assignedTo: this.assignedToMe ? 'me' : undefined }
+      });
 
       if (result) {
         submissionStore.updateState(result);
@@ -134,17 +183,40 @@ export class AdminDashboardController {
     this.components.modal.updateSubmission(submission);
   }
 
-  handleSubmissionsFiltered(submissions) {
-    this.components.table.render(submissions);
-    this.components.pagination.showSearchResult(submissions.length);
-  }
+  // Новая заявка попадает в таблицу, только если список не отфильтрован
+  // и открыта первая страница, иначе обновляется лишь счетчик
+  handleLiveSubmissionCreated({ submission }) {
+    if (!submission) return;
+    if (submission.status === 'spam' && !this.includeSpam) return;
 
-  async handlePageChange(page) {
-    if (submissionStore.getFilters().search) {
-      this.components.searchBox.clear();
-      submissionStore.resetFilters();
+    const unfiltered = !this.assignedToMe && Object.keys(this.filters).length === 0;
+    if (!unfiltered) return;
+
+    if (submissionStore.getCurrentPage() === CONFIG.PAGINATION.DEFAULT_PAGE) {
+      submissionStore.addSubmission(submission);
+    } else {
+      this.components.statsPanel.incrementTotal();
+      this.components.statsPanel.incrementToday();
     }
+  }
+
+  handleLiveStatusChanged({ submission_id, to }) {
+    submissionStore.updateSubmissionStatus(submission_id, to);
+  }
+
+  handleLiveAssignmentChanged({ submission_id, assignee_id, assignee_name }) {
+    submissionStore.updateSubmissionFields(submission_id, {
+      admin_id: assignee_id,
+      assignee_name
+    });
+  }
+
+  // Новый, измененный или удаленный комментарий: ветки открытой заявки перечитываются
+  handleLiveCommentChanged({ submission_id }) {
+    this.components.modal.refreshComments(submission_id);
+  }
 
+  async handlePageChange(page) {
     await this.loadData(page);
   }
 
@@ -184,10 +256,31 @@ export class AdminDashboardController {
     }
   }
 
-  handleSubmissionView(submissionId) {
+  async handleSubmissionView(submissionId) {
     const submission = submissionStore.findSubmissionById(submissionId);
-    if (submission) {
-      this.components.modal.open(submission);
+    // Заявка из уведомления может быть не на текущей странице списка
+    if (!submission) {
+      try {
+        const fresh = await apiService.fetchSubmission(submissionId);
+        if (fresh) this.components.modal.open(fresh);
+      } catch (error) {
+        console.error('Error loading submission:', error);
+        notificationService.error('Не удалось открыть заявку');
+      }
+      return;
+    }
+
+    this.components.modal.open(submission);
+
+    // Сервер переводит новую заявку в «Просмотрено» при первом открытии
+    try {
+      const fresh = await apiService.fetchSubmission(submissionId);
+      if (fresh && fresh.status !== submission.status) {
+        submissionStore.updateSubmissionFields(submissionId, { status: fresh.status });
+        this.components.modal.setStatus(submissionId, fresh.status);
+      }
+    } catch (error) {
+      console.error('Error loading submission:', error);
     }
   }
 
@@ -210,16 +303,28 @@ export class AdminDashboardController {
         this.components.modal.updateSubmission(submission);
       }
 
-      notificationService.error('Ошибка обновления статуса');
+      notificationService.error(error.body?.message || 'Ошибка обновления статуса');
     }
   }
 
-  handleSearch({ term, immediate, isEmpty }) {
+  handleQuoteUpdated({ submissionId, quote }) {
+    submissionStore.updateSubmissionFields(submissionId, quote);
+  }
+
+  // Поиск выполняется на сервере: email и телефон ищутся точно, остальное — полнотекстово
+  handleSearch({ term, isEmpty }) {
     if (isEmpty) {
-      this.loadData(CONFIG.PAGINATION.DEFAULT_PAGE);
+      this.filters = {};
+    } else if (term.includes('@')) {
+      this.filters = { email: term };
+    } else if (/^[+\d\s()-]{10,}$/.test(term)) {
+      this.filters = { phone: term };
     } else {
-      submissionStore.applySearchFilter(term);
+      this.filters = { q: term };
     }
+
+    submissionStore.setSearchFilter(term);
+    this.loadData(CONFIG.PAGINATION.DEFAULT_PAGE);
   }
 
   async handleClientSave(clientData) {
@@ -269,15 +374,16 @@ export class AdminDashboardController {
     }
   }
 
-  handleLogout(e) {
+  async handleLogout(e) {
     e && e.preventDefault && e.preventDefault();
 
-    // Optional: you can use a configured base path if you have one:
-    // const logoutPath = (CONFIG && CONFIG.BASE_PATH) ? `${CONFIG.BASE_PATH}/admin/logout` : '/admin/logout';
-    // window.location.assign(logoutPath);
-
-    // Simple redirect to logout endpoint
-    window.location.assign('/admin/logout');
+    try {
+      await apiService.logout();
+      window.location.assign('/admin/login');
+    } catch (err) {
+      console.error('Logout failed:', err);
+      notificationService.error('Не удалось выйти из панели');
+    }
   }
 
   async refresh() {
@@ -311,6 +417,7 @@ export class AdminDashboardController {
       }
     });
 
+    eventStream.disconnect();
     submissionStore.clear();
 
     if (this._logoutBtn && this._boundHandleLogout) {
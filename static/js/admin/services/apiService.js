@@ -2,19 +2,46 @@
 import { CONFIG } from '../config/constants.js';
 import { EventBus } from '../utils/eventBus.js';
 
+const SAFE_METHODS = ['GET', 'HEAD', 'OPTIONS'];
+const CSRF_ERROR_CODES = ['CSRF_TOKEN_MISSING', 'CSRF_TOKEN_INVALID'];
+
 class ApiService {
   constructor() {
     this.baseUrl = CONFIG.API.BASE_URL || '';
   }
 
-  async request(endpoint, options = {}) {
+  // CSRF-токен одноразовый: сервер удаляет его при проверке, поэтому
+  // каждый изменяющий запрос получает свой
+  async getCsrfToken() {
+    const response = await fetch(CONFIG.API.CSRF_TOKEN_URL, {
+      method: 'GET',
+      credentials: 'same-origin',
+      headers: { 'Accept': 'application/json' }
+    });
+    if (!response.ok) {
+      throw new Error(`CSRF token fetch failed: HTTP ${response.status}`);
+    }
+
+    const data = await response.json();
+    return data.token;
+  }
+
+  async request(endpoint, options = {}, retryOnCsrfError = true) {
     const ep = (endpoint && endpoint.startsWith('/')) ? endpoint : `/${endpoint}`;
     const url = `${this.baseUrl}${ep}`;
+    const method = (options.method || 'GET').toUpperCase();
 
     const defaultHeaders = {
       'Content-Type': 'application/json',
       'Accept': 'application/json'
     };
+    // Для FormData заголовок с границей multipart выставляет браузер
+    if (options.body instanceof FormData) {
+      delete defaultHeaders['Content-Type'];
+    }
+    if (!SAFE_METHODS.includes(method)) {
+      defaultHeaders['X-CSRF-Token'] = await this.getCsrfToken();
+    }
 
     const fetchOptions = {
       credentials: 'same-origin',
@@ -41,6 +68,10 @@ class ApiService {
         } else {
           errBody = await response.text().catch(() => null);
         }
+        // Токен истек или выдан для прежней сессии — повторяем запрос с новым
+        if (retryOnCsrfError && response.status === 403 && CSRF_ERROR_CODES.includes(errBody?.code)) {
+          return await this.request(endpoint, options, false);
+        }
         const err = new Error(`HTTP ${response.status}`);
         err.body = errBody;
         throw err;
@@ -66,7 +97,21 @@ class ApiService {
   }
 
   // Загрузка списка заявок (как было)
-  async fetchSubmissions(page, perPage, { sortBy, order } = {}) {
+  // filters: { q, email, phone, status, dateFrom, dateTo, assignedTo, priceMin, priceMax, currency }
+  // Границы цены сервер принимает только вместе с валютой
+  // Выход идет через POST, чтобы его нельзя было вызвать чужой ссылкой
+  async logout() {
+    const response = await fetch(CONFIG.API.LOGOUT_URL, {
+      method: 'POST',
+      credentials: 'same-origin',
+      headers: { 'X-CSRF-Token': await this.getCsrfToken() }
+    });
+    if (!response.ok) {
+      throw new Error(`Logout failed: HTTP ${response.status}`);
+    }
+  }
+
+  async fetchSubmissions(page, perPage, { sortBy, order, includeSpam, filters = {} } = {}) {
     try {
       const params = new URLSearchParams({
         page: String(page),
@@ -75,6 +120,23 @@ class ApiService {
 
       if (sortBy) params.append('sort_by', sortBy);
       if (order) params.append('order', order);
+      if (includeSpam) params.append('include_spam', 'true');
+
+      const filterParams = {
+        q: filters.q,
+        email: filters.email,
+        phone: filters.phone,
+        status: Array.isArray(filters.status) ? filters.status.join(',') : filters.status,
+        date_from: filters.dateFrom,
+        date_to: filters.dateTo,
+        assigned_to: filters.assignedTo,
+        price_min: filters.priceMin,
+        price_max: filters.priceMax,
+        currency: filters.currency
+      };
+      Object.entries(filterParams).forEach(([key, value]) => {
+        if (value !== undefined && value !== null && value !== '') params.append(key, String(value));
+      });
 
       return await this.request(`${CONFIG.API.ENDPOINTS.SUBMISSIONS}?${params.toString()}`, {
         method: 'POST'
@@ -85,6 +147,10 @@ class ApiService {
     }
   }
 
+  async fetchSubmission(submissionId) {
+    return await this.request(`${CONFIG.API.ENDPOINTS.SUBMISSION}/${submissionId}`, { method: 'GET' });
+  }
+
   async updateSubmissionStatus(submissionId, status) {
     try {
       await this.request(CONFIG.API.ENDPOINTS.UPDATE_STATUS, {
@@ -97,7 +163,9 @@ class ApiService {
       return true;
     } catch (error) {
       console.error('updateSubmissionStatus error', error);
-      throw new Error('Ошибка обновления статуса');
+      const err = new Error('Ошибка обновления статуса');
+      err.body = error.body;
+      throw err;
     }
   }
 
@@ -109,43 +177,178 @@ class ApiService {
       });
     } catch (error) {
       console.error('addSubmission error', error);
-      throw new Error('Ошибка при сохранении клиента');
+      const err = new Error('Ошибка при сохранении клиента');
+      err.body = error.body;
+      throw err;
     }
   }
 
 
-  async fetchAdminComments(submissionId) {
-    try {
-      if (!submissionId) throw new Error('submissionId required');
-      const payload = { submissions_id: submissionId };
-      return await this.request(CONFIG.API.ENDPOINTS.GET_COMMENTS, {
-        method: 'POST',
-        body: JSON.stringify(payload)
-      });
-    } catch (err) {
-      console.error('fetchAdminComments error', err);
-      throw err;
-    }
+  // Ветки комментариев заявки, постранично
+  async fetchAdminComments(submissionId, page = 1) {
+    if (!submissionId) throw new Error('submissionId required');
+    const params = new URLSearchParams({ page: String(page) });
+    return await this.request(`${CONFIG.API.ENDPOINTS.SUBMISSION}/${submissionId}/comments?${params}`);
   }
 
-  async addAdminComment(submissionId, commentText) {
-    try {
-      if (!submissionId) throw new Error('submissionId required');
-      if (!commentText || !commentText.trim()) throw new Error('commentText required');
-  
-      const body = {
-        submissions_id: submissionId,
-        text: commentText
-      };
-  
-      return await this.request(CONFIG.API.ENDPOINTS.ADD_COMMENTS, {
-        method: 'POST',
-        body: JSON.stringify(body),
-      });
-    } catch (err) {
-      console.error('addAdminComment error', err);
-      throw err;
-    }
+  // parentId — ответ на комментарий; упоминания @логин сервер разбирает сам
+  async addAdminComment(submissionId, commentText, parentId = null) {
+    if (!submissionId) throw new Error('submissionId required');
+    if (!commentText || !commentText.trim()) throw new Error('commentText required');
+
+    return await this.request(`${CONFIG.API.ENDPOINTS.SUBMISSION}/${submissionId}/comments`, {
+      method: 'POST',
+      body: JSON.stringify({ text: commentText, parent_id: parentId })
+    });
+  }
+
+  async updateAdminComment(commentId, text) {
+    return await this.request(`${CONFIG.API.ENDPOINTS.COMMENTS}/${commentId}`, {
+      method: 'PUT',
+      body: JSON.stringify({ text })
+    });
+  }
+
+  async deleteAdminComment(commentId) {
+    return await this.request(`${CONFIG.API.ENDPOINTS.COMMENTS}/${commentId}`, {
+      method: 'DELETE'
+    });
+  }
+
+  async fetchCommentEdits(commentId) {
+    return await this.request(`${CONFIG.API.ENDPOINTS.COMMENTS}/${commentId}/edits`);
+  }
+
+  // Оценка стоимости: цена в копейках (центах)
+  async updateSubmissionQuote(submissionId, { price, currency, note }) {
+    return await this.request(`${CONFIG.API.ENDPOINTS.SUBMISSION}/${submissionId}/quote`, {
+      method: 'PUT',
+      body: JSON.stringify({ price, currency, note })
+    });
+  }
+
+  async fetchQuoteHistory(submissionId) {
+    return await this.request(`${CONFIG.API.ENDPOINTS.SUBMISSION}/${submissionId}/quote/history`, {
+      method: 'GET'
+    });
+  }
+
+  quoteDocumentUrl(submissionId) {
+    return `${this.baseUrl}${CONFIG.API.ENDPOINTS.SUBMISSION}/${submissionId}/quote/document`;
+  }
+
+  // Вложения заявки
+  async fetchAttachments(submissionId) {
+    return await this.request(`${CONFIG.API.ENDPOINTS.SUBMISSION}/${submissionId}/attachments`, {
+      method: 'GET'
+    });
+  }
+
+  async uploadAttachments(submissionId, files) {
+    const body = new FormData();
+    Array.from(files).forEach(file => body.append('files', file, file.name));
+    return await this.request(`${CONFIG.API.ENDPOINTS.SUBMISSION}/${submissionId}/attachments`, {
+      method: 'POST',
+      body
+    });
+  }
+
+  attachmentUrl(attachmentId) {
+    return `${this.baseUrl}${CONFIG.API.ENDPOINTS.ATTACHMENTS}/${attachmentId}`;
+  }
+
+  // Управление администраторами
+  async fetchAdmins() {
+    return await this.request(CONFIG.API.ENDPOINTS.ADMINS, { method: 'GET' });
+  }
+
+  async createAdmin({ username, password, role }) {
+    return await this.request(CONFIG.API.ENDPOINTS.ADMINS, {
+      method: 'POST',
+      body: JSON.stringify({ username, password, role })
+    });
+  }
+
+  async setAdminActive(adminId, isActive) {
+    return await this.request(`${CONFIG.API.ENDPOINTS.ADMINS}/${adminId}/active`, {
+      method: 'PUT',
+      body: JSON.stringify({ is_active: isActive })
+    });
+  }
+
+  async setAdminRole(adminId, role) {
+    return await this.request(`${CONFIG.API.ENDPOINTS.ADMINS}/${adminId}/role`, {
+      method: 'PUT',
+      body: JSON.stringify({ role })
+    });
+  }
+
+  async resetAdminPassword(adminId, password) {
+    return await this.request(`${CONFIG.API.ENDPOINTS.ADMINS}/${adminId}/password`, {
+      method: 'PUT',
+      body: JSON.stringify({ password })
+    });
+  }
+
+  // Почта для уведомлений и дежурство (письма о новых заявках)
+  async setAdminMail(adminId, { email, onDuty }) {
+    return await this.request(`${CONFIG.API.ENDPOINTS.ADMINS}/${adminId}/mail`, {
+      method: 'PUT',
+      body: JSON.stringify({ email, on_duty: onDuty })
+    });
+  }
+
+  // Привязка аккаунта Telegram для кнопок бота; null — отвязать
+  async setAdminTelegram(adminId, telegramUserId) {
+    return await this.request(`${CONFIG.API.ENDPOINTS.ADMINS}/${adminId}/telegram`, {
+      method: 'PUT',
+      body: JSON.stringify({ telegram_user_id: telegramUserId })
+    });
+  }
+
+  // Срок выполнения заявки; null — снять срок
+  async setSubmissionDueDate(submissionId, dueAt) {
+    return await this.request(`${CONFIG.API.ENDPOINTS.SUBMISSION}/${submissionId}/due-date`, {
+      method: 'PUT',
+      body: JSON.stringify({ due_at: dueAt })
+    });
+  }
+
+  // Входящие текущего администратора, ответ содержит unread_count
+  async fetchNotifications(page = 1, { unreadOnly = false } = {}) {
+    const params = new URLSearchParams({ page: String(page) });
+    if (unreadOnly) params.set('unread', 'true');
+    return await this.request(`${CONFIG.API.ENDPOINTS.NOTIFICATIONS}?${params}`, { method: 'GET' });
+  }
+
+  async setNotificationRead(notificationId, read) {
+    return await this.request(`${CONFIG.API.ENDPOINTS.NOTIFICATIONS}/${notificationId}`, {
+      method: 'PUT',
+      body: JSON.stringify({ read })
+    });
+  }
+
+  async markAllNotificationsRead() {
+    return await this.request(`${CONFIG.API.ENDPOINTS.NOTIFICATIONS}/read-all`, {
+      method: 'POST'
+    });
+  }
+
+  async fetchNotificationPreferences() {
+    return await this.request(`${CONFIG.API.ENDPOINTS.NOTIFICATIONS}/preferences`, { method: 'GET' });
+  }
+
+  async updateNotificationPreferences(preferences) {
+    return await this.request(`${CONFIG.API.ENDPOINTS.NOTIFICATIONS}/preferences`, {
+      method: 'PUT',
+      body: JSON.stringify(preferences)
+    });
+  }
+
+  async deleteAdmin(adminId) {
+    return await this.request(`${CONFIG.API.ENDPOINTS.ADMINS}/${adminId}`, {
+      method: 'DELETE'
+    });
   }
 }
 
@@ -0,0 +1,80 @@
+import { EventBus } from '../utils/eventBus.js';
+import { CONFIG } from '../config/constants.js';
+
+// События сервера, которые пересылаются в EventBus как `live:<тип>`
+const EVENT_TYPES = [
+  'submission_created',
+  'status_changed',
+  'comment_added',
+  'comment_updated',
+  'comment_deleted',
+  'assignment_changed',
+  'resync'
+];
+
+const MIN_RETRY_DELAY = 3000;
+const MAX_RETRY_DELAY = 60000;
+
+class EventStreamService {
+  constructor() {
+    this.source = null;
+    this.retryDelay = MIN_RETRY_DELAY;
+    this.retryTimer = null;
+    this.interrupted = false;
+  }
+
+  connect() {
+    if (this.source || !window.EventSource) return;
+
+    const source = new EventSource(`${CONFIG.API.BASE_URL}${CONFIG.API.ENDPOINTS.EVENTS}`);
+    this.source = source;
+
+    source.onopen = () => {
+      // После разрыва события могли потеряться — перезагружаем данные
+      if (this.interrupted) EventBus.emit('live:resync', {});
+      this.interrupted = false;
+      this.retryDelay = MIN_RETRY_DELAY;
+    };
+
+    // Разрыв соединения браузер восстанавливает сам. Закрытый поток
+    // (например, после выхода из сессии) переподключаем с паузой
+    source.onerror = () => {
+      this.interrupted = true;
+      if (source.readyState !== EventSource.CLOSED) return;
+      this.source = null;
+      this.retryTimer = setTimeout(() => this.connect(), this.retryDelay);
+      this.retryDelay = Math.min(this.retryDelay * 2, MAX_RETRY_DELAY);
+    };
+
+    // Сервер периодически закрывает поток, чтобы заново проверить сессию.
+    // Переподключаемся сразу, без перезагрузки данных
+    source.addEventListener('reconnect', () => {
+      source.close();
+      this.source = null;
+      this.connect();
+    });
+
+    EVENT_TYPES.forEach(type => {
+      source.addEventListener(type, (event) => this.dispatch(type, event));
+    });
+  }
+
+  dispatch(type, event) {
+    try {
+      EventBus.emit(`live:${type}`, JSON.parse(event.data || '{}'));
+    } catch (error) {
+      console.error(`Invalid ${type} event`, error);
+    }
+  }
+
+  disconnect() {
+    clearTimeout(this.retryTimer);
+    if (this.source) {
+      this.source.close();
+      this.source = null;
+    }
+    this.interrupted = false;
+  }
+}
+
+export const eventStream = new EventStreamService();
@@ -64,7 +64,10 @@ class SubmissionStore {
   }
 
   // Методы для работы с заявками
+  // Заявка может прийти дважды: из ответа API и из потока событий
   addSubmission(submission) {
+    if (this.findSubmissionById(submission.submission_id)) return;
+
     this.state.submissions.unshift(submission);
     this.originalData.unshift(submission);
     this.emit('submission:added', submission);
@@ -87,25 +90,17 @@ class SubmissionStore {
     }
   }
 
-  findSubmissionById(submissionId) {
-    return this.state.submissions.find(s => s.submission_id === submissionId);
+  // Модальное окно обновляет себя само, поэтому перерисовывается только таблица
+  updateSubmissionFields(submissionId, fields) {
+    [this.state.submissions, this.originalData].forEach(list => {
+      const submission = list.find(s => s.submission_id === submissionId);
+      if (submission) Object.assign(submission, fields);
+    });
+    this.emit('submissions:updated', this.state.submissions);
   }
 
-  // Клиентская фильтрация
-  applySearchFilter(searchTerm) {
-    if (!searchTerm || searchTerm.trim() === '') {
-      this.state.submissions = [...this.originalData];
-    } else {
-      const filtered = this.originalData.filter(submission => 
-        submission.name.toLowerCase().includes(searchTerm.toLowerCase()) ||
-        submission.email.toLowerCase().includes(searchTerm.toLowerCase()) ||
-        submission.submission_id.toLowerCase().includes(searchTerm.toLowerCase())
-      );
-      this.state.submissions = filtered;
-    }
-    
-    this.setSearchFilter(searchTerm);
-    this.emit('submissions:filtered', this.state.submissions);
+  findSubmissionById(submissionId) {
+    return this.state.submissions.find(s => s.submission_id === submissionId);
   }
 
   // Сброс фильтров
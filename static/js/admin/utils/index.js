@@ -54,6 +54,21 @@ export class DateUtils {
   }
 }
 
+/**
+ * Утилиты для работы со стоимостью (сервер хранит копейки/центы)
+ */
+export class PriceUtils {
+  static format(minorUnits, currency = 'RUB') {
+    return new Intl.NumberFormat('ru-RU', { style: 'currency', currency })
+      .format((minorUnits || 0) / 100);
+  }
+
+  static toMinorUnits(value) {
+    const amount = parseFloat(String(value).replace(/\s/g, '').replace(',', '.'));
+    return Number.isFinite(amount) ? Math.round(amount * 100) : NaN;
+  }
+}
+
 /**
  * Утилиты для работы с пользователями
  */
@@ -2,6 +2,7 @@ class ContactFormHandler {
     constructor() {
         this.csrfToken = null;
         this.csrfTokenExpiry = null;
+        this.powSolution = null;
         this.API_BASE = '/api/v1';
         this.form = document.getElementById('contactForm');
         this.submitBtn = document.getElementById('submitBtn');
@@ -16,6 +17,47 @@ class ContactFormHandler {
         this.setupEventListeners();
         this.startTokenRefreshTimer();
         this.setupPhoneMask();
+        this.powPromise = this.solveProofOfWork();
+    }
+
+    // Антиспам: решение задачи proof-of-work в фоне, пока пользователь заполняет форму
+    async solveProofOfWork() {
+        this.powSolution = null;
+        try {
+            const response = await fetch(`${this.API_BASE}/pow-challenge`, {
+                method: 'GET',
+                credentials: 'include',
+                headers: { 'Accept': 'application/json' }
+            });
+            if (!response.ok) {
+                throw new Error(`HTTP ${response.status}`);
+            }
+
+            const { challenge, difficulty } = await response.json();
+            const encoder = new TextEncoder();
+            for (let nonce = 0; ; nonce++) {
+                const digest = await crypto.subtle.digest('SHA-256', encoder.encode(`${challenge}:${nonce}`));
+                if (this.leadingZeroBits(new Uint8Array(digest)) >= difficulty) {
+                    this.powSolution = { pow_challenge: challenge, pow_nonce: String(nonce) };
+                    return;
+                }
+            }
+        } catch (error) {
+            console.error('❌ Failed to solve proof-of-work challenge:', error);
+        }
+    }
+
+    leadingZeroBits(bytes) {
+        let bits = 0;
+        for (const byte of bytes) {
+            if (byte === 0) {
+                bits += 8;
+                continue;
+            }
+            bits += Math.clz32(byte) - 24;
+            break;
+        }
+        return bits;
     }
 
     setupPhoneMask() {
@@ -220,27 +262,47 @@ class ContactFormHandler {
             return;
         }
 
+        this.setSubmitState(true);
+        await this.powPromise;
+
         const formData = {
             name: document.getElementById('name').value.trim(),
             email: document.getElementById('email').value.trim(),
             phone: document.getElementById('phone').value.trim() || null,
-            message: document.getElementById('message').value.trim()
+            message: document.getElementById('message').value.trim(),
+            honeypot: document.getElementById('honeypot').value,
+            ...(this.powSolution || {})
         };
 
-        this.setSubmitState(true);
+        // С файлами форма уходит как multipart/form-data, заголовок с границей ставит браузер
+        const files = document.getElementById('files').files;
+        const headers = {
+            'X-CSRF-Token': this.csrfToken,
+            'Accept': 'application/json',
+        };
+        let body;
+        if (files.length > 0) {
+            body = new FormData();
+            Object.entries(formData).forEach(([key, value]) => {
+                if (value !== null && value !== undefined) body.append(key, value);
+            });
+            Array.from(files).forEach(file => body.append('files', file, file.name));
+        } else {
+            headers['Content-Type'] = 'application/json';
+            body = JSON.stringify(formData);
+        }
 
         try {
             const response = await fetch(`${this.API_BASE}/contact-submissions`, {
                 method: 'POST',
                 credentials: 'include',
-                headers: {
-                    'Content-Type': 'application/json',
-                    'X-CSRF-Token': this.csrfToken,
-                    'Accept': 'application/json',
-                },
-                body: JSON.stringify(formData)
+                headers,
+                body
             });
 
+            // Сервер удалил использованный токен, следующей отправке нужен новый
+            await this.loadCsrfToken();
+
             // Сначала получаем текстовый ответ
             const responseText = await response.text();
             
@@ -264,34 +326,27 @@ class ContactFormHandler {
                         <p>На ваш email <strong>${formData.email}</strong> будет отправлено подтверждение.</p>`
                 );
                 this.form.reset();
+                this.powPromise = this.solveProofOfWork();
                 this.resetErrors();
             } else {
-                // Обработка ошибок валидации
-                let fieldErrors = '';
-                
-                if (result.code === "VALIDATION_ERROR") {
-                    if (result.message.includes("Имя")) {
-                        this.showError('name', result.message);
-                    } else if (result.message.includes("email")) {
-                        this.showError('email', result.message);
-                    } else if (result.message.includes("Сообщение")) {
-                        this.showError('message', result.message);
-                    }
-                    fieldErrors = `<p>Проверьте правильность заполнения полей</p>`;
+                // Токен истек: новый уже получен, повторная отправка пройдет
+                if (result.code === 'CSRF_TOKEN_MISSING' || result.code === 'CSRF_TOKEN_INVALID') {
+                    result.message = 'Сессия формы устарела, отправьте сообщение еще раз';
                 }
                 
-                // Если сервер вернул детализированные ошибки
-                if (result.errors) {
-                    result.errors.forEach(error => {
-                        this.showError(error.field, error.message);
-                        fieldErrors += `<p>${error.field}: ${error.message}</p>`;
-                    });
+                // Сервер возвращает все ошибки сразу: выводим каждую под своим полем
+                if (result.code === 'VALIDATION_ERROR' && Array.isArray(result.details)) {
+                    result.details.forEach(error => this.showError(error.field, error.message));
+                    result.message = 'Проверьте правильность заполнения полей';
+                }
+                if (result.code === 'PAYLOAD_TOO_LARGE' || result.code === 'UNSUPPORTED_MEDIA_TYPE') {
+                    this.showError('files', result.message);
                 }
                 
                 this.showResponse('error', 
                     `❌ Ошибка: ${result.message || 'Произошла неизвестная ошибка'}<br>
                         <p>Код ошибки: ${result.code || 'UNKNOWN'}</p>
-                        ${fieldErrors}`
+                        ${result.request_id ? `<p>Номер обращения: ${result.request_id}</p>` : ''}`
                 );
             }
 